@@ -0,0 +1,104 @@
+//! The shape of the board and the rules of each variant.
+
+pub const SIZE: usize = 9;
+pub const BOX_SIZE: usize = 3;
+
+/// Extra placement rules layered on top of the classic row/column/box constraints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Variant {
+    #[default]
+    Classic,
+    Diagonal,
+    Windoku,
+    AntiKnight,
+}
+
+const KNIGHT_OFFSETS: [(isize, isize); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+
+impl Variant {
+    pub const ALL: [Variant; 4] = [
+        Variant::Classic,
+        Variant::Diagonal,
+        Variant::Windoku,
+        Variant::AntiKnight,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Variant::Classic => "Classic",
+            Variant::Diagonal => "X (Diagonals)",
+            Variant::Windoku => "Windoku",
+            Variant::AntiKnight => "Anti-Knight",
+        }
+    }
+
+    /// One-line description of the extra rule, used by the grid legend.
+    pub fn rule_text(&self) -> &'static str {
+        match self {
+            Variant::Classic => "Rows, columns and boxes hold 1-9 once",
+            Variant::Diagonal => "Both main diagonals also hold 1-9 once",
+            Variant::Windoku => "The four shaded windows also hold 1-9 once",
+            Variant::AntiKnight => "Cells a knight's move apart can't repeat",
+        }
+    }
+
+    /// Returns true if the variant's extra rule forbids (r1, c1) and (r2, c2) sharing a digit.
+    pub fn links(&self, (r1, c1): (usize, usize), (r2, c2): (usize, usize)) -> bool {
+        match self {
+            Variant::Classic => false,
+            Variant::Diagonal => {
+                (on_main_diagonal(r1, c1) && on_main_diagonal(r2, c2))
+                    || (on_anti_diagonal(r1, c1) && on_anti_diagonal(r2, c2))
+            }
+            Variant::Windoku => {
+                window_index(r1, c1).is_some() && window_index(r1, c1) == window_index(r2, c2)
+            }
+            Variant::AntiKnight => KNIGHT_OFFSETS.iter().any(|&(dr, dc)| {
+                r1 as isize + dr == r2 as isize && c1 as isize + dc == c2 as isize
+            }),
+        }
+    }
+
+    /// Returns true if the two distinct cells may not hold the same digit.
+    pub fn sees(&self, a: (usize, usize), b: (usize, usize)) -> bool {
+        if a == b {
+            return false;
+        }
+        shares_unit(a, b) || self.links(a, b)
+    }
+}
+
+/// Whether two cells lie in the same row, column or box.
+pub fn shares_unit(a: (usize, usize), b: (usize, usize)) -> bool {
+    a.0 == b.0
+        || a.1 == b.1
+        || (a.0 / BOX_SIZE == b.0 / BOX_SIZE && a.1 / BOX_SIZE == b.1 / BOX_SIZE)
+}
+
+pub fn on_main_diagonal(r: usize, c: usize) -> bool {
+    r == c
+}
+
+pub fn on_anti_diagonal(r: usize, c: usize) -> bool {
+    r + c == SIZE - 1
+}
+
+/// Index (0-3) of the Windoku window containing the cell, if any.
+pub fn window_index(r: usize, c: usize) -> Option<usize> {
+    let band = |i: usize| match i {
+        1..=3 => Some(0),
+        5..=7 => Some(1),
+        _ => None,
+    };
+    Some(band(r)? * 2 + band(c)?)
+}
@@ -0,0 +1,11 @@
+//! The board rules and the logical solver behind tui_sudoku, without the
+//! terminal UI. Only `core` and `alloc` are used, so the engine also builds
+//! for targets without the standard library, such as WASM workers or
+//! embedded boards. Puzzle generation stays in the game, since it needs a
+//! clock and a seeded random generator.
+#![no_std]
+
+extern crate alloc;
+
+pub mod grid;
+pub mod solver;
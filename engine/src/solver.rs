@@ -0,0 +1,522 @@
+use crate::grid::{BOX_SIZE, SIZE, Variant, on_anti_diagonal, on_main_diagonal, window_index};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+pub type Board = [[u8; SIZE]; SIZE];
+pub type Cell = (usize, usize);
+
+const ALL_CANDIDATES: u16 = 0b11_1111_1110; // Bits 1..=9
+
+/// Human-style solving techniques, ordered from easiest to hardest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    NakedSingle,
+    HiddenSingle,
+    LockedCandidates,
+    NakedPair,
+    HiddenPair,
+}
+
+impl Technique {
+    /// Every technique, easiest first.
+    pub const ALL: [Technique; 5] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::LockedCandidates,
+        Technique::NakedPair,
+        Technique::HiddenPair,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "Naked Single",
+            Technique::HiddenSingle => "Hidden Single",
+            Technique::LockedCandidates => "Locked Candidates",
+            Technique::NakedPair => "Naked Pair",
+            Technique::HiddenPair => "Hidden Pair",
+        }
+    }
+}
+
+/// A group of cells that must hold each digit exactly once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Region {
+    Row(usize),
+    Column(usize),
+    Box(usize),
+    Diagonal,
+    AntiDiagonal,
+    Window(usize),
+}
+
+impl Region {
+    pub fn cells(&self) -> Vec<Cell> {
+        let all = (0..SIZE).flat_map(|r| (0..SIZE).map(move |c| (r, c)));
+        match *self {
+            Region::Row(r) => (0..SIZE).map(|c| (r, c)).collect(),
+            Region::Column(c) => (0..SIZE).map(|r| (r, c)).collect(),
+            Region::Box(b) => {
+                let (br, bc) = ((b / BOX_SIZE) * BOX_SIZE, (b % BOX_SIZE) * BOX_SIZE);
+                (0..SIZE)
+                    .map(|i| (br + i / BOX_SIZE, bc + i % BOX_SIZE))
+                    .collect()
+            }
+            Region::Diagonal => all.filter(|&(r, c)| on_main_diagonal(r, c)).collect(),
+            Region::AntiDiagonal => all.filter(|&(r, c)| on_anti_diagonal(r, c)).collect(),
+            Region::Window(w) => all
+                .filter(|&(r, c)| window_index(r, c) == Some(w))
+                .collect(),
+        }
+    }
+
+    /// Human readable name, 1-based like the coordinates shown to the player.
+    pub fn name(&self) -> String {
+        match self {
+            Region::Row(r) => format!("row {}", r + 1),
+            Region::Column(c) => format!("column {}", c + 1),
+            Region::Box(b) => format!("box {}", b + 1),
+            Region::Diagonal => "the main diagonal".to_string(),
+            Region::AntiDiagonal => "the anti-diagonal".to_string(),
+            Region::Window(w) => format!("window {}", w + 1),
+        }
+    }
+}
+
+/// Every region of the classic rules plus those added by the variant.
+pub fn regions(variant: Variant) -> Vec<Region> {
+    let mut regions: Vec<Region> = (0..SIZE)
+        .flat_map(|i| [Region::Row(i), Region::Column(i), Region::Box(i)])
+        .collect();
+    match variant {
+        Variant::Diagonal => regions.extend([Region::Diagonal, Region::AntiDiagonal]),
+        Variant::Windoku => regions.extend((0..4).map(Region::Window)),
+        Variant::Classic | Variant::AntiKnight => {}
+    }
+    regions
+}
+
+/// Hardest technique needed to solve `grid` logically, or None if the
+/// techniques run out before the grid is full.
+pub fn grade(grid: Board, variant: Variant) -> Option<Technique> {
+    let mut solver = Solver::new(grid, variant);
+    let mut hardest = Technique::NakedSingle;
+    while solver.grid.iter().flatten().any(|&v| v == 0) {
+        let (_, technique) = solver.next_placement()?;
+        hardest = hardest.max(technique);
+    }
+    Some(hardest)
+}
+
+/// One logical deduction: either a placement or a set of candidate eliminations.
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub technique: Technique,
+    /// Region the deduction is made in
+    pub region: Region,
+    /// Region the eliminations are made in, when it differs from `region`
+    pub other: Option<Region>,
+    /// Cells whose candidates form the pattern, e.g. the two cells of a pair
+    pub pattern: Vec<Cell>,
+    /// Digits the pattern is about
+    pub digits: Vec<u8>,
+    pub placement: Option<(usize, usize, u8)>,
+    pub eliminations: Vec<(usize, usize, u8)>,
+}
+
+/// Deductions available on a board, per family of techniques.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MoveCounts {
+    /// Empty cells a naked or hidden single can fill
+    pub singles: usize,
+    pub locked_candidates: usize,
+    /// Naked and hidden pairs that remove candidates
+    pub pairs: usize,
+}
+
+/// A cell as shown to the player, e.g. `r3c5`.
+pub fn cell_name((r, c): Cell) -> String {
+    format!("r{}c{}", r + 1, c + 1)
+}
+
+/// Joins names as `a`, `a and b` or `a, b and c`.
+fn list(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+impl Step {
+    /// One sentence explaining why the deduction holds.
+    pub fn explanation(&self) -> String {
+        let region = self.region.name();
+        let cells = list(
+            &self
+                .pattern
+                .iter()
+                .map(|&cell| cell_name(cell))
+                .collect::<Vec<_>>(),
+        );
+        let digits = list(&self.digits.iter().map(u8::to_string).collect::<Vec<_>>());
+        match (self.technique, self.placement) {
+            (Technique::NakedSingle, Some((r, c, d))) => format!(
+                "{} can only be {d}: every other digit is already in a cell it sees",
+                cell_name((r, c))
+            ),
+            (Technique::HiddenSingle, Some((r, c, d))) => format!(
+                "{d} fits nowhere else in {region}, so it goes in {}",
+                cell_name((r, c))
+            ),
+            (Technique::LockedCandidates, _) => {
+                let other = self.other.map_or_else(String::new, |other| other.name());
+                format!(
+                    "In {region}, {digits} can only go in {cells}, which all lie in {other}, \
+                     so {digits} can't go anywhere else in {other}"
+                )
+            }
+            (Technique::NakedPair, _) => {
+                format!("{cells} can only hold {digits}, so no other cell of {region} can")
+            }
+            (Technique::HiddenPair, _) => format!(
+                "In {region}, {digits} only fit in {cells}, so those cells hold nothing else"
+            ),
+            (technique, _) => format!("{} in {region}", technique.name()),
+        }
+    }
+}
+
+/// Logical solver tracking candidates for every empty cell.
+#[derive(Clone, Debug)]
+pub struct Solver {
+    grid: Board,
+    candidates: [[u16; SIZE]; SIZE],
+    variant: Variant,
+    regions: Vec<Region>,
+}
+
+fn all_cells() -> impl Iterator<Item = Cell> {
+    (0..SIZE).flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+}
+
+fn bit(digit: u8) -> u16 {
+    1 << digit
+}
+
+fn digits(mask: u16) -> impl Iterator<Item = u8> {
+    (1..=9u8).filter(move |&d| mask & bit(d) != 0)
+}
+
+/// The first of the steps a technique found.
+fn first(steps: Vec<Step>) -> Option<Step> {
+    steps.into_iter().next()
+}
+
+impl Solver {
+    /// Builds a solver for `grid` (0 = empty), with candidates derived from the placed digits.
+    pub fn new(grid: Board, variant: Variant) -> Self {
+        let mut solver = Solver {
+            grid,
+            candidates: [[0; SIZE]; SIZE],
+            variant,
+            regions: regions(variant),
+        };
+        for (r, c) in all_cells() {
+            if grid[r][c] == 0 {
+                solver.candidates[r][c] = ALL_CANDIDATES;
+            }
+        }
+        for (r, c) in all_cells() {
+            if grid[r][c] != 0 {
+                solver.remove_from_peers((r, c), grid[r][c]);
+            }
+        }
+        solver
+    }
+
+    fn remove_from_peers(&mut self, cell: Cell, digit: u8) {
+        for (r, c) in all_cells() {
+            if self.variant.sees(cell, (r, c)) {
+                self.candidates[r][c] &= !bit(digit);
+            }
+        }
+    }
+
+    /// Applies a step found by `next_step`.
+    pub fn apply(&mut self, step: &Step) {
+        if let Some((r, c, digit)) = step.placement {
+            self.grid[r][c] = digit;
+            self.candidates[r][c] = 0;
+            self.remove_from_peers((r, c), digit);
+        }
+        for &(r, c, digit) in &step.eliminations {
+            self.candidates[r][c] &= !bit(digit);
+        }
+    }
+
+    /// Finds the easiest available deduction, if any. Each technique is only
+    /// tried when every easier one found nothing.
+    pub fn next_step(&self) -> Option<Step> {
+        first(self.naked_singles())
+            .or_else(|| first(self.hidden_singles()))
+            .or_else(|| self.next_elimination())
+    }
+
+    /// Applies deductions until one places a digit. Returns that placing step and
+    /// the hardest technique used along the way.
+    pub fn next_placement(&mut self) -> Option<(Step, Technique)> {
+        let mut steps = self.placement_chain()?;
+        let hardest = steps.iter().map(|step| step.technique).max()?;
+        Some((steps.pop()?, hardest))
+    }
+
+    /// Applies deductions until one places a digit, returning every step taken
+    /// with the placing one last.
+    pub fn placement_chain(&mut self) -> Option<Vec<Step>> {
+        let mut steps = Vec::new();
+        loop {
+            let step = self.next_step()?;
+            self.apply(&step);
+            let placed = step.placement.is_some();
+            steps.push(step);
+            if placed {
+                return Some(steps);
+            }
+        }
+    }
+
+    /// Digits that can be ruled out of an empty cell without placing anything:
+    /// those a placed digit already excludes, plus any that the elimination
+    /// techniques remove when applied until none is left.
+    pub fn eliminable(&self, (r, c): Cell) -> Vec<u8> {
+        let mut solver = self.clone();
+        while let Some(step) = solver.next_elimination() {
+            solver.apply(&step);
+        }
+        digits(ALL_CANDIDATES & !solver.candidates[r][c]).collect()
+    }
+
+    /// How the digit of an empty cell can be found without placing any other:
+    /// eliminations are applied, easiest first, until a single fills the cell.
+    /// Returns the digit and the hardest technique needed, or None when the
+    /// techniques run out first.
+    pub fn technique_for(&self, cell: Cell) -> Option<(u8, Technique)> {
+        let mut solver = self.clone();
+        let mut hardest = Technique::NakedSingle;
+        loop {
+            let single = solver
+                .naked_singles()
+                .into_iter()
+                .chain(solver.hidden_singles())
+                .find(|step| step.placement.is_some_and(|(r, c, _)| (r, c) == cell));
+            if let Some(step) = single {
+                let (_, _, digit) = step.placement?;
+                return Some((digit, hardest.max(step.technique)));
+            }
+            let step = solver.next_elimination()?;
+            hardest = hardest.max(step.technique);
+            solver.apply(&step);
+        }
+    }
+
+    /// Counts the deductions available right now without applying any. A cell
+    /// found by several singles, say in its row and its box, counts once.
+    pub fn move_counts(&self) -> MoveCounts {
+        let mut singles: Vec<Cell> = self
+            .naked_singles()
+            .into_iter()
+            .chain(self.hidden_singles())
+            .filter_map(|step| step.placement.map(|(r, c, _)| (r, c)))
+            .collect();
+        singles.sort_unstable();
+        singles.dedup();
+        MoveCounts {
+            singles: singles.len(),
+            locked_candidates: self.locked_candidates().len(),
+            pairs: self.naked_pairs().len() + self.hidden_pairs().len(),
+        }
+    }
+
+    fn next_elimination(&self) -> Option<Step> {
+        first(self.locked_candidates())
+            .or_else(|| first(self.naked_pairs()))
+            .or_else(|| first(self.hidden_pairs()))
+    }
+
+    fn naked_singles(&self) -> Vec<Step> {
+        let mut steps = Vec::new();
+        for (r, c) in all_cells() {
+            let mask = self.candidates[r][c];
+            if self.grid[r][c] == 0
+                && mask.count_ones() == 1
+                && let Some(digit) = digits(mask).next()
+            {
+                steps.push(Step {
+                    technique: Technique::NakedSingle,
+                    region: Region::Box((r / BOX_SIZE) * BOX_SIZE + c / BOX_SIZE),
+                    other: None,
+                    pattern: Vec::new(),
+                    digits: vec![digit],
+                    placement: Some((r, c, digit)),
+                    eliminations: Vec::new(),
+                });
+            }
+        }
+        steps
+    }
+
+    fn hidden_singles(&self) -> Vec<Step> {
+        let mut steps = Vec::new();
+        for region in &self.regions {
+            let cells = region.cells();
+            for digit in 1..=9u8 {
+                let mut spots = cells
+                    .iter()
+                    .filter(|&&(r, c)| self.candidates[r][c] & bit(digit) != 0);
+                if let (Some(&(r, c)), None) = (spots.next(), spots.next()) {
+                    steps.push(Step {
+                        technique: Technique::HiddenSingle,
+                        region: *region,
+                        other: None,
+                        pattern: Vec::new(),
+                        digits: vec![digit],
+                        placement: Some((r, c, digit)),
+                        eliminations: Vec::new(),
+                    });
+                }
+            }
+        }
+        steps
+    }
+
+    /// Pointing and claiming: a digit confined to the overlap of two regions
+    /// can be removed from the rest of the other region.
+    fn locked_candidates(&self) -> Vec<Step> {
+        let mut steps = Vec::new();
+        for region in &self.regions {
+            let cells = region.cells();
+            for digit in 1..=9u8 {
+                let spots: Vec<Cell> = cells
+                    .iter()
+                    .copied()
+                    .filter(|&(r, c)| self.candidates[r][c] & bit(digit) != 0)
+                    .collect();
+                if spots.len() < 2 {
+                    continue;
+                }
+                for other in &self.regions {
+                    if other == region {
+                        continue;
+                    }
+                    let other_cells = other.cells();
+                    if !spots.iter().all(|cell| other_cells.contains(cell)) {
+                        continue;
+                    }
+                    let eliminations: Vec<(usize, usize, u8)> = other_cells
+                        .iter()
+                        .filter(|cell| !cells.contains(cell))
+                        .filter(|&&(r, c)| self.candidates[r][c] & bit(digit) != 0)
+                        .map(|&(r, c)| (r, c, digit))
+                        .collect();
+                    if !eliminations.is_empty() {
+                        steps.push(Step {
+                            technique: Technique::LockedCandidates,
+                            region: *region,
+                            other: Some(*other),
+                            pattern: spots.clone(),
+                            digits: vec![digit],
+                            placement: None,
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        steps
+    }
+
+    fn naked_pairs(&self) -> Vec<Step> {
+        let mut steps = Vec::new();
+        for region in &self.regions {
+            let cells = region.cells();
+            let pairs: Vec<Cell> = cells
+                .iter()
+                .copied()
+                .filter(|&(r, c)| self.candidates[r][c].count_ones() == 2)
+                .collect();
+            for (i, &a) in pairs.iter().enumerate() {
+                for &b in &pairs[i + 1..] {
+                    let mask = self.candidates[a.0][a.1];
+                    if mask != self.candidates[b.0][b.1] {
+                        continue;
+                    }
+                    let eliminations: Vec<(usize, usize, u8)> = cells
+                        .iter()
+                        .filter(|&&cell| cell != a && cell != b)
+                        .flat_map(|&(r, c)| {
+                            digits(self.candidates[r][c] & mask).map(move |d| (r, c, d))
+                        })
+                        .collect();
+                    if !eliminations.is_empty() {
+                        steps.push(Step {
+                            technique: Technique::NakedPair,
+                            region: *region,
+                            other: None,
+                            pattern: vec![a, b],
+                            digits: digits(mask).collect(),
+                            placement: None,
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        steps
+    }
+
+    fn hidden_pairs(&self) -> Vec<Step> {
+        let mut steps = Vec::new();
+        for region in &self.regions {
+            let cells = region.cells();
+            let spots = |digit: u8| -> Vec<Cell> {
+                cells
+                    .iter()
+                    .copied()
+                    .filter(|&(r, c)| self.candidates[r][c] & bit(digit) != 0)
+                    .collect()
+            };
+            for d1 in 1..=9u8 {
+                let first = spots(d1);
+                if first.len() != 2 {
+                    continue;
+                }
+                for d2 in d1 + 1..=9 {
+                    if spots(d2) != first {
+                        continue;
+                    }
+                    let keep = bit(d1) | bit(d2);
+                    let eliminations: Vec<(usize, usize, u8)> = first
+                        .iter()
+                        .flat_map(|&(r, c)| {
+                            digits(self.candidates[r][c] & !keep).map(move |d| (r, c, d))
+                        })
+                        .collect();
+                    if !eliminations.is_empty() {
+                        steps.push(Step {
+                            technique: Technique::HiddenPair,
+                            region: *region,
+                            other: None,
+                            pattern: first.clone(),
+                            digits: vec![d1, d2],
+                            placement: None,
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        steps
+    }
+}
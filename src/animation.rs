@@ -0,0 +1,76 @@
+use crate::solver::Region;
+use crate::theme::Theme;
+use ratatui::style::Color;
+use std::time::{Duration, Instant};
+
+/// Time each animation frame is shown; matches the event poll interval.
+const FRAME: Duration = Duration::from_millis(100);
+
+/// A short visual effect played over the grid.
+#[derive(Clone, Debug)]
+pub enum Effect {
+    /// Color wave across the whole board after solving it
+    Celebration,
+    /// Blink of a row, column or box that was just completed
+    Flash(Region),
+}
+
+impl Effect {
+    fn duration(&self) -> Duration {
+        match self {
+            Effect::Celebration => Duration::from_millis(2000),
+            Effect::Flash(_) => Duration::from_millis(600),
+        }
+    }
+
+    /// Static replacement shown when animations are turned off.
+    pub fn message(&self) -> Option<String> {
+        match self {
+            // The solved banner already says it all
+            Effect::Celebration => None,
+            Effect::Flash(region) => Some(format!("Completed {}", region.name())),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Animation {
+    pub effect: Effect,
+    started: Instant,
+}
+
+impl Animation {
+    pub fn new(effect: Effect) -> Self {
+        Animation {
+            effect,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.started.elapsed() >= self.effect.duration()
+    }
+
+    /// Background of a cell in the current frame, or None if the effect leaves it alone.
+    pub fn cell_bg(&self, (r, c): (usize, usize), theme: &Theme) -> Option<Color> {
+        let frame = (self.started.elapsed().as_millis() / FRAME.as_millis()) as usize;
+        match &self.effect {
+            Effect::Celebration => {
+                let palette = [
+                    theme.success_fg,
+                    theme.info_fg,
+                    theme.title_fg,
+                    theme.hint_cell_bg,
+                ];
+                // Diagonal bands of color sweeping across the board
+                Some(palette[(r + c + frame) % palette.len()])
+            }
+            Effect::Flash(region)
+                if frame.is_multiple_of(2) && region.cells().contains(&(r, c)) =>
+            {
+                Some(theme.flash_bg)
+            }
+            Effect::Flash(_) => None,
+        }
+    }
+}
@@ -1,127 +1,2584 @@
-use crate::sudoku::{Difficulty, SIZE, SudokuGrid};
+use crate::animation::{Animation, Effect};
+use crate::challenge::Challenge;
+use crate::clipboard;
+use crate::command::{self, Command, Entry};
+use crate::compare::{self, Comparison};
+use crate::config::{Config, Gameplay, HintPricing, OverwriteMode, PeekMode, Validation};
+use crate::daily::{self, DailyProgress};
+use crate::export;
+use crate::history::{self, GameRecord, Outcome};
+use crate::hunt::Hunt;
+use crate::import::{self, ImportReview};
+use crate::keymap::{Action, Keymap, KeymapProblem};
+use crate::ladder::{self, LadderProgress};
+use crate::latency::Latency;
+use crate::memory::Memory;
+use crate::metrics::Metrics;
+use crate::puzzle_id::PuzzleId;
+use crate::quiz::Quiz;
+use crate::save::{Comment, Elimination, Link, Move, SavedGame};
+use crate::score::{self, HINT_TIER_PENALTIES};
+use crate::screensaver::Screensaver;
+use crate::settings::Setting;
+use crate::solver::{Board, MoveCounts, Region, Solver, Step, Technique};
+use crate::source::{self, PuzzleSource};
+use crate::stats::{self, DifficultyStats};
+use crate::storage;
+use crate::style_rule::StyleRule;
+use crate::sudoku::{BOX_SIZE, CellState, Difficulty, SIZE, SudokuGrid, Symmetry, Variant};
+use crate::theme::{Scheme, Theme};
+use crate::tournament::{GameResult, HintPolicy, ResultOutcome, TournamentRun};
+use crate::ui::{self, LayoutTier};
+use crate::undo::{Edit, UndoHistory};
+use crate::update::{self, Release, UpdateError};
+use crate::view::GridView;
+use crate::win::{WinChoice, WinSummary};
+use chrono::NaiveDate;
 use ratatui::layout::Rect; // Import Rect
+use ratatui::style::Color;
+use ratatui::widgets::Block;
+use std::io;
+use std::path::Path;
+use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
+use sudoku_widget::GridText;
+
+/// Generator reports of every game started, for attaching to bug reports
+const GENERATION_LOG: &str = "generation.log";
+
+/// How far the clock may stray from the median pace, in percent, and still count as on pace
+const PACE_TOLERANCE_PERCENT: u64 = 10;
+
+/// How long the board must stay unchanged before the technique radar recounts
+const RADAR_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How long the custom clue count must stay put before a board is queued for it
+const CUSTOM_CLUES_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Colors a digit's pencil marks step through in the note color popup,
+/// before going back to the plain note color
+pub const NOTE_PALETTE: [Color; 6] = [
+    Color::Green,
+    Color::Yellow,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Red,
+    Color::Blue,
+];
+
+/// How long a progress check keeps the wrong entries marked
+const CHECK_SHOWN: Duration = Duration::from_secs(3);
+
+/// The menu difficulty selected at launch, Medium
+const DEFAULT_DIFFICULTY_INDEX: usize = 1;
+
+/// How long a click on an empty cell must be held before the digit picker opens
+const PICKER_HOLD: Duration = Duration::from_millis(300);
+
+/// How the clock compares with the usual solve time for the difficulty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pace {
+    Ahead,
+    Even,
+    Behind,
+}
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)] // Added Debug
 pub enum AppState {
+    ResumePrompt,
     SelectingDifficulty,
+    Previewing,
     Running,
     Solved,
+    Failed,
+    History,
+    Stats,
+    Settings,
+    Daily,
+    Ladder,
+    Import,
+    Tournament,
+    Challenge,
+    Hunt,
+}
+
+/// A hint for the next deduction, revealed one tier at a time.
+pub struct Hint {
+    /// 1 = region, 2 = technique, 3 = exact placement
+    pub tier: usize,
+    /// The step that places a digit
+    pub step: Step,
+    /// Eliminations the solver needed first, in order
+    pub lead_up: Vec<Step>,
+    /// Hardest technique needed to reach the placement
+    pub technique: Technique,
+    /// Whether the explanation of every step is open, once fully revealed
+    pub explained: bool,
+    /// What each tier costs in percent of the flat price
+    pub price_percent: u32,
+}
+
+/// Clickable buttons mirroring key commands, for players who don't remember the keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoftKey {
+    Hint,
+    Check,
+    Notes,
+    Pause,
+    Menu,
+}
+
+impl SoftKey {
+    pub const ALL: [SoftKey; 5] = [
+        SoftKey::Hint,
+        SoftKey::Check,
+        SoftKey::Notes,
+        SoftKey::Pause,
+        SoftKey::Menu,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SoftKey::Hint => "Hint",
+            SoftKey::Check => "Check",
+            SoftKey::Notes => "Notes",
+            SoftKey::Pause => "Pause",
+            SoftKey::Menu => "Menu",
+        }
+    }
+
+    /// The action doing the same thing, whose key is taught after each click.
+    pub fn action(&self) -> Action {
+        match self {
+            SoftKey::Hint => Action::Hint,
+            SoftKey::Check => Action::Diff,
+            SoftKey::Notes => Action::Notes,
+            SoftKey::Pause => Action::Pause,
+            SoftKey::Menu => Action::Menu,
+        }
+    }
+}
+
+/// An action held back until the player answers yes or no in a popup, for
+/// steps that would lose the game in progress.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Confirm {
+    /// Quitting a game that can't be saved
+    Quit,
+    /// Going to the menu from a game with progress, which abandons it;
+    /// `save` offers to keep it for the next launch instead, and `replaces`
+    /// tells that doing so overwrites a game saved earlier
+    LeaveGame { save: bool, replaces: bool },
+    /// Giving up the game, which counts as a loss
+    GiveUp,
+}
+
+impl Confirm {
+    pub fn question(&self) -> &'static str {
+        match self {
+            Confirm::Quit => "Quit without saving?",
+            Confirm::LeaveGame { .. } => "Abandon this game for the menu?",
+            Confirm::GiveUp => "Give up this game?",
+        }
+    }
+
+    /// What happens to the game on a yes.
+    pub fn detail(&self) -> &'static str {
+        match self {
+            Confirm::Quit => "The game in progress is not kept.",
+            Confirm::LeaveGame {
+                save: true,
+                replaces: false,
+            } => "Save it first to resume it next launch.",
+            Confirm::LeaveGame {
+                save: true,
+                replaces: true,
+            } => "Saving it replaces the game saved earlier.",
+            Confirm::LeaveGame { save: false, .. } => "The game in progress can't be resumed.",
+            Confirm::GiveUp => "It counts as lost, and the solution is shown.",
+        }
+    }
+
+    /// Whether the game can be saved instead, with `s`.
+    pub fn offers_save(&self) -> bool {
+        matches!(self, Confirm::LeaveGame { save: true, .. })
+    }
+
+    /// What answering yes does.
+    pub fn accept(&self) -> &'static str {
+        match self {
+            Confirm::Quit => "Yes",
+            Confirm::LeaveGame { .. } => "Abandon",
+            Confirm::GiveUp => "Give up",
+        }
+    }
+
+    /// What answering no keeps doing.
+    pub fn decline(&self) -> &'static str {
+        match self {
+            Confirm::Quit | Confirm::LeaveGame { .. } | Confirm::GiveUp => "Keep playing",
+        }
+    }
+}
+
+/// A 3×3 pad of digits opened by holding a click on an empty cell; letting
+/// go over a digit enters it there.
+#[derive(Clone, Copy, Debug)]
+pub struct DigitPicker {
+    /// Board cell the click landed on
+    pub cell: (usize, usize),
+    /// Screen position of the click, which the pad is centred on
+    anchor: (u16, u16),
+    pressed_at: Instant,
+    /// Opened once the hold lasts long enough or the pointer is dragged
+    pub open: bool,
+    /// Digit under the pointer while dragging
+    pub hover: Option<u8>,
+    /// Whether the pointer has left the click's position, since the pad
+    /// opens with 5 under it
+    moved: bool,
+    /// Where the pad was last drawn
+    pub rect: Option<Rect>,
+}
+
+impl DigitPicker {
+    /// Three columns of three-wide digits inside a border.
+    const WIDTH: u16 = 11;
+    const HEIGHT: u16 = 5;
+
+    /// Centres the pad on the click, moved inside `area` where it would
+    /// stick out at an edge.
+    pub fn place(&mut self, area: Rect) {
+        let (x, y) = self.anchor;
+        let width = Self::WIDTH.min(area.width);
+        let height = Self::HEIGHT.min(area.height);
+        let left = x
+            .saturating_sub(width / 2)
+            .clamp(area.x, area.right() - width);
+        let top = y
+            .saturating_sub(height / 2)
+            .clamp(area.y, area.bottom() - height);
+        self.rect = Some(Rect::new(left, top, width, height));
+    }
+
+    /// The digit drawn at a screen position, laid out 1-3 on the top row.
+    pub fn digit_at(&self, screen_col: u16, screen_row: u16) -> Option<u8> {
+        let inner = self.rect?.inner(ratatui::layout::Margin::new(1, 1));
+        if !inner.contains(ratatui::layout::Position::new(screen_col, screen_row)) {
+            return None;
+        }
+        let (col, row) = ((screen_col - inner.x) / 3, screen_row - inner.y);
+        (col < 3 && row < 3).then(|| (row * 3 + col + 1) as u8)
+    }
 }
 
 pub struct App {
     // Game state (relevant when Running or Solved)
     pub sudoku: Option<SudokuGrid>,
-    pub cursor_pos: (usize, usize), // Reset when game starts
-    pub show_solution: bool,        // Reset when game starts
+    pub cursor_pos: (usize, usize),  // Reset when game starts
+    pub show_solution: bool,         // Reset when game starts
+    pub peek_until: Option<Instant>, // End of the current guarded peek
+    pub checked: Option<(Instant, Vec<(usize, usize)>)>, // Wrong entries a progress check marks, until then
+    pub peeks_used: u32,
+    pub wrong_submissions: u32, // Boards submitted unsolved in manual-submit mode
+    pub mistakes: u32,          // Digits entered against the solution
+    assisting: bool,            // An assist is filling cells, which count as no mistakes
+    pub mistake_limit: Option<u32>, // Mistakes that end the game, fixed when it starts
+    pub failure: Option<Outcome>, // How the failed game was lost
+    pub validation: Validation, // Picked on the preview screen for the next game
+    pub symmetry: Symmetry,     // Clue pattern of generated puzzles, picked on the preview screen
+    pub game_validation: Validation, // What the game in progress checks entries against
+    pub limited_mistakes: bool, // Menu option giving new games a mistake limit
+    pub memory_mode: bool,      // Menu option hiding the entries of new games after a moment
+    pub zen_mode: bool,         // Menu option making new games zen games
+    pub time_attack_mode: bool, // Menu option giving new games a countdown
+    pub memory: Option<Memory>, // Fading entries and recalls, in a memory-mode game
+    pub show_diff: bool,        // Overlay marking wrong entries and remaining empty cells
+    pub show_legend: bool,      // Color key below the grid, kept between games
+    pub show_help: bool,        // Popup listing the keys and the rules
+    pub show_focus: bool,       // Enlarged view of the selected cell, kept between games
+    pub show_debug: bool,       // Generator report for the board on screen, kept between games
+    pub show_radar: bool,       // Counts of the moves available per technique, kept between games
+    pub show_candidates: bool,  // Every legal digit in each empty cell, kept between games
+    pub show_digits: bool,      // Row counting the digits left to place, kept between games
+    pub live_check: bool,       // Mark entries clashing with a peer, kept between games
+    pub radar: Option<(Board, MoveCounts)>, // Last counts, with the board they were worked out for
+    radar_changed: Option<(Board, Instant)>, // Board awaiting a recount, and when it was first seen
+    pub note_grid: bool, // Three lines per cell so pencil marks show in full, kept between games
+    pub view: GridView,  // Turn and mirror of the board on screen, kept between games
+    pub paused_at: Option<Instant>, // Set while the clock is stopped and the board hidden
+    pub popup_since: Option<Instant>, // Set while an open popup stops the clock
     start_time: Option<Instant>,
     pub elapsed_time: Option<Duration>,
     grid_screen_rect: Option<Rect>,
+    pub layout: LayoutTier, // What the game screen has room for, settled on resize and each frame
+    pub latency: Latency,   // Input-to-render times, shown in the debug overlay
+    pub grid: GridText,     // The grid's text as last drawn, rebuilt cell by cell
+    pub soft_keys: bool,    // Button row below the game, from the config
+    pub style_rules: Vec<StyleRule>, // Conditional cell colors from the config
+    pub peer_highlight: bool, // Shade the cursor's row, column and box, from the config
+    pub blur_behind_popups: bool, // Hide the board while a popup stops the clock, from the config
+    pub colorblind: bool,   // Mark cells with modifiers and symbols as well as colors
+    pub show_timer: bool,   // Show the game clock while playing, from the config
+    pub settings_index: usize, // Option selected on the settings screen
+    soft_key_rects: Vec<(Rect, SoftKey)>,
+    pub picker: Option<DigitPicker>, // Set from a press on an empty cell until the release
     // Overall App State
     pub state: AppState,
     pub last_input_valid: bool, // Reset when game starts
+    pub status_message: Option<String>,
+    // Notes the player attached to the current game
+    pub comments: Vec<Comment>,
+    pub comment_input: Option<String>, // Some while the comment popup is open
+    pub seed_input: Option<String>,    // Some while the menu's seed prompt is open
+    pub command_input: Option<String>, // Some while the `:` entry line is open
+    pub quit_prompt: bool,             // Asking whether to save or abandon before quitting
+    pub save_replaces_other: bool,     // Saving from the open prompt overwrites another game's save
+    pub confirm: Option<Confirm>,      // Action awaiting a yes or no before it runs
+    pub moves: Vec<Move>,              // Timestamped entries and clears, oldest first
+    undo: UndoHistory,                 // This session's changes, grouped into undo steps
+    pub note_mode: bool,               // Digit keys toggle pencil marks instead of entering
+    pub note_colors: [Option<Color>; SIZE], // Color of each digit's pencil marks, kept between games
+    pub note_color_popup: bool,             // Open while digits are being given note colors
+    pub links: Vec<Link>,                   // Chain links drawn on the board, saved with the game
+    pub link_from: Option<(usize, usize)>,  // Cell a link being drawn starts at
+    pub comparison: Option<Comparison>,     // Another save of the puzzle laid over the board
+    pub eliminations: Vec<Elimination>,     // Removed pencil marks, when logging is on
+    pub hint: Option<Hint>,                 // Cleared whenever the board changes
+    pub penalty: u32,                       // Score points lost to hints
+    pub hints_used: u32,                    // Hints asked for this game
+    last_hint_secs: Option<u64>,            // Game clock at the last new hint, for the cooldown
+    pub daily_date: Option<NaiveDate>,      // Set when playing a daily puzzle
+    pub ladder_rung: Option<usize>,         // Set when playing a rung of the practice ladder
+    pub zen: bool, // Relaxed game: no clock, score, pace, validation feedback or mistakes
+    pub countdown: Option<u64>, // Game-clock seconds a time-attack game must be solved in
+    pub median_secs: Option<u64>, // Median solve time of the game's difficulty, for the pace
+    pub animation: Option<Animation>,
+    reduced_motion: bool, // Show static messages instead of animations
+    // Demo of the solver shown over an idle menu
+    pub screensaver: Option<Screensaver>,
+    screensaver_coming: Option<JoinHandle<Screensaver>>, // Being made in the background, shown once done
+    screensaver_after: Option<Duration>,
+    last_input: Instant, // Key press or click that last reset the idle time
+    custom_clues_changed: Option<Instant>, // When the custom clue count last moved, until its board is queued
+    pub gameplay: Gameplay,
+    // Autosaved game found at startup, offered for resuming
+    pub saved_game: Option<SavedGame>,
+    resumed_from_save: bool, // Whether the autosave belongs to the current game
     // Difficulty Selection State
     pub selected_difficulty_index: usize,
-    pub difficulties: [Difficulty; 3], // Make accessible for UI
+    pub difficulties: [Difficulty; 6], // The named ones, minimal, then the custom clue count
+    pub selected_variant_index: usize,
+    pub theme: Theme,
+    pub scheme: Scheme, // Built-in colors under the theme, switched in the settings
+    color_overrides: Vec<(String, Color)>, // `[colors]` from the config, kept over every scheme
+    // Game screen keys, and what was wrong with the configured ones
+    pub keymap: Keymap,
+    pub keymap_problems: Vec<KeymapProblem>, // Reported at startup until dismissed
+    // Pre-game preview of the board that will be played
+    pub preview: Option<SudokuGrid>,
+    // Where new games come from; built-in sources first, then configured ones
+    pub sources: Vec<Box<dyn PuzzleSource>>,
+    pub selected_source_index: usize,
+    // History browser state
+    pub history: Vec<GameRecord>,    // Newest first
+    pub stats: Vec<DifficultyStats>, // Per difficulty, shown on the stats screen
+    pub history_index: usize,
+    // Daily puzzle screen state
+    pub daily_progress: DailyProgress,
+    pub ladder_progress: LadderProgress,
+    pub selected_rung: usize,
+    rung_coming: Option<(usize, JoinHandle<SudokuGrid>)>, // Selected rung's puzzle, made in the background
+    rung_asked: bool, // Whether the rung being made starts as soon as it is done
+    // Puzzle import: pasted text, then the parsed board under review
+    pub import_text: String,
+    pub import_review: Option<ImportReview>,
+    // Tournament being played, if the app was started with a bundle
+    pub tournament: Option<TournamentRun>,
+    // Cross-variant challenge being played in this session
+    pub challenge: Option<Challenge>,
+    // "Find the mistake" round being played, from the menu or a game
+    pub hunt: Option<Hunt>,
+    hunt_coming: Option<JoinHandle<Hunt>>, // Round being made for the menu, opened once done
+    resume_after_hunt: bool,               // Whether the game paused for the hunt picks up after it
+    // Win screen of the game just solved, until it is closed to look at the board
+    pub win: Option<WinSummary>,
+    // Abandoned game being finished by the solver; true once it has completed the board
+    pub archiving: Option<JoinHandle<io::Result<bool>>>,
+    // Background look for a newer release, reported on the menu when done
+    update_check: Option<JoinHandle<Result<Option<Release>, UpdateError>>>,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(config: Config) -> Self {
+        let [easy, medium, hard, expert] = Difficulty::ALL;
+        let difficulties = [
+            easy,
+            medium,
+            hard,
+            expert,
+            Difficulty::Minimal,
+            Difficulty::Custom(config.gameplay.custom_clues),
+        ];
+        let saved_game = SavedGame::load();
+        let (keymap, keymap_problems) = Keymap::build(&config.keys);
+        let state = if saved_game.is_some() {
+            AppState::ResumePrompt
+        } else {
+            AppState::SelectingDifficulty
+        };
         App {
             sudoku: None,
             cursor_pos: (0, 0),
             show_solution: false,
+            peek_until: None,
+            checked: None,
+            peeks_used: 0,
+            wrong_submissions: 0,
+            mistakes: 0,
+            assisting: false,
+            mistake_limit: None,
+            failure: None,
+            limited_mistakes: config.gameplay.limited_mistakes,
+            memory_mode: config.gameplay.memory_mode,
+            zen_mode: config.gameplay.zen_mode,
+            time_attack_mode: config.gameplay.time_attack,
+            memory: None,
+            validation: config.gameplay.validation,
+            symmetry: config.gameplay.symmetry,
+            game_validation: config.gameplay.validation,
+            show_diff: false,
+            show_legend: false,
+            show_help: false,
+            show_focus: config.focus_panel,
+            show_debug: false,
+            show_radar: config.radar_panel,
+            show_digits: config.digit_panel,
+            show_candidates: config.auto_candidates,
+            live_check: config.gameplay.live_check,
+            radar: None,
+            radar_changed: None,
+            note_grid: config.note_grid,
+            view: config.view,
+            paused_at: None,
+            soft_keys: config.soft_keys,
+            peer_highlight: config.peer_highlight,
+            blur_behind_popups: config.blur_behind_popups,
+            colorblind: config.colorblind,
+            show_timer: config.show_timer,
+            settings_index: 0,
+            style_rules: config.style_rules,
+            soft_key_rects: Vec::new(),
+            picker: None,
             start_time: None,
+            popup_since: None,
             elapsed_time: None,
             grid_screen_rect: None,
-            state: AppState::SelectingDifficulty,
+            layout: LayoutTier::Full,
+            grid: GridText::default(),
+            latency: Latency::default(),
+            state,
             last_input_valid: true,
-            selected_difficulty_index: 1, // Default to Medium
-            difficulties: [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard],
+            status_message: None,
+            comments: Vec::new(),
+            comment_input: None,
+            seed_input: None,
+            command_input: None,
+            note_colors: [None; SIZE],
+            note_color_popup: false,
+            links: Vec::new(),
+            link_from: None,
+            comparison: None,
+            quit_prompt: false,
+            save_replaces_other: false,
+            confirm: None,
+            moves: Vec::new(),
+            undo: UndoHistory::new(config.gameplay.undo_steps),
+            note_mode: false,
+            eliminations: Vec::new(),
+            hint: None,
+            penalty: 0,
+            hints_used: 0,
+            last_hint_secs: None,
+            daily_date: None,
+            ladder_rung: None,
+            zen: false,
+            countdown: None,
+            median_secs: None,
+            animation: None,
+            reduced_motion: config.reduced_motion,
+            screensaver: None,
+            screensaver_coming: None,
+            screensaver_after: config.screensaver_after,
+            last_input: Instant::now(),
+            custom_clues_changed: None,
+            gameplay: config.gameplay,
+            saved_game,
+            resumed_from_save: false,
+            selected_difficulty_index: DEFAULT_DIFFICULTY_INDEX,
+            difficulties,
+            selected_variant_index: 0, // Default to Classic
+            theme: config.theme,
+            scheme: config.scheme,
+            color_overrides: config.colors,
+            keymap,
+            keymap_problems,
+            preview: None,
+            sources: source::all_sources(
+                &config.sources,
+                &difficulties,
+                difficulties[DEFAULT_DIFFICULTY_INDEX],
+                config.gameplay.symmetry,
+            ),
+            selected_source_index: 0,
+            history: Vec::new(),
+            stats: Vec::new(),
+            history_index: 0,
+            daily_progress: DailyProgress::default(),
+            ladder_progress: LadderProgress::default(),
+            selected_rung: 0,
+            rung_coming: None,
+            rung_asked: false,
+            import_text: String::new(),
+            import_review: None,
+            tournament: None,
+            challenge: None,
+            hunt: None,
+            hunt_coming: None,
+            resume_after_hunt: false,
+            win: None,
+            archiving: None,
+            update_check: None,
+        }
+    }
+
+    /// Collects puzzles the sources prepared in the background, and queues a
+    /// board for a custom clue count once it has settled.
+    pub fn poll_sources(&mut self) {
+        if self
+            .custom_clues_changed
+            .is_some_and(|since| since.elapsed() >= CUSTOM_CLUES_DEBOUNCE)
+        {
+            self.custom_clues_changed = None;
+            self.prepare_source();
+        }
+        for source in &mut self.sources {
+            source.poll();
+        }
+    }
+
+    /// Starts looking for a newer release unless a look is already under way.
+    pub fn check_for_update(&mut self) {
+        if self.update_check.is_none() {
+            self.update_check = Some(update::check_in_background());
+            self.status_message = Some("Checking for updates...".to_string());
+        }
+    }
+
+    /// Reports the update check once it has finished. Never blocks.
+    pub fn poll_update_check(&mut self) {
+        if !self
+            .update_check
+            .as_ref()
+            .is_some_and(JoinHandle::is_finished)
+        {
+            return;
+        }
+        let Some(check) = self.update_check.take() else {
+            return;
+        };
+        self.status_message = Some(match check.join() {
+            Ok(Ok(Some(release))) => format!(
+                "Version {} is available (you have {}): {}",
+                release.version,
+                env!("CARGO_PKG_VERSION"),
+                release.url
+            ),
+            Ok(Ok(None)) => format!("Version {} is the latest", env!("CARGO_PKG_VERSION")),
+            Ok(Err(e)) => format!("Could not check for updates: {e}"),
+            Err(_) => "The update check stopped unexpectedly".to_string(),
+        });
+    }
+
+    pub fn selected_difficulty(&self) -> Difficulty {
+        self.difficulties[self.selected_difficulty_index]
+    }
+
+    pub fn selected_variant(&self) -> Variant {
+        Variant::ALL[self.selected_variant_index]
+    }
+
+    /// Starts a game right away with the given settings, skipping the menu and
+    /// any resume prompt. Missing settings keep the menu's defaults, and a
+    /// seed picks the exact board. An autosaved game is left for next time.
+    pub fn quick_play(
+        &mut self,
+        difficulty: Option<Difficulty>,
+        variant: Option<Variant>,
+        seed: Option<u64>,
+    ) {
+        if let Some(difficulty) = difficulty {
+            self.select_difficulty(difficulty);
+        }
+        if let Some(index) = variant.and_then(|v| Variant::ALL.iter().position(|&x| x == v)) {
+            self.selected_variant_index = index;
+        }
+        self.saved_game = None;
+        self.state = AppState::SelectingDifficulty;
+        self.preview = seed.map(|seed| {
+            SudokuGrid::from_seed(self.selected_difficulty(), self.selected_variant(), seed)
+        });
+        self.start_game();
+    }
+
+    /// Opens the prompt for a seed to play from the menu.
+    pub fn open_seed_input(&mut self) {
+        self.seed_input = Some(String::new());
+    }
+
+    pub fn push_seed_digit(&mut self, ch: char) {
+        if let Some(input) = self.seed_input.as_mut()
+            && ch.is_ascii_digit()
+        {
+            input.push(ch);
+        }
+    }
+
+    pub fn pop_seed_digit(&mut self) {
+        if let Some(input) = self.seed_input.as_mut() {
+            input.pop();
+        }
+    }
+
+    pub fn cancel_seed(&mut self) {
+        self.seed_input = None;
+    }
+
+    /// Previews the board the typed seed makes with the selected difficulty
+    /// and variant, the same board anyone else gets from that seed.
+    pub fn submit_seed(&mut self) {
+        let Some(input) = self.seed_input.take() else {
+            return;
+        };
+        match input.parse::<u64>() {
+            Ok(seed) => {
+                self.preview = Some(SudokuGrid::symmetric(
+                    self.selected_difficulty(),
+                    self.selected_variant(),
+                    self.symmetry,
+                    seed,
+                ));
+                self.state = AppState::Previewing;
+            }
+            Err(_) => self.status_message = Some(format!("Not a seed: '{input}'")),
+        }
+    }
+
+    /// Shows the clue layout of a freshly generated board before starting.
+    pub fn open_preview(&mut self) {
+        if let Some(sudoku) = self.fetch_puzzle() {
+            self.preview = Some(sudoku);
+            self.state = AppState::Previewing;
+        }
+    }
+
+    /// Swaps the previewed board for another one from the same source and settings.
+    pub fn reroll_preview(&mut self) {
+        if self.state == AppState::Previewing
+            && let Some(sudoku) = self.fetch_puzzle()
+        {
+            self.preview = Some(sudoku);
+        }
+    }
+
+    /// Switches to the next clue symmetry and previews a board with it.
+    pub fn cycle_symmetry(&mut self) {
+        self.symmetry = self.symmetry.next();
+        self.reroll_preview();
+    }
+
+    /// Starts a new game with the previewed board, or a fresh one with the selected settings.
+    pub fn start_game(&mut self) {
+        let Some(sudoku) = self.preview.take().or_else(|| self.fetch_puzzle()) else {
+            return;
+        };
+        self.begin_game(sudoku, Duration::ZERO);
+        self.resumed_from_save = false;
+    }
+
+    /// Asks the selected source for a puzzle, reporting a failure in the status line.
+    fn fetch_puzzle(&mut self) -> Option<SudokuGrid> {
+        let (difficulty, variant) = (self.selected_difficulty(), self.selected_variant());
+        match self.sources[self.selected_source_index].fetch(difficulty, variant, self.symmetry) {
+            Ok(sudoku) => Some(sudoku),
+            Err(e) => {
+                self.status_message = Some(e.to_string());
+                None
+            }
+        }
+    }
+
+    pub fn selected_source(&self) -> &dyn PuzzleSource {
+        self.sources[self.selected_source_index].as_ref()
+    }
+
+    /// Cycles through the puzzle sources listed in the menu.
+    pub fn cycle_source(&mut self, delta: isize) {
+        let num_options = self.sources.len() as isize;
+        let new_index = (self.selected_source_index as isize + delta).rem_euclid(num_options);
+        self.selected_source_index = new_index as usize;
+        self.status_message = None;
+        self.prepare_source();
+    }
+
+    /// Lets the selected source get ahead on the menu's current settings.
+    fn prepare_source(&mut self) {
+        let (difficulty, variant) = (self.selected_difficulty(), self.selected_variant());
+        self.sources[self.selected_source_index].prepare(difficulty, variant, self.symmetry);
+    }
+
+    /// Continues the autosaved game offered by the resume prompt.
+    pub fn resume_saved_game(&mut self) {
+        if let Some(saved) = self.saved_game.take() {
+            self.begin_game(saved.sudoku, Duration::from_secs(saved.elapsed_secs));
+            self.comments = saved.comments;
+            self.moves = saved.moves;
+            self.eliminations = saved.eliminations;
+            self.penalty = saved.penalty;
+            self.hints_used = saved.hints_used;
+            self.daily_date = saved.daily;
+            self.ladder_rung = saved.ladder_rung;
+            self.peeks_used = saved.peeks_used;
+            self.wrong_submissions = saved.wrong_submissions;
+            self.mistakes = saved.mistakes;
+            self.mistake_limit = saved.mistake_limit;
+            self.links = saved.links;
+            self.game_validation = saved.validation;
+            self.zen = saved.zen;
+            self.countdown = saved.countdown;
+            self.memory = saved
+                .memory_recalls
+                .zip(self.sudoku.as_ref())
+                .map(|(recalls, sudoku)| Memory::new(sudoku, self.memory_fade(), recalls));
+            self.resumed_from_save = true;
+        }
+    }
+
+    /// Deletes the autosaved game and goes to the menu.
+    pub fn discard_saved_game(&mut self) {
+        self.saved_game = None;
+        if let Err(e) = SavedGame::discard() {
+            self.status_message = Some(format!("Could not delete saved game: {e}"));
+        }
+        self.state = AppState::SelectingDifficulty;
+    }
+
+    /// Leaves the autosave on disk to be offered again next launch.
+    pub fn keep_saved_game(&mut self) {
+        self.saved_game = None;
+        self.state = AppState::SelectingDifficulty;
+    }
+
+    fn begin_game(&mut self, mut sudoku: SudokuGrid, elapsed: Duration) {
+        sudoku.recheck_all();
+        let log_error = sudoku.generation_report().and_then(|report| {
+            let line = format!(
+                "{} {} {} version={}",
+                chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+                PuzzleId::of(&sudoku),
+                report.log_fields(),
+                env!("CARGO_PKG_VERSION")
+            );
+            storage::append_line(GENERATION_LOG, &line).err()
+        });
+        self.median_secs = history::median_solve_secs(&history::load(), sudoku.difficulty());
+        // Like the mistake limit, memory mode stays out of tournaments
+        self.memory = (self.memory_mode && self.tournament.is_none())
+            .then(|| Memory::new(&sudoku, self.memory_fade(), self.gameplay.memory_recalls));
+        self.sudoku = Some(sudoku);
+        self.state = AppState::Running;
+        self.cursor_pos = (0, 0);
+        self.show_solution = false;
+        self.peek_until = None;
+        self.checked = None;
+        self.peeks_used = 0;
+        self.wrong_submissions = 0;
+        self.mistakes = 0;
+        self.failure = None;
+        self.win = None;
+        // Tournament rules are the same for everyone, so the menu options stay out
+        self.zen = self.zen_mode && self.tournament.is_none();
+        // Zen games count no mistakes, so they have no limit either
+        self.mistake_limit = (self.limited_mistakes && self.tournament.is_none() && !self.zen)
+            .then_some(self.gameplay.mistake_limit);
+        // A zen game has no clock to count down
+        let timed = self.time_attack_mode && self.tournament.is_none() && !self.zen;
+        self.countdown = self
+            .sudoku
+            .as_ref()
+            .filter(|_| timed)
+            .map(|sudoku| sudoku.difficulty().time_attack_secs());
+        // Right-or-wrong feedback would be an assist in a tournament
+        self.game_validation = match self.tournament {
+            Some(_) => Validation::Conflicts,
+            None => self.validation,
+        };
+        self.show_diff = false;
+        self.last_input_valid = true;
+        self.status_message = log_error.map(|e| format!("Could not write the generation log: {e}"));
+        self.paused_at = None;
+        self.popup_since = None;
+        self.comments.clear();
+        self.links.clear();
+        self.link_from = None;
+        self.comparison = None;
+        self.comment_input = None;
+        self.moves.clear();
+        self.undo.clear();
+        self.note_mode = false;
+        self.eliminations.clear();
+        self.hint = None;
+        self.penalty = 0;
+        self.hints_used = 0;
+        self.last_hint_secs = None;
+        self.daily_date = None;
+        self.ladder_rung = None;
+        self.animation = None;
+        // Backdate the start so the clock continues from the elapsed time
+        let now = Instant::now();
+        self.start_time = Some(now.checked_sub(elapsed).unwrap_or(now));
+        self.elapsed_time = Some(elapsed);
+        self.grid_screen_rect = None; // Will be set by UI draw
+    }
+
+    /// Plays an effect, or shows its static message in reduced-motion mode.
+    /// Every animation starts here so that the setting is always honoured.
+    fn animate(&mut self, effect: Effect) {
+        if self.reduced_motion {
+            if let Some(message) = effect.message() {
+                self.status_message = Some(message);
+            }
+        } else {
+            self.animation = Some(Animation::new(effect));
+        }
+    }
+
+    /// Drops the current animation once it has finished playing.
+    pub fn update_animation(&mut self) {
+        if self.animation.as_ref().is_some_and(Animation::is_finished) {
+            self.animation = None;
+        }
+    }
+
+    /// Starts the screensaver once the menu has been left alone long enough.
+    /// Like the other animations it stays off in reduced-motion mode.
+    pub fn update_screensaver(&mut self) {
+        if let Some(screensaver) = self.screensaver.as_mut() {
+            screensaver.update();
+        } else if self.screensaver_coming.is_some() {
+            if self
+                .screensaver_coming
+                .as_ref()
+                .is_some_and(JoinHandle::is_finished)
+            {
+                self.screensaver = self
+                    .screensaver_coming
+                    .take()
+                    .and_then(|coming| coming.join().ok())
+                    .map(Screensaver::started);
+            }
+        } else if self.state == AppState::SelectingDifficulty
+            && self.keymap_problems.is_empty()
+            && !self.reduced_motion
+            && self
+                .screensaver_after
+                .is_some_and(|after| self.last_input.elapsed() >= after)
+        {
+            self.screensaver_coming = Some(Screensaver::in_background());
+        }
+    }
+
+    /// Restarts the idle time on any input. Returns true if the input only
+    /// dismissed the screensaver and should go no further.
+    pub fn wake(&mut self) -> bool {
+        self.last_input = Instant::now();
+        self.screensaver_coming = None;
+        self.screensaver.take().is_some()
+    }
+
+    /// Selects the difficulty in the menu, a custom one with its clue count.
+    fn select_difficulty(&mut self, difficulty: Difficulty) {
+        let index = match difficulty {
+            Difficulty::Custom(_) => self.difficulties.len() - 1,
+            named => self
+                .difficulties
+                .iter()
+                .position(|&d| d == named)
+                .unwrap_or(DEFAULT_DIFFICULTY_INDEX),
+        };
+        self.difficulties[index] = difficulty;
+        self.selected_difficulty_index = index;
+    }
+
+    /// Changes the clue count of the custom difficulty, when it is selected.
+    /// The background worker makes a board for the new count once it stays
+    /// put for a moment, so stepping through several doesn't queue each.
+    pub fn adjust_custom_clues(&mut self, delta: i32) {
+        let selected = &mut self.difficulties[self.selected_difficulty_index];
+        if matches!(selected, Difficulty::Custom(_)) {
+            *selected = selected.with_more_clues(delta);
+            self.custom_clues_changed = Some(Instant::now());
+        }
+    }
+
+    /// Moves the difficulty selection cursor.
+    pub fn move_difficulty_selection(&mut self, delta: isize) {
+        let current_index = self.selected_difficulty_index as isize;
+        let num_options = self.difficulties.len() as isize;
+        // Calculate new index with wrapping
+        let mut new_index = (current_index + delta) % num_options;
+        if new_index < 0 {
+            new_index += num_options;
+        }
+        self.selected_difficulty_index = new_index as usize;
+        self.prepare_source();
+    }
+
+    /// Cycles the variant selection and warms up the generator for it.
+    pub fn cycle_variant(&mut self, delta: isize) {
+        let num_options = Variant::ALL.len() as isize;
+        let new_index = (self.selected_variant_index as isize + delta).rem_euclid(num_options);
+        self.selected_variant_index = new_index as usize;
+        self.prepare_source();
+    }
+
+    /// Resets the app state to difficulty selection, or to the standings during a tournament
+    /// or challenge. An unfinished game with progress is recorded in the history as abandoned,
+    /// and leaving one ends the challenge it belongs to.
+    pub fn return_to_difficulty_selection(&mut self) {
+        if self.state == AppState::Running
+            && self
+                .sudoku
+                .as_ref()
+                .is_some_and(|s| s.has_progress() || !self.comments.is_empty())
+        {
+            self.record_game(Outcome::Abandoned);
+        }
+        if self.state == AppState::Running && self.tournament.is_some() {
+            self.record_tournament_result(ResultOutcome::Forfeited);
+        }
+        if self.state == AppState::Running && self.challenge.take().is_some() {
+            self.status_message = Some("Challenge abandoned".to_string());
+        }
+        self.leave_game();
+    }
+
+    fn leave_game(&mut self) {
+        self.sudoku = None;
+        self.win = None;
+        self.preview = None;
+        self.animation = None;
+        self.state = if self.tournament.is_some() {
+            AppState::Tournament
+        } else if self.challenge.is_some() {
+            AppState::Challenge
+        } else {
+            AppState::SelectingDifficulty
+        };
+        self.start_time = None;
+        self.elapsed_time = None;
+        self.grid_screen_rect = None;
+        self.soft_key_rects.clear();
+        self.paused_at = None;
+        // Keep selected_difficulty_index as is
+    }
+
+    /// Starts going to the menu: a game with progress asks first, since
+    /// leaving abandons it, and offers to save it when it can be resumed.
+    pub fn request_menu(&mut self) {
+        if self.game_in_progress() {
+            let save = self.should_confirm_quit();
+            self.confirm = Some(Confirm::LeaveGame {
+                save,
+                replaces: save && self.other_save_kept(),
+            });
+        } else {
+            self.return_to_difficulty_selection();
+        }
+    }
+
+    /// Saves the game for the next launch and goes to the menu, instead of
+    /// archiving it as abandoned. A challenge it belongs to ends.
+    pub fn save_and_leave(&mut self) {
+        if let Err(e) = self.autosave() {
+            self.status_message = Some(format!("Could not save the game: {e}"));
+            return;
+        }
+        self.status_message = Some(if self.challenge.take().is_some() {
+            "Challenge abandoned, its game is saved for the next launch".to_string()
+        } else {
+            "Saved, offered again at the next launch".to_string()
+        });
+        self.leave_game();
+    }
+
+    /// Whether quitting should first ask to save or abandon the game. Tournament
+    /// games, untouched boards and guest games are never autosaved, so they don't ask.
+    pub fn should_confirm_quit(&self) -> bool {
+        self.game_in_progress() && self.tournament.is_none() && storage::persistent()
+    }
+
+    /// Starts quitting: asks first when the game in progress should be saved
+    /// or would be lost, and otherwise returns true to leave at once.
+    pub fn request_quit(&mut self) -> bool {
+        if self.should_confirm_quit() {
+            self.quit_prompt = true;
+            self.save_replaces_other = self.other_save_kept();
+        } else if self.quit_loses_game() {
+            self.confirm = Some(Confirm::Quit);
+        } else {
+            return true;
+        }
+        false
+    }
+
+    /// Whether quitting now would lose a game with progress that is never
+    /// autosaved, a tournament or guest game, so it has to be confirmed.
+    pub fn quit_loses_game(&self) -> bool {
+        self.game_in_progress() && (self.tournament.is_some() || !storage::persistent())
+    }
+
+    /// Whether the save file holds a game other than this one, such as one
+    /// kept with "save and leave" before this game was started.
+    fn other_save_kept(&self) -> bool {
+        !self.resumed_from_save && SavedGame::load().is_some()
+    }
+
+    /// A running game with entries, pencil marks or comments to lose.
+    fn game_in_progress(&self) -> bool {
+        self.state == AppState::Running
+            && self
+                .sudoku
+                .as_ref()
+                .is_some_and(|s| s.has_progress() || !self.comments.is_empty())
+    }
+
+    /// Archives the running game as abandoned instead of autosaving it. With
+    /// `solver_finish` the solver completes the board in the background first;
+    /// see `archiving`.
+    pub fn abandon_game(&mut self, solver_finish: bool) {
+        self.quit_prompt = false;
+        if !solver_finish {
+            self.record_game(Outcome::Abandoned);
+        } else if let Some(record) = self.game_record(Outcome::Abandoned) {
+            self.archiving = Some(history::append_solver_finished(record));
+            if let Err(e) = self.discard_own_save() {
+                self.status_message = Some(format!("Could not remove the autosave: {e}"));
+            }
+        }
+        self.leave_game();
+    }
+
+    /// Appends the current game to the history and drops its autosave.
+    fn record_game(&mut self, outcome: Outcome) {
+        let Some(record) = self.game_record(outcome) else {
+            return;
+        };
+        let result = history::append(record).and_then(|_| self.discard_own_save());
+        if let Err(e) = result {
+            self.status_message = Some(format!("Could not update history: {e}"));
+        }
+    }
+
+    fn game_record(&self, outcome: Outcome) -> Option<GameRecord> {
+        let sudoku = self.sudoku.clone()?;
+        Some(GameRecord {
+            finished_at: chrono::Utc::now().timestamp(),
+            sudoku,
+            elapsed_secs: self.elapsed_time.unwrap_or(Duration::ZERO).as_secs(),
+            outcome,
+            comments: self.comments.clone(),
+            penalty: self.penalty,
+            hints_used: self.hints_used,
+            mistakes: self.mistakes,
+            daily: self.daily_date,
+            moves: self.moves.clone(),
+            eliminations: self.eliminations.clone(),
+            solver_finish: None,
+        })
+    }
+
+    /// Removes the autosave the current game was resumed from, if any.
+    fn discard_own_save(&mut self) -> io::Result<()> {
+        if !self.resumed_from_save {
+            return Ok(());
+        }
+        self.resumed_from_save = false;
+        SavedGame::discard()
+    }
+
+    /// Writes the unfinished game so it can be resumed later. Does nothing if no game
+    /// is running. Tournament games can't be resumed, so they aren't saved either,
+    /// and neither are untouched boards, which would only overwrite a game saved
+    /// earlier.
+    pub fn autosave(&self) -> io::Result<()> {
+        if self.state != AppState::Running || self.tournament.is_some() {
+            return Ok(());
+        }
+        if !self.game_in_progress() && !self.resumed_from_save {
+            return Ok(());
+        }
+        let Some(sudoku) = self.sudoku.clone() else {
+            return Ok(());
+        };
+        SavedGame {
+            sudoku,
+            elapsed_secs: self.elapsed_time.unwrap_or(Duration::ZERO).as_secs(),
+            comments: self.comments.clone(),
+            penalty: self.penalty,
+            daily: self.daily_date,
+            moves: self.moves.clone(),
+            hints_used: self.hints_used,
+            peeks_used: self.peeks_used,
+            eliminations: self.eliminations.clone(),
+            wrong_submissions: self.wrong_submissions,
+            mistakes: self.mistakes,
+            mistake_limit: self.mistake_limit,
+            memory_recalls: self.memory.as_ref().map(|memory| memory.recalls_left),
+            zen: self.zen,
+            countdown: self.countdown,
+            ladder_rung: self.ladder_rung,
+            links: self.links.clone(),
+            validation: self.game_validation,
+            saved_at: chrono::Utc::now().timestamp(),
+        }
+        .write()
+    }
+
+    /// Opens the daily puzzle screen, spending streak freezes on any missed days.
+    pub fn open_daily(&mut self) {
+        self.daily_progress = DailyProgress::load(daily::today());
+        if let Err(e) = self.daily_progress.write() {
+            self.status_message = Some(format!("Could not save daily progress: {e}"));
+        }
+        self.state = AppState::Daily;
+    }
+
+    /// Starts today's daily puzzle.
+    pub fn start_daily(&mut self) {
+        let date = daily::today();
+        self.begin_game(daily::puzzle_for(date), Duration::ZERO);
+        self.resumed_from_save = false;
+        self.daily_date = Some(date);
+    }
+
+    /// Credits a solved daily puzzle to the streak.
+    fn complete_daily(&mut self, date: NaiveDate) {
+        let mut progress = DailyProgress::load(daily::today());
+        let earned_freeze = progress.complete(date);
+        self.status_message = Some(match progress.write() {
+            Err(e) => format!("Could not save daily progress: {e}"),
+            Ok(()) if earned_freeze => format!(
+                "Daily streak: {} days - earned a streak freeze!",
+                progress.streak
+            ),
+            Ok(()) => format!("Daily streak: {} days", progress.streak),
+        });
+        self.daily_progress = progress;
+    }
+
+    /// Opens the practice ladder with the next rung to climb selected.
+    pub fn open_ladder(&mut self) {
+        self.ladder_progress = LadderProgress::load();
+        self.selected_rung = self.ladder_progress.cleared().min(ladder::RUNGS - 1);
+        self.state = AppState::Ladder;
+        self.prepare_rung();
+    }
+
+    /// Moves the rung selection, within the rungs that can be played.
+    pub fn move_rung_selection(&mut self, delta: isize) {
+        let count = (self.ladder_progress.cleared() + 1).min(ladder::RUNGS) as isize;
+        self.selected_rung = (self.selected_rung as isize + delta).rem_euclid(count) as usize;
+        self.rung_asked = false;
+        self.prepare_rung();
+    }
+
+    /// Starts making the selected rung's puzzle in the background, unless
+    /// it is already under way.
+    fn prepare_rung(&mut self) {
+        let rung = self.selected_rung;
+        if self
+            .rung_coming
+            .as_ref()
+            .is_none_or(|&(coming, _)| coming != rung)
+        {
+            self.rung_coming = Some((rung, thread::spawn(move || ladder::puzzle_for(rung))));
+        }
+    }
+
+    /// Starts the selected rung of the ladder, once its puzzle is made.
+    pub fn start_ladder_rung(&mut self) {
+        if !self.ladder_progress.unlocked(self.selected_rung) {
+            return;
+        }
+        self.prepare_rung();
+        self.rung_asked = true;
+        self.status_message = Some(format!("Preparing rung {}...", self.selected_rung + 1));
+        self.update_ladder();
+    }
+
+    /// Starts the rung asked for once its puzzle is done, if the ladder
+    /// screen is still open. Never blocks.
+    pub fn update_ladder(&mut self) {
+        if self.state != AppState::Ladder {
+            self.rung_asked = false;
+        }
+        if !self.rung_asked
+            || !self
+                .rung_coming
+                .as_ref()
+                .is_some_and(|(_, coming)| coming.is_finished())
+        {
+            return;
         }
+        let Some((rung, coming)) = self.rung_coming.take() else {
+            return;
+        };
+        self.rung_asked = false;
+        let Ok(puzzle) = coming.join() else {
+            self.status_message = Some(format!("Could not make the puzzle of rung {}", rung + 1));
+            return;
+        };
+        self.status_message = None;
+        self.begin_game(puzzle, Duration::ZERO);
+        self.resumed_from_save = false;
+        self.ladder_rung = Some(rung);
+    }
+
+    /// Credits a solved rung, unlocking the next one the first time.
+    fn complete_ladder_rung(&mut self, rung: usize) {
+        let mut progress = LadderProgress::load();
+        let secs = self.elapsed_time.unwrap_or(Duration::ZERO).as_secs();
+        let previous = progress.best(rung);
+        let cleared = progress.complete(rung, secs);
+        self.status_message = Some(match progress.write() {
+            Err(e) => format!("Could not save ladder progress: {e}"),
+            Ok(()) if cleared && rung + 1 == ladder::RUNGS => {
+                "Top of the ladder reached!".to_string()
+            }
+            Ok(()) if cleared => format!("Rung {} cleared, rung {} unlocked", rung + 1, rung + 2),
+            Ok(()) if previous.is_some_and(|best| secs < best) => {
+                format!("New best on rung {}", rung + 1)
+            }
+            Ok(()) => format!("Rung {} climbed again", rung + 1),
+        });
+        self.ladder_progress = progress;
+        self.selected_rung = self.ladder_progress.cleared().min(ladder::RUNGS - 1);
+    }
+
+    /// Shows the standings of a tournament loaded from a bundle.
+    pub fn open_tournament(&mut self, run: TournamentRun) {
+        self.tournament = Some(run);
+        self.state = AppState::Tournament;
+    }
+
+    /// Starts the next unplayed puzzle of the tournament.
+    pub fn play_next_tournament_game(&mut self) {
+        let Some(sudoku) = self.tournament.as_ref().and_then(|run| {
+            run.next_index()
+                .and_then(|index| run.tournament.puzzle(index))
+        }) else {
+            return;
+        };
+        self.begin_game(sudoku, Duration::ZERO);
+        self.resumed_from_save = false;
+    }
+
+    /// Starts a cross-variant challenge of the selected difficulty with a fresh seed.
+    pub fn new_challenge(&mut self) {
+        self.open_challenge(Challenge::new(self.selected_difficulty(), rand::random()));
+    }
+
+    /// Shows the puzzles of a challenge and the times so far.
+    pub fn open_challenge(&mut self, challenge: Challenge) {
+        self.challenge = Some(challenge);
+        self.status_message = None;
+        self.state = AppState::Challenge;
+    }
+
+    /// Leaves the challenge screen, giving up on the challenge.
+    pub fn close_challenge(&mut self) {
+        self.challenge = None;
+        self.leave_game();
+    }
+
+    /// Starts the next unplayed puzzle of the challenge.
+    pub fn play_next_challenge_game(&mut self) {
+        let Some(sudoku) = self.challenge.as_ref().and_then(|challenge| {
+            challenge
+                .next_index()
+                .map(|index| challenge.puzzles[index].clone())
+        }) else {
+            return;
+        };
+        self.begin_game(sudoku, Duration::ZERO);
+        self.resumed_from_save = false;
+    }
+
+    /// Adds the solve time of the puzzle just finished to the challenge.
+    fn record_challenge_time(&mut self) {
+        let elapsed = self.elapsed_time.unwrap_or(Duration::ZERO);
+        if let Some(challenge) = self.challenge.as_mut() {
+            challenge.times.push(elapsed);
+            if challenge.next_index().is_none() {
+                self.status_message = Some("Challenge complete, n for the total time".to_string());
+            }
+        }
+    }
+
+    /// Starts a "find the mistake" round on a generated position of the
+    /// selected difficulty.
+    /// The round is made in the background and opens once it is done.
+    pub fn new_hunt(&mut self) {
+        if self.hunt_coming.is_none() {
+            self.hunt_coming = Some(Hunt::in_background(self.selected_difficulty()));
+            self.status_message = Some("Preparing a round...".to_string());
+        }
+    }
+
+    /// Starts a round on the running game's board, its wrong entries being
+    /// the ones to find. The game waits, paused, until the round is left.
+    fn hunt_own_board(&mut self) {
+        if self.state != AppState::Running || !self.assists_allowed() {
+            return;
+        }
+        let Some(hunt) = self.sudoku.as_ref().and_then(Hunt::from_board) else {
+            self.status_message = Some("No wrong entries to look for".to_string());
+            return;
+        };
+        self.resume_after_hunt = !self.is_paused();
+        if self.resume_after_hunt {
+            self.toggle_pause();
+        }
+        self.hunt = Some(hunt);
+        self.state = AppState::Hunt;
+        self.status_message = None;
+    }
+
+    pub fn move_hunt_cursor(&mut self, dr: isize, dc: isize) {
+        if let Some(hunt) = self.hunt.as_mut() {
+            hunt.move_cursor(dr, dc);
+        }
+    }
+
+    /// Picks the cell under the hunt's cursor as the mistake.
+    pub fn hunt_pick(&mut self) {
+        let Some(hunt) = self.hunt.as_mut() else {
+            return;
+        };
+        let hit = hunt.pick();
+        self.status_message = Some(
+            match (hit, hunt.hidden.len()) {
+                (true, 0) => "Found it!",
+                (true, _) => "Found one, there are more",
+                (false, _) if hunt.is_over() => return,
+                (false, _) => "That one is right",
+            }
+            .to_string(),
+        );
+    }
+
+    /// Starts the next round on a fresh position, for generated hunts.
+    pub fn next_hunt_round(&mut self) {
+        if let Some(hunt) = self.hunt.as_mut().filter(|hunt| hunt.is_over()) {
+            self.status_message =
+                (!hunt.next_round()).then(|| "Preparing the next round...".to_string());
+        }
+    }
+
+    /// The solved game against the earlier solves of its difficulty, read
+    /// before the game itself is added to the history.
+    fn win_summary(&self) -> Option<WinSummary> {
+        let sudoku = self.sudoku.as_ref()?;
+        let earlier = stats::for_difficulty(&history::load(), sudoku.difficulty());
+        Some(WinSummary::new(
+            sudoku.variant(),
+            self.elapsed_time.unwrap_or(Duration::ZERO).as_secs(),
+            self.current_score(),
+            self.mistakes,
+            self.hints_used,
+            Quiz::from_game(sudoku, &self.moves, &mut rand::rng()),
+            &earlier,
+        ))
+    }
+
+    pub fn move_win_selection(&mut self, delta: isize) {
+        if let Some(win) = self.win.as_mut() {
+            win.move_selection(delta);
+        }
+    }
+
+    /// The technique quiz open on the win screen, if any.
+    pub fn quiz_mut(&mut self) -> Option<&mut Quiz> {
+        self.win
+            .as_mut()
+            .filter(|win| win.quizzing)
+            .and_then(|win| win.quiz.as_mut())
+    }
+
+    /// Closes the quiz, back to the win screen's figures.
+    pub fn close_quiz(&mut self) {
+        if let Some(win) = self.win.as_mut() {
+            win.quizzing = false;
+        }
+    }
+
+    /// Acts on the win screen's selected entry. Returns true when it is the
+    /// one to quit.
+    pub fn choose_win(&mut self) -> bool {
+        let Some(choice) = self.win.as_ref().map(WinSummary::choice) else {
+            return false;
+        };
+        match choice {
+            WinChoice::NewGame => self.return_to_difficulty_selection(),
+            WinChoice::SameDifficulty => self.play_same_difficulty(),
+            WinChoice::Quiz => {
+                if let Some(win) = self.win.as_mut() {
+                    win.quizzing = true;
+                }
+            }
+            WinChoice::ViewBoard => self.win = None,
+            WinChoice::Quit => return true,
+        }
+        false
+    }
+
+    /// Starts a fresh game of the solved one's difficulty and variant, from
+    /// the menu's selected source.
+    fn play_same_difficulty(&mut self) {
+        let Some(sudoku) = self.sudoku.as_ref() else {
+            return;
+        };
+        let (difficulty, variant) = (sudoku.difficulty(), sudoku.variant());
+        self.return_to_difficulty_selection();
+        self.select_difficulty(difficulty);
+        if let Some(index) = Variant::ALL.iter().position(|&v| v == variant) {
+            self.selected_variant_index = index;
+        }
+        self.start_game();
+    }
+
+    /// Leaves the hunt, for the game it was started from or the menu.
+    pub fn close_hunt(&mut self) {
+        let from_game = self
+            .hunt
+            .take()
+            .is_some_and(|hunt| hunt.difficulty.is_none());
+        self.status_message = None;
+        if !from_game {
+            self.return_to_difficulty_selection();
+            return;
+        }
+        self.state = AppState::Running;
+        if self.resume_after_hunt {
+            self.toggle_pause();
+        }
+    }
+
+    /// Ends the hunt's round when its time runs out, and opens a round made
+    /// in the background once it is done, unless the menu was left meanwhile.
+    pub fn update_hunt(&mut self) {
+        if self
+            .hunt_coming
+            .as_ref()
+            .is_some_and(JoinHandle::is_finished)
+            && let Some(Ok(hunt)) = self.hunt_coming.take().map(JoinHandle::join)
+            && self.state == AppState::SelectingDifficulty
+        {
+            self.hunt = Some(hunt.started());
+            self.state = AppState::Hunt;
+            self.status_message = None;
+        }
+        if let Some(hunt) = self.hunt.as_mut()
+            && hunt.update()
+        {
+            self.status_message = None;
+        }
+    }
+
+    /// Game-clock seconds left under the tournament time limit, if there is one.
+    pub fn tournament_time_left(&self) -> Option<u64> {
+        let limit = self.tournament.as_ref()?.tournament.rules.time_limit_secs?;
+        let elapsed = self.elapsed_time.unwrap_or(Duration::ZERO).as_secs();
+        Some(limit.saturating_sub(elapsed))
+    }
+
+    /// Ends a tournament game whose time limit has run out.
+    pub fn check_tournament_time(&mut self) {
+        if self.state == AppState::Running && self.tournament_time_left() == Some(0) {
+            self.record_tournament_result(ResultOutcome::TimedOut);
+            self.record_game(Outcome::TimedOut);
+            self.leave_game();
+            self.status_message = Some("Time is up".to_string());
+        }
+    }
+
+    fn record_tournament_result(&mut self, outcome: ResultOutcome) {
+        let score = if outcome == ResultOutcome::Solved {
+            self.current_score()
+        } else {
+            0
+        };
+        let Some(run) = self.tournament.as_mut() else {
+            return;
+        };
+        let Some(puzzle) = run.next_index() else {
+            return;
+        };
+        let result = GameResult {
+            puzzle,
+            outcome,
+            elapsed_secs: self.elapsed_time.unwrap_or(Duration::ZERO).as_secs(),
+            score,
+            hints_used: self.hints_used,
+        };
+        if let Err(e) = run.record(result) {
+            self.status_message = Some(format!("Could not write tournament results: {e}"));
+        }
+    }
+
+    /// Whether solution peeks and the diff view are allowed; tournaments forbid them.
+    fn assists_allowed(&mut self) -> bool {
+        if self.state == AppState::Running && self.tournament.is_some() {
+            self.status_message = Some("Not allowed during a tournament".to_string());
+            return false;
+        }
+        true
+    }
+
+    /// Opens the import screen for pasting a puzzle.
+    pub fn open_import(&mut self) {
+        self.import_text.clear();
+        self.import_review = None;
+        self.state = AppState::Import;
+    }
+
+    pub fn push_import_char(&mut self, ch: char) {
+        self.import_text.push(ch);
+    }
+
+    pub fn pop_import_char(&mut self) {
+        self.import_text.pop();
+    }
+
+    /// Takes a bracketed paste, which is usually the whole puzzle, and reviews it.
+    pub fn paste_import(&mut self, text: &str) {
+        self.import_text.push_str(text);
+        self.review_import();
+    }
+
+    /// Parses the pasted text and shows the board for checking. Text carrying a
+    /// puzzle ID, such as an export footer, reloads that puzzle directly instead.
+    pub fn review_import(&mut self) {
+        if let Some(id) = PuzzleId::find(&self.import_text) {
+            match id.load() {
+                Some(sudoku) => {
+                    self.import_text.clear();
+                    self.begin_game(sudoku, Duration::ZERO);
+                    self.resumed_from_save = false;
+                    self.status_message = Some(format!("Loaded puzzle {id}"));
+                }
+                None => self.status_message = Some("That puzzle ID has no solution".to_string()),
+            }
+            return;
+        }
+        match import::parse_loose(&self.import_text) {
+            Some(parsed) => self.import_review = Some(ImportReview::new(parsed)),
+            None => self.status_message = Some("No puzzle cells found in the text".to_string()),
+        }
+    }
+
+    /// Goes back from the review to the pasted text.
+    pub fn cancel_import_review(&mut self) {
+        self.import_review = None;
+    }
+
+    /// Plays the reviewed board if it can be solved.
+    pub fn start_import(&mut self) {
+        let Some(review) = self.import_review.as_ref() else {
+            return;
+        };
+        let Some((sudoku, solutions)) = SudokuGrid::from_clues(review.clues, Variant::Classic)
+        else {
+            self.status_message = Some("This board has no solution - fix it first".to_string());
+            return;
+        };
+        self.import_review = None;
+        self.import_text.clear();
+        self.play_imported(sudoku, solutions);
+    }
+
+    /// Starts a game on a puzzle brought in from outside, skipping any resume
+    /// prompt, and warns when the clues allow more than one solution.
+    pub fn play_imported(&mut self, sudoku: SudokuGrid, solutions: usize) {
+        self.saved_game = None;
+        self.begin_game(sudoku, Duration::ZERO);
+        self.resumed_from_save = false;
+        if solutions > 1 {
+            self.status_message = Some(
+                "Imported puzzle has several solutions; entries are checked against one"
+                    .to_string(),
+            );
+        }
+    }
+
+    /// Game-clock seconds until a new hint may be asked for, if still cooling down.
+    pub fn hint_cooldown_left(&self) -> Option<u64> {
+        let elapsed = self.elapsed_time.unwrap_or(Duration::ZERO).as_secs();
+        let ready_at = self.last_hint_secs? + self.gameplay.hint_cooldown_seconds;
+        (ready_at > elapsed).then(|| ready_at - elapsed)
+    }
+
+    /// Points the next new hint's first tier will cost, as the cheapest and
+    /// dearest it can be. They differ only under dynamic pricing, where the
+    /// technique the hint needs isn't known until it is asked for.
+    pub fn next_hint_cost(&self) -> (u32, u32) {
+        let flat = score::hint_penalty(
+            1,
+            self.hints_used + 1,
+            self.gameplay.hint_escalation_percent,
+        );
+        match self.gameplay.hint_pricing {
+            HintPricing::Flat => (flat, flat),
+            HintPricing::Dynamic => {
+                let progress = self.sudoku.as_ref().map_or(0, |s| s.progress_percent());
+                let price = |technique| score::dynamic_percent(technique, progress);
+                (
+                    score::scaled(flat, price(Some(Technique::NakedSingle))),
+                    score::scaled(flat, price(None)),
+                )
+            }
+        }
+    }
+
+    /// Percent of the flat price a hint or reveal costs at this point of the game.
+    fn hint_price_percent(&self, technique: Option<Technique>) -> u32 {
+        match self.gameplay.hint_pricing {
+            HintPricing::Flat => 100,
+            HintPricing::Dynamic => score::dynamic_percent(
+                technique,
+                self.sudoku.as_ref().map_or(0, |s| s.progress_percent()),
+            ),
+        }
+    }
+
+    /// Reveals the next hint tier for the next logical placement, charging its
+    /// penalty. Every hint is checked against the stored solution. New hints are
+    /// refused during the cooldown, and each costs more than the one before.
+    /// Once fully revealed, further presses open and close its explanation.
+    pub fn request_hint(&mut self) {
+        if self.state != AppState::Running || self.sudoku.is_none() {
+            return;
+        }
+        if let Some(hint) = self.hint.as_mut() {
+            if hint.tier < HINT_TIER_PENALTIES.len() {
+                hint.tier += 1;
+                self.penalty += score::scaled(
+                    score::hint_penalty(
+                        hint.tier,
+                        self.hints_used,
+                        self.gameplay.hint_escalation_percent,
+                    ),
+                    hint.price_percent,
+                );
+            } else {
+                // Explaining a fully revealed hint costs nothing more
+                hint.explained = !hint.explained;
+            }
+            return;
+        }
+        if !self.new_hint_allowed() {
+            return;
+        }
+        let Some(sudoku) = self.sudoku.as_ref() else {
+            return;
+        };
+
+        let wrong = sudoku.wrong_entry_count();
+        if wrong > 0 {
+            self.status_message = Some(format!(
+                "{wrong} entr{} wrong - no hint until fixed",
+                if wrong == 1 { "y is" } else { "ies are" }
+            ));
+            return;
+        }
+        let mut solver = Solver::new(sudoku.board(), sudoku.variant());
+        let mut chain = solver.placement_chain().unwrap_or_default();
+        let technique = chain.iter().map(|step| step.technique).max();
+        match (chain.pop(), technique) {
+            (Some(step), Some(technique))
+                if step
+                    .placement
+                    .is_some_and(|(r, c, d)| sudoku.solution_value(r, c) == d) =>
+            {
+                let price_percent = self.hint_price_percent(Some(technique));
+                self.hints_used += 1;
+                self.penalty += score::scaled(
+                    score::hint_penalty(1, self.hints_used, self.gameplay.hint_escalation_percent),
+                    price_percent,
+                );
+                self.hint = Some(Hint {
+                    tier: 1,
+                    step,
+                    lead_up: chain,
+                    technique,
+                    explained: false,
+                    price_percent,
+                });
+                self.add_time_penalty();
+                self.last_hint_secs = Some(self.elapsed_time.unwrap_or(Duration::ZERO).as_secs());
+            }
+            _ => {
+                self.status_message =
+                    Some("No deduction found with the known techniques".to_string());
+            }
+        }
+    }
+
+    /// Fills the selected cell with its solution digit as a hint. The cell is
+    /// locked afterwards and the hint is charged like a fully revealed one.
+    pub fn reveal_current_cell(&mut self) {
+        if self.state != AppState::Running {
+            return;
+        }
+        let (r, c) = self.cursor_pos;
+        if self
+            .sudoku
+            .as_ref()
+            .is_some_and(|s| s.is_fixed(r, c) || s.is_revealed(r, c))
+        {
+            self.status_message = Some("This cell's digit is already known".to_string());
+            return;
+        }
+        if !self.new_hint_allowed() {
+            return;
+        }
+        // Priced on the board as it was before the reveal
+        let price_percent = self.hint_price_percent(None);
+        let Some(sudoku) = self.sudoku.as_mut() else {
+            return;
+        };
+        sudoku.reveal_cell(r, c);
+        let region = completed_region(sudoku, r, c);
+        self.hints_used += 1;
+        self.penalty += score::scaled(
+            score::reveal_penalty(self.hints_used, self.gameplay.hint_escalation_percent),
+            price_percent,
+        );
+        self.add_time_penalty();
+        self.last_hint_secs = Some(self.elapsed_time.unwrap_or(Duration::ZERO).as_secs());
+        self.hint = None;
+        self.last_input_valid = true;
+        if !self.settle_full_board()
+            && let Some(region) = region
+        {
+            self.animate(Effect::Flash(region));
+        }
+    }
+
+    /// Whether a new hint may be given now, reporting why not otherwise.
+    fn new_hint_allowed(&mut self) -> bool {
+        if self
+            .tournament
+            .as_ref()
+            .is_some_and(|run| run.tournament.rules.hints == HintPolicy::Off)
+        {
+            self.status_message = Some("Hints are off in this tournament".to_string());
+            return false;
+        }
+        if let Some(left) = self.hint_cooldown_left() {
+            self.status_message = Some(format!("Next hint available in {left}s"));
+            return false;
+        }
+        true
+    }
+
+    /// Moves the clock forward by the configured, escalating time penalty of a new hint.
+    fn add_time_penalty(&mut self) {
+        self.add_clock_time(score::escalated(
+            self.gameplay.hint_time_penalty_seconds,
+            self.hints_used,
+            self.gameplay.hint_escalation_percent,
+        ));
+    }
+
+    fn add_clock_time(&mut self, secs: u32) {
+        let elapsed = self.elapsed_time.unwrap_or(Duration::ZERO);
+        if secs > 0 {
+            let penalty = Duration::from_secs(secs.into());
+            self.start_time = self.start_time.and_then(|start| start.checked_sub(penalty));
+            self.elapsed_time = Some(elapsed + penalty);
+        }
+    }
+
+    /// Current score of the running game.
+    pub fn current_score(&self) -> u32 {
+        let elapsed = self.elapsed_time.unwrap_or(Duration::ZERO).as_secs();
+        self.sudoku.as_ref().map_or(0, |sudoku| {
+            score::score(sudoku.difficulty(), elapsed, self.penalty)
+        })
+    }
+
+    /// How the clock compares with the median time for the difficulty, scaled
+    /// to how much of the board is filled in. None without enough solved
+    /// games for a median, or before the first entry.
+    pub fn pace(&self) -> Option<Pace> {
+        let median = self.median_secs?;
+        let progress = self.sudoku.as_ref()?.progress_percent() as u64;
+        if progress == 0 {
+            return None;
+        }
+        // Both in seconds times percent
+        let expected = median * progress;
+        let elapsed = self.elapsed_time.unwrap_or(Duration::ZERO).as_secs() * 100;
+        Some(
+            if elapsed * 100 < expected * (100 - PACE_TOLERANCE_PERCENT) {
+                Pace::Ahead
+            } else if elapsed * 100 > expected * (100 + PACE_TOLERANCE_PERCENT) {
+                Pace::Behind
+            } else {
+                Pace::Even
+            },
+        )
+    }
+
+    /// Pace statistics of the current game so far.
+    pub fn metrics(&self) -> Metrics {
+        let elapsed = self.elapsed_time.unwrap_or(Duration::ZERO).as_secs();
+        Metrics::from_moves(&self.moves, elapsed)
+    }
+
+    fn log_move(&mut self, row: usize, col: usize, digit: u8) {
+        let elapsed = self.game_clock();
+        self.moves.push(Move {
+            elapsed_ms: elapsed.as_millis() as u64,
+            row,
+            col,
+            digit,
+            assisted: false,
+        });
+    }
+
+    /// Switches the digit keys between entering digits and toggling pencil marks.
+    pub fn toggle_note_mode(&mut self) {
+        if self.state == AppState::Running {
+            self.note_mode = !self.note_mode;
+        }
+    }
+
+    /// Adds or removes a pencil mark in the selected cell.
+    pub fn toggle_note(&mut self, digit: u8) {
+        let before = self.cursor_cell_state();
+        self.toggle_note_untracked(digit);
+        self.push_edit(before);
+    }
+
+    fn toggle_note_untracked(&mut self, digit: u8) {
+        if self.state != AppState::Running {
+            return;
+        }
+        let (r, c) = self.cursor_pos;
+        let Some(sudoku) = self.sudoku.as_mut() else {
+            return;
+        };
+        match sudoku.toggle_note(r, c, digit) {
+            Some(true) => {}
+            Some(false) => self.log_elimination(r, c, digit),
+            None => self.status_message = Some("Only empty cells take notes".to_string()),
+        }
+    }
+
+    /// Removes every pencil mark from the selected cell.
+    pub fn clear_notes(&mut self) {
+        if self.state != AppState::Running {
+            return;
+        }
+        let (r, c) = self.cursor_pos;
+        let notes = self.sudoku.as_ref().map_or(Vec::new(), |s| s.notes(r, c));
+        let before = self.cursor_cell_state();
+        for digit in notes {
+            self.toggle_note_untracked(digit);
+        }
+        self.push_edit(before);
+    }
+
+    fn cursor_cell_state(&self) -> Option<CellState> {
+        let (r, c) = self.cursor_pos;
+        self.sudoku.as_ref().map(|s| s.cell_state(r, c))
+    }
+
+    /// Adds the change made to the selected cell since `before` to the undo
+    /// history, dropping anything that could have been redone.
+    fn push_edit(&mut self, before: Option<CellState>) {
+        let (row, col) = self.cursor_pos;
+        let (Some(before), Some(after)) = (before, self.cursor_cell_state()) else {
+            return;
+        };
+        if before != after {
+            self.undo.record(Edit::new(row, col, before, after), false);
+        }
+    }
+
+    /// Erases the digit just placed in the selected cell from its peers'
+    /// pencil marks, as part of the placement's edit in the undo history.
+    fn clear_peer_notes(&mut self) {
+        let (r, c) = self.cursor_pos;
+        let Some(sudoku) = self.sudoku.as_mut() else {
+            return;
+        };
+        let Some(digit) = sudoku
+            .get_cell(r, c, false)
+            .filter(|_| !sudoku.is_fixed(r, c))
+        else {
+            return;
+        };
+        for ((row, col), before) in sudoku.clear_peer_notes(r, c, digit) {
+            let after = sudoku.cell_state(row, col);
+            self.undo.record(Edit::new(row, col, before, after), true);
+        }
+    }
+
+    /// Takes back the latest change and moves the cursor to its cell.
+    pub fn undo(&mut self) {
+        if self.state != AppState::Running {
+            return;
+        }
+        let Some(step) = self.undo.undo() else {
+            self.status_message = Some("Nothing to undo".to_string());
+            return;
+        };
+        for edit in step {
+            self.apply_cell_state(edit.row, edit.col, edit.before);
+        }
+    }
+
+    /// Applies again the latest change taken back with undo.
+    pub fn redo(&mut self) {
+        if self.state != AppState::Running {
+            return;
+        }
+        let Some(step) = self.undo.redo() else {
+            self.status_message = Some("Nothing to redo".to_string());
+            return;
+        };
+        for edit in &step {
+            self.apply_cell_state(edit.row, edit.col, edit.after);
+        }
+        self.cursor_pos = (step[0].row, step[0].col);
+        self.settle_full_board();
+    }
+
+    fn apply_cell_state(&mut self, row: usize, col: usize, state: CellState) {
+        let Some(sudoku) = self.sudoku.as_mut() else {
+            return;
+        };
+        let value_changed = sudoku.cell_state(row, col).value != state.value;
+        sudoku.restore_cell(row, col, state);
+        self.cursor_pos = (row, col);
+        self.last_input_valid = true;
+        self.hint = None;
+        if value_changed {
+            self.log_move(row, col, state.value);
+        }
+    }
+
+    /// Logs a removed pencil mark with the digits the solver could rule out of
+    /// the cell at that moment, if elimination logging is on. Wrong entries are
+    /// left out of the solver's view of the board.
+    fn log_elimination(&mut self, row: usize, col: usize, digit: u8) {
+        if !self.gameplay.log_eliminations {
+            return;
+        }
+        let Some(sudoku) = self.sudoku.as_ref() else {
+            return;
+        };
+        let removable =
+            Solver::new(sudoku.correct_entries(), sudoku.variant()).eliminable((row, col));
+        let elapsed = self.game_clock();
+        self.eliminations.push(Elimination {
+            elapsed_ms: elapsed.as_millis() as u64,
+            row,
+            col,
+            digit,
+            removable,
+        });
+    }
+
+    /// Opens the comment popup for the current game.
+    pub fn open_comment_input(&mut self) {
+        if self.sudoku.is_some() {
+            self.comment_input = Some(String::new());
+        }
+    }
+
+    pub fn push_comment_char(&mut self, ch: char) {
+        if let Some(input) = self.comment_input.as_mut() {
+            input.push(ch);
+        }
+    }
+
+    pub fn pop_comment_char(&mut self) {
+        if let Some(input) = self.comment_input.as_mut() {
+            input.pop();
+        }
+    }
+
+    pub fn cancel_comment(&mut self) {
+        self.comment_input = None;
+    }
+
+    /// Copies the puzzle's clues to the clipboard as an 81-cell line.
+    pub fn copy_puzzle(&mut self) {
+        if let Some(text) = self.sudoku.as_ref().map(export::puzzle_line) {
+            self.copy_to_clipboard(&text, "puzzle line");
+        }
+    }
+
+    /// Copies the board with the entries made so far to the clipboard as a text grid.
+    pub fn copy_board(&mut self) {
+        if let Some(text) = self.sudoku.as_ref().map(export::progress_text) {
+            self.copy_to_clipboard(&text, "board");
+        }
+    }
+
+    fn copy_to_clipboard(&mut self, text: &str, what: &str) {
+        self.status_message = Some(match clipboard::copy(text) {
+            Ok(via) => format!("Copied the {what} to the clipboard (via {via})"),
+            Err(e) => format!("Could not copy the {what}: {e}"),
+        });
+    }
+
+    /// Opens the line for entering cells by coordinates, e.g. `r5c3=7`.
+    pub fn open_command_input(&mut self) {
+        if self.state == AppState::Running {
+            self.command_input = Some(String::new());
+        }
+    }
+
+    pub fn push_command_char(&mut self, ch: char) {
+        if let Some(input) = self.command_input.as_mut() {
+            input.push(ch);
+        }
+    }
+
+    pub fn pop_command_char(&mut self) {
+        if let Some(input) = self.command_input.as_mut() {
+            input.pop();
+        }
+    }
+
+    pub fn cancel_command(&mut self) {
+        self.command_input = None;
+    }
+
+    /// Makes the typed entries as if each cell were selected and the digit or
+    /// clear key pressed, so fixed and revealed cells stay as they are and
+    /// every change can be undone. The cursor is left on the last cell named.
+    pub fn submit_command(&mut self) {
+        let Some(text) = self.command_input.take() else {
+            return;
+        };
+        let entries = match command::parse(&text) {
+            Ok(Command::Enter(entries)) => entries,
+            Ok(Command::FillSingles) => {
+                self.fill_singles();
+                return;
+            }
+            Ok(Command::ClearLinks) => {
+                self.clear_links();
+                return;
+            }
+            Ok(Command::Hunt) => {
+                self.hunt_own_board();
+                return;
+            }
+            Ok(Command::GiveUp) => {
+                self.request_give_up();
+                return;
+            }
+            Ok(Command::Compare(path)) => {
+                self.compare_with(path.as_deref());
+                return;
+            }
+            Err(e) => {
+                self.status_message = Some(e);
+                return;
+            }
+        };
+        let (mut made, mut skipped) = (0, 0);
+        let mut refused = None;
+        // With undo steps per action, the whole command undoes as one step
+        self.undo.begin();
+        for entry in entries {
+            if self.state != AppState::Running {
+                break;
+            }
+            let (Entry::Set(r, c, _) | Entry::Clear(r, c)) = entry;
+            self.cursor_pos = (r, c);
+            if self
+                .sudoku
+                .as_ref()
+                .is_some_and(|s| s.is_fixed(r, c) || s.is_revealed(r, c))
+            {
+                skipped += 1;
+                continue;
+            }
+            self.status_message = None;
+            let wanted = match entry {
+                Entry::Set(_, _, digit) => {
+                    self.set_current_cell(digit);
+                    Some(digit)
+                }
+                Entry::Clear(..) => {
+                    self.clear_current_cell();
+                    None
+                }
+            };
+            // Overwrite rules can refuse a cell, saying why
+            match self.sudoku.as_ref().map(|s| s.get_cell(r, c, false)) {
+                Some(now) if now == wanted => made += 1,
+                _ => refused = self.status_message.take().or(refused),
+            }
+        }
+        self.undo.commit();
+        // A solved board brings its own message
+        if self.state == AppState::Running {
+            let plural = |n: usize| if n == 1 { "" } else { "s" };
+            let mut message = match skipped {
+                0 => format!("Entered {made} cell{}", plural(made)),
+                _ => format!(
+                    "Entered {made} cell{}, skipped {skipped} locked cell{}",
+                    plural(made),
+                    plural(skipped)
+                ),
+            };
+            if let Some(refused) = refused {
+                message.push_str(&format!("; {refused}"));
+            }
+            self.status_message = Some(message);
+        }
+    }
+
+    /// Fills every cell the board leaves a single digit for, then looks again
+    /// until none is left. Each fill is logged as an assisted move, and with
+    /// undo steps per action the whole sweep undoes as one step, so skipping
+    /// the easy part of a puzzle can be taken back in one key press.
+    pub fn fill_singles(&mut self) {
+        if self.state != AppState::Running || !self.assists_allowed() {
+            return;
+        }
+        self.undo.begin();
+        self.assisting = true;
+        let mut filled = 0;
+        'sweep: loop {
+            let singles = self
+                .sudoku
+                .as_ref()
+                .map_or_else(Vec::new, SudokuGrid::naked_singles);
+            if singles.is_empty() || self.state != AppState::Running {
+                break;
+            }
+            for (r, c, digit) in singles {
+                // An earlier fill of this sweep may have taken the digit, or
+                // solved or lost the game
+                if self.state != AppState::Running
+                    || self
+                        .sudoku
+                        .as_ref()
+                        .is_none_or(|s| s.candidates(r, c) != 1 << digit)
+                {
+                    continue 'sweep;
+                }
+                self.cursor_pos = (r, c);
+                let logged = self.moves.len();
+                self.set_current_cell(digit);
+                if let Some(entry) = self.moves.get_mut(logged) {
+                    entry.assisted = true;
+                }
+                filled += 1;
+            }
+        }
+        self.assisting = false;
+        self.undo.commit();
+        if self.state == AppState::Running {
+            self.status_message = Some(match filled {
+                0 => "No cell has a single candidate".to_string(),
+                1 => "Filled 1 naked single".to_string(),
+                _ => format!("Filled {filled} naked singles"),
+            });
+        }
+    }
+
+    /// Attaches the typed comment to the game, stamped with the game clock.
+    pub fn submit_comment(&mut self) {
+        let Some(text) = self.comment_input.take() else {
+            return;
+        };
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        self.comments.push(Comment {
+            elapsed_secs: self.elapsed_time.unwrap_or(Duration::ZERO).as_secs(),
+            written_at: chrono::Utc::now().timestamp(),
+            text: text.to_string(),
+        });
+        self.status_message = Some("Comment added".to_string());
+    }
+
+    /// Opens the history browser, newest games first.
+    pub fn open_history(&mut self) {
+        self.history = history::load();
+        self.history.reverse();
+        self.history_index = 0;
+        self.state = AppState::History;
+    }
+
+    /// Opens the statistics screen, worked out afresh from the history file.
+    pub fn open_stats(&mut self) {
+        self.stats = stats::per_difficulty(&history::load());
+        self.state = AppState::Stats;
+    }
+
+    /// Opens the settings screen, with the first option selected.
+    pub fn open_settings(&mut self) {
+        self.state = AppState::Settings;
+        self.settings_index = 0;
+        self.status_message = None;
+    }
+
+    pub fn move_settings_selection(&mut self, delta: isize) {
+        let len = Setting::ALL.len() as isize;
+        self.settings_index = (self.settings_index as isize + delta).rem_euclid(len) as usize;
+    }
+
+    /// Changes the selected option to its next or previous value, taking
+    /// effect at once, and keeps the new value in the config file.
+    pub fn adjust_setting(&mut self, delta: isize) {
+        let setting = Setting::ALL[self.settings_index];
+        match setting {
+            Setting::Theme => {
+                let len = Scheme::ALL.len() as isize;
+                let index = Scheme::ALL
+                    .iter()
+                    .position(|&s| s == self.scheme)
+                    .unwrap_or(0) as isize;
+                self.scheme = Scheme::ALL[(index + delta).rem_euclid(len) as usize];
+                // The `[colors]` overrides stay on top of the new scheme
+                self.theme = self.scheme.theme().with_colors(&self.color_overrides);
+            }
+            Setting::PeerHighlight => self.peer_highlight = !self.peer_highlight,
+            // The game in progress keeps what it was started with
+            Setting::Validation => self.validation = self.validation.toggled(),
+            Setting::ShowTimer => self.show_timer = !self.show_timer,
+            Setting::AutoAdvance => self.gameplay.auto_advance = !self.gameplay.auto_advance,
+            Setting::Colorblind => self.colorblind = !self.colorblind,
+            Setting::UndoSteps => {
+                self.gameplay.undo_steps = self.gameplay.undo_steps.cycled(delta);
+                self.undo.steps = self.gameplay.undo_steps;
+            }
+        }
+        let (table, key) = setting.config_key();
+        let value = self.setting_toml(setting);
+        self.status_message = Some(match Config::save_setting(table, key, value) {
+            Ok(path) => format!("Saved to {}", path.display()),
+            Err(e) => format!("Could not save the settings: {e}"),
+        });
+    }
+
+    /// The option's current value as the settings screen shows it.
+    pub fn setting_value(&self, setting: Setting) -> String {
+        let on_off = |on: bool| if on { "on" } else { "off" }.to_string();
+        match setting {
+            Setting::Theme => self.scheme.name().to_string(),
+            Setting::PeerHighlight => on_off(self.peer_highlight),
+            Setting::Validation => self.validation.name().to_string(),
+            Setting::ShowTimer => on_off(self.show_timer),
+            Setting::AutoAdvance => on_off(self.gameplay.auto_advance),
+            Setting::Colorblind => on_off(self.colorblind),
+            Setting::UndoSteps => self.gameplay.undo_steps.name().to_string(),
+        }
+    }
+
+    /// The option's current value as written in the config file.
+    fn setting_toml(&self, setting: Setting) -> toml::Value {
+        let quoted = |text: &str| toml::Value::String(text.to_string());
+        match setting {
+            Setting::Theme => quoted(self.scheme.key()),
+            Setting::PeerHighlight => toml::Value::Boolean(self.peer_highlight),
+            Setting::Validation => quoted(self.validation.key()),
+            Setting::ShowTimer => toml::Value::Boolean(self.show_timer),
+            Setting::AutoAdvance => toml::Value::Boolean(self.gameplay.auto_advance),
+            Setting::Colorblind => toml::Value::Boolean(self.colorblind),
+            Setting::UndoSteps => quoted(self.gameplay.undo_steps.key()),
+        }
+    }
+
+    /// Starts a disguised copy of the selected history game for practice.
+    pub fn practice_history_game(&mut self) {
+        let Some(record) = self.history.get(self.history_index) else {
+            return;
+        };
+        let sudoku = record.sudoku.remapped();
+        self.begin_game(sudoku, Duration::ZERO);
+        self.resumed_from_save = false;
+        self.status_message =
+            Some("Practice: same puzzle with relabelled digits and a turned board".to_string());
+    }
+
+    pub fn move_history_selection(&mut self, delta: isize) {
+        if self.history.is_empty() {
+            return;
+        }
+        let len = self.history.len() as isize;
+        self.history_index = (self.history_index as isize + delta).clamp(0, len - 1) as usize;
+    }
+
+    /// Stores the calculated screen area of the grid, border included.
+    pub fn set_grid_rect(&mut self, rect: Rect) {
+        self.grid_screen_rect = Some(rect);
+    }
+
+    /// Settles the game layout for a terminal of the new size. Where the grid
+    /// and the soft keys were drawn is forgotten until the next frame places
+    /// them, so no click is mapped through the old layout, and a held click
+    /// on the grid is dropped.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.layout = ui::negotiate_layout(self, Rect::new(0, 0, width, height));
+        self.forget_layout();
+        self.picker = None;
+    }
+
+    /// Drops the stored grid and soft key positions, as when nothing of the
+    /// game screen is drawn.
+    pub fn forget_layout(&mut self) {
+        self.grid_screen_rect = None;
+        self.soft_key_rects.clear();
     }
 
-    /// Starts a new game with the currently selected difficulty.
-    pub fn start_game(&mut self) {
-        let selected_difficulty = self.difficulties[self.selected_difficulty_index];
-        self.sudoku = Some(SudokuGrid::new(selected_difficulty));
-        self.state = AppState::Running;
-        self.cursor_pos = (0, 0);
-        self.show_solution = false;
-        self.last_input_valid = true;
-        self.start_time = Some(Instant::now());
-        self.elapsed_time = Some(Duration::ZERO);
-        self.grid_screen_rect = None; // Will be set by UI draw
+    /// Stores where the UI drew the soft key buttons.
+    pub fn set_soft_key_rects(&mut self, rects: Vec<(Rect, SoftKey)>) {
+        self.soft_key_rects = rects;
     }
 
-    /// Moves the difficulty selection cursor.
-    pub fn move_difficulty_selection(&mut self, delta: isize) {
-        let current_index = self.selected_difficulty_index as isize;
-        let num_options = self.difficulties.len() as isize;
-        // Calculate new index with wrapping
-        let mut new_index = (current_index + delta) % num_options;
-        if new_index < 0 {
-            new_index += num_options;
+    /// Presses a soft key button: runs its command, then names the key doing
+    /// the same unless the command reported something itself.
+    pub fn press_soft_key(&mut self, soft_key: SoftKey) {
+        self.status_message = None;
+        match soft_key {
+            SoftKey::Hint | SoftKey::Check | SoftKey::Notes if self.is_paused() => {
+                self.status_message = Some("Resume the game first".to_string())
+            }
+            SoftKey::Hint => self.request_hint(),
+            SoftKey::Check => self.toggle_diff(),
+            SoftKey::Notes if self.state == AppState::Running => self.toggle_note_mode(),
+            SoftKey::Notes => {}
+            SoftKey::Pause => self.toggle_pause(),
+            SoftKey::Menu => self.request_menu(),
         }
-        self.selected_difficulty_index = new_index as usize;
+        self.status_message.get_or_insert_with(|| {
+            format!(
+                "Tip: {} is also on the {} key",
+                soft_key.label(),
+                self.keymap.label(soft_key.action())
+            )
+        });
     }
 
-    /// Resets the app state to difficulty selection.
-    pub fn return_to_difficulty_selection(&mut self) {
-        self.sudoku = None;
-        self.state = AppState::SelectingDifficulty;
-        self.start_time = None;
-        self.elapsed_time = None;
-        self.grid_screen_rect = None;
-        // Keep selected_difficulty_index as is
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
     }
 
-    /// Stores the calculated screen area of the grid.
-    pub fn set_grid_rect(&mut self, rect: Rect) {
-        self.grid_screen_rect = Some(rect);
+    /// Stops the clock and hides the board, or picks up where the game left off.
+    pub fn toggle_pause(&mut self) {
+        if self.state != AppState::Running {
+            return;
+        }
+        if self.tournament.is_some() {
+            self.status_message = Some("Pausing is off in tournaments".to_string());
+            return;
+        }
+        match self.paused_at.take() {
+            // Move the start forward so the paused time is not counted
+            Some(paused_at) => {
+                self.start_time = self.start_time.map(|start| start + paused_at.elapsed())
+            }
+            None => self.paused_at = Some(Instant::now()),
+        }
     }
 
-    /// Attempts to move the cursor based on screen coordinates.
-    /// Only active when state is Running.
+    /// Presses the clicked soft key, or moves the cursor to the clicked cell.
+    /// Cells are only selectable while the game is running.
     pub fn handle_mouse_click(&mut self, screen_col: u16, screen_row: u16) {
-        if self.state != AppState::Running {
+        self.picker = None;
+        if self.popup_open() {
+            return;
+        }
+        let position = ratatui::layout::Position::new(screen_col, screen_row);
+        if let Some(&(_, soft_key)) = self
+            .soft_key_rects
+            .iter()
+            .find(|(rect, _)| rect.contains(position))
+        {
+            self.press_soft_key(soft_key);
+            return;
+        }
+        if self.state != AppState::Running || self.is_paused() {
             return;
         }
-        if let Some(grid_rect) = self.grid_screen_rect {
-            if screen_col > grid_rect.x
-                && screen_col < grid_rect.right() - 1
-                && screen_row > grid_rect.y
-                && screen_row < grid_rect.bottom() - 1
+        // The grid sits inside the block's border
+        if let Some(grid_rect) = self.grid_screen_rect
+            && let Some(screen_cell) = self
+                .grid
+                .cell_at(Block::bordered().inner(grid_rect), position)
+        {
+            let (r, c) = self.view.to_board(screen_cell, SIZE);
+            self.cursor_pos = (r, c);
+            if self
+                .sudoku
+                .as_ref()
+                .is_some_and(|s| s.get_cell(r, c, false).is_none())
             {
-                let relative_col = screen_col - (grid_rect.x + 1);
-                let relative_row = screen_row - (grid_rect.y + 1);
-                let grid_c = (relative_col / 4) as usize;
-                let grid_r = (relative_row / 2) as usize;
-                let clicked_on_cell_col = relative_col % 4 != 3;
-                let clicked_on_cell_row = relative_row % 2 == 0;
-
-                if clicked_on_cell_col && clicked_on_cell_row && grid_r < SIZE && grid_c < SIZE {
-                    self.cursor_pos = (grid_r, grid_c);
-                }
+                self.picker = Some(DigitPicker {
+                    cell: (r, c),
+                    anchor: (screen_col, screen_row),
+                    pressed_at: Instant::now(),
+                    open: false,
+                    hover: None,
+                    moved: false,
+                    rect: None,
+                });
+            }
+        }
+    }
+
+    /// Opens the digit picker once the click on an empty cell has been held
+    /// long enough.
+    pub fn update_picker(&mut self) {
+        if let Some(picker) = self.picker.as_mut()
+            && picker.pressed_at.elapsed() >= PICKER_HOLD
+        {
+            picker.open = true;
+        }
+    }
+
+    /// Follows a held click: dragging opens the picker at once and marks the
+    /// digit under the pointer.
+    pub fn drag_picker(&mut self, screen_col: u16, screen_row: u16) {
+        if let Some(picker) = self.picker.as_mut() {
+            picker.open = true;
+            picker.moved |= (screen_col, screen_row) != picker.anchor;
+            picker.hover = picker.digit_at(screen_col, screen_row);
+        }
+    }
+
+    /// Ends a held click, entering the digit it was let go over the way its
+    /// key would, so note mode, undo and the checks all apply. Letting go
+    /// anywhere else, or without having dragged, leaves the cell as it was.
+    pub fn release_picker(&mut self, screen_col: u16, screen_row: u16) {
+        let Some(picker) = self.picker.take() else {
+            return;
+        };
+        if !picker.open
+            || !picker.moved
+            || self.state != AppState::Running
+            || self.is_paused()
+            || self.popup_open()
+        {
+            return;
+        }
+        if let Some(digit) = picker.digit_at(screen_col, screen_row) {
+            self.cursor_pos = picker.cell;
+            if self.note_mode {
+                self.toggle_note(digit);
+            } else {
+                self.set_current_cell(digit);
+            }
+        }
+    }
+
+    /// Whether a popup is open over the game screen, taking the keys. The
+    /// `:` line and the note colors are left out: they work on the board, so
+    /// it stays in view and the clock runs on while they are open.
+    pub fn popup_open(&self) -> bool {
+        self.quit_prompt
+            || self.confirm.is_some()
+            || self.comment_input.is_some()
+            || self.show_help
+            || !self.keymap_problems.is_empty()
+    }
+
+    /// Stops the clock while a popup is open over a running game and starts
+    /// it again once the popup closes, so reading or typing in one costs no
+    /// time. Tournament clocks run on for everyone alike. A held click on
+    /// the board is dropped when a popup opens, so it cannot place a digit
+    /// once the popup closes.
+    pub fn update_popup_pause(&mut self) {
+        if self.popup_open() {
+            self.picker = None;
+        }
+        let stop = self.state == AppState::Running
+            && self.tournament.is_none()
+            && self.paused_at.is_none()
+            && self.popup_open();
+        match (stop, self.popup_since) {
+            (true, None) => self.popup_since = Some(Instant::now()),
+            (false, Some(since)) => {
+                self.start_time = self.start_time.map(|start| start + since.elapsed());
+                self.popup_since = None;
             }
+            _ => {}
         }
     }
 
+    /// Time on the game clock, leaving out a popup that stops it right now.
+    fn game_clock(&self) -> Duration {
+        let running = self
+            .start_time
+            .map_or(Duration::ZERO, |start| start.elapsed());
+        running.saturating_sub(
+            self.popup_since
+                .map_or(Duration::ZERO, |since| since.elapsed()),
+        )
+    }
+
     /// Updates the elapsed time if the game is running.
     pub fn update_timer(&mut self) {
-        if self.state == AppState::Running {
-            if let (Some(start), Some(elapsed)) = (self.start_time, self.elapsed_time.as_mut()) {
-                *elapsed = start.elapsed();
+        if self.state == AppState::Running
+            && self.paused_at.is_none()
+            && self.popup_since.is_none()
+            && let (Some(start), Some(elapsed)) = (self.start_time, self.elapsed_time.as_mut())
+        {
+            *elapsed = start.elapsed();
+            if self.time_left() == Some(0) {
+                self.fail_game(Outcome::TimedOut);
             }
         }
     }
 
-    /// Moves the grid cursor. Only active when state is Running.
+    /// Game-clock seconds left on a time-attack game's countdown.
+    pub fn time_left(&self) -> Option<u64> {
+        let elapsed = self.elapsed_time.unwrap_or(Duration::ZERO).as_secs();
+        Some(self.countdown?.saturating_sub(elapsed))
+    }
+
+    /// Moves the grid cursor in screen directions, so arrows follow a turned
+    /// board. Only active when state is Running.
     pub fn move_cursor(&mut self, dr: isize, dc: isize) {
         if self.state != AppState::Running {
             return;
         }
-        let (mut r, mut c) = self.cursor_pos;
+        let (mut r, mut c) = self.view.to_screen(self.cursor_pos, SIZE);
         if dr > 0 {
             r = (r + dr as usize) % SIZE;
         }
@@ -144,22 +2601,95 @@ impl App {
                 c = 0;
             }
         }
-        self.cursor_pos = (r, c);
+        self.cursor_pos = self.view.to_board((r, c), SIZE);
     }
 
     /// Sets number in the current cell. Only active when state is Running.
+    /// Typing over a different entry follows the configured overwrite mode.
     pub fn set_current_cell(&mut self, num: u8) {
-        if self.state != AppState::Running {
+        let before = self.cursor_cell_state();
+        self.enter_digit(num);
+        let placed = self
+            .cursor_cell_state()
+            .is_some_and(|after| after.value == num && before.is_some_and(|b| b.value != num));
+        self.push_edit(before);
+        if placed && self.gameplay.auto_clear_notes {
+            self.clear_peer_notes();
+        }
+        if placed && self.gameplay.auto_advance {
+            self.advance_to_empty_cell();
+        }
+    }
+
+    /// Moves the cursor to the next cell left to fill, in reading order as
+    /// the board is shown, wrapping around; it stays put on a full board.
+    fn advance_to_empty_cell(&mut self) {
+        let Some(sudoku) = self.sudoku.as_ref() else {
+            return;
+        };
+        let (r, c) = self.view.to_screen(self.cursor_pos, SIZE);
+        let start = r * SIZE + c;
+        let next = (1..SIZE * SIZE)
+            .map(|step| {
+                let i = (start + step) % (SIZE * SIZE);
+                self.view.to_board((i / SIZE, i % SIZE), SIZE)
+            })
+            .find(|&(r, c)| !sudoku.is_fixed(r, c) && sudoku.get_cell(r, c, false).is_none());
+        if let Some(pos) = next {
+            self.cursor_pos = pos;
+        }
+    }
+
+    fn enter_digit(&mut self, num: u8) {
+        if self.state != AppState::Running || self.cursor_on_revealed() {
             return;
         }
+        let (r, c) = self.cursor_pos;
+        let entry = self
+            .sudoku
+            .as_ref()
+            .filter(|s| !s.is_fixed(r, c))
+            .and_then(|s| s.get_cell(r, c, false));
+        if let Some(old) = entry
+            && old != num
+            && (1..=9).contains(&num)
+        {
+            match self.gameplay.overwrite {
+                OverwriteMode::Replace => {}
+                OverwriteMode::ClearFirst => {
+                    self.status_message = Some(format!(
+                        "r{}c{} already holds {old}; clear it first with 0 or Del",
+                        r + 1,
+                        c + 1
+                    ));
+                    return;
+                }
+                OverwriteMode::SwapIntoNotes => {
+                    self.swap_into_notes(old, num);
+                    return;
+                }
+            }
+        }
+        if self.sudoku.as_ref().is_some_and(|s| !s.is_fixed(r, c)) && (1..=9).contains(&num) {
+            self.log_move(r, c, num);
+        }
         if let Some(sudoku) = self.sudoku.as_mut() {
-            let (r, c) = self.cursor_pos;
             if !sudoku.is_fixed(r, c) {
                 if (1..=9).contains(&num) {
-                    self.last_input_valid = sudoku.is_valid_move(r, c, num);
                     sudoku.set_number(r, c, num);
-                    if sudoku.is_solved() {
-                        self.state = AppState::Solved;
+                    self.last_input_valid =
+                        !flagged(self.game_validation, self.live_check, sudoku, r, c);
+                    self.hint = None;
+                    let mistake = entry != Some(num) && sudoku.solution_value(r, c) != num;
+                    // A flash for a correct region would be feedback zen games leave out
+                    let region = completed_region(sudoku, r, c).filter(|_| !self.zen);
+                    if mistake && !self.zen && !self.assisting && self.count_mistake() {
+                        return;
+                    }
+                    if !self.settle_full_board()
+                        && let Some(region) = region
+                    {
+                        self.animate(Effect::Flash(region));
                     }
                 } else {
                     self.last_input_valid = false; // Input is not 1-9
@@ -172,15 +2702,266 @@ impl App {
         }
     }
 
+    /// Whether the selected cell was filled by a reveal hint, which locks it.
+    fn cursor_on_revealed(&mut self) -> bool {
+        let (r, c) = self.cursor_pos;
+        let revealed = self.sudoku.as_ref().is_some_and(|s| s.is_revealed(r, c));
+        if revealed {
+            self.last_input_valid = false;
+            self.status_message = Some("A revealed digit can't be changed".to_string());
+        }
+        revealed
+    }
+
+    /// Ends the game once the board matches the solution. In manual-submit
+    /// mode a full board only says it is ready, right or not, and the game
+    /// waits for the player to submit it. Returns whether the game ended.
+    fn settle_full_board(&mut self) -> bool {
+        let Some(sudoku) = self.sudoku.as_ref() else {
+            return false;
+        };
+        if self.gameplay.manual_submit {
+            if sudoku.progress_percent() == 100 {
+                self.status_message = Some(format!(
+                    "Every cell is filled; press {} to submit",
+                    self.keymap.label(Action::Submit)
+                ));
+            }
+            false
+        } else if sudoku.is_solved() {
+            self.finish_solved_game();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Hands in the board in manual-submit mode. A board that isn't solved
+    /// counts as a wrong submission and adds the configured time penalty,
+    /// without telling which cells are wrong.
+    pub fn submit_board(&mut self) {
+        if self.state != AppState::Running || !self.gameplay.manual_submit {
+            return;
+        }
+        let Some(sudoku) = self.sudoku.as_ref() else {
+            return;
+        };
+        if sudoku.is_solved() {
+            self.status_message = None;
+            self.finish_solved_game();
+            return;
+        }
+        if sudoku.progress_percent() < 100 {
+            self.status_message = Some("Fill every cell before submitting".to_string());
+            return;
+        }
+        self.wrong_submissions += 1;
+        let secs = self.gameplay.wrong_submit_penalty_seconds;
+        self.add_clock_time(secs);
+        self.status_message = Some(match secs {
+            0 => "Not solved yet - keep looking".to_string(),
+            _ => format!("Not solved yet - {secs}s added to the clock"),
+        });
+    }
+
+    /// Counts a digit entered against the solution. With a mistake limit the
+    /// player is told how many are left, and the game is lost on reaching
+    /// it; returns whether it was.
+    fn count_mistake(&mut self) -> bool {
+        self.mistakes += 1;
+        let Some(limit) = self.mistake_limit else {
+            return false;
+        };
+        if self.mistakes < limit {
+            let left = limit - self.mistakes;
+            self.status_message = Some(format!("Mistake! {left} more and the game is over",));
+            return false;
+        }
+        self.fail_game(Outcome::Failed);
+        true
+    }
+
+    /// Ends a game that reached its mistake limit or was given up, recording
+    /// it in the history as lost that way. The board stays on screen for a retry.
+    fn fail_game(&mut self, outcome: Outcome) {
+        self.status_message = self.challenge.take().map(|_| "Challenge lost".to_string());
+        self.record_game(outcome);
+        self.failure = Some(outcome);
+        self.paused_at = None;
+        self.state = AppState::Failed;
+    }
+
+    /// Starts giving up the running game, asking first unless the config
+    /// says not to. Tournament games are forfeited from the menu instead.
+    pub fn request_give_up(&mut self) {
+        if self.state != AppState::Running || self.sudoku.is_none() {
+            return;
+        }
+        if self.tournament.is_some() {
+            self.status_message = Some(format!(
+                "Tournament games are forfeited with {}",
+                self.keymap.label(Action::Menu)
+            ));
+        } else if self.gameplay.confirm_give_up {
+            self.confirm = Some(Confirm::GiveUp);
+        } else {
+            self.give_up();
+        }
+    }
+
+    /// Ends the game as lost, then shows the solution with the wrong entries
+    /// marked. The game is kept in the history as given up, and can be
+    /// retried from its clues like one that ran out of mistakes.
+    pub fn give_up(&mut self) {
+        if self.state != AppState::Running || self.tournament.is_some() {
+            return;
+        }
+        self.fail_game(Outcome::GaveUp);
+        self.show_solution = true;
+        self.peek_until = None;
+        self.hint = None;
+        self.link_from = None;
+    }
+
+    /// Plays the failed puzzle again from its clues, with the mistakes reset.
+    pub fn retry_game(&mut self) {
+        if self.state != AppState::Failed {
+            return;
+        }
+        let Some(sudoku) = self.sudoku.as_ref().map(SudokuGrid::restarted) else {
+            return;
+        };
+        let (daily, rung) = (self.daily_date, self.ladder_rung);
+        self.begin_game(sudoku, Duration::ZERO);
+        self.daily_date = daily;
+        self.ladder_rung = rung;
+        self.resumed_from_save = false;
+    }
+
+    /// Switches the menu's limited-mistakes option for the next games.
+    pub fn toggle_limited_mistakes(&mut self) {
+        self.limited_mistakes = !self.limited_mistakes;
+    }
+
+    /// Switches the menu's memory-mode option for the next games.
+    pub fn toggle_memory_mode(&mut self) {
+        self.memory_mode = !self.memory_mode;
+    }
+
+    /// Switches the menu's zen option for the next games.
+    pub fn toggle_zen_mode(&mut self) {
+        self.zen_mode = !self.zen_mode;
+    }
+
+    /// Switches the menu's time-attack option for the next games.
+    pub fn toggle_time_attack_mode(&mut self) {
+        self.time_attack_mode = !self.time_attack_mode;
+    }
+
+    fn memory_fade(&self) -> Duration {
+        Duration::from_secs(self.gameplay.memory_fade_seconds)
+    }
+
+    /// Shows the digits memory mode has hidden for a moment, from the game's
+    /// budget of recalls.
+    pub fn recall_digits(&mut self) {
+        let Some(memory) = self.memory.as_mut() else {
+            self.status_message =
+                Some("Recalls are for memory mode, M in the menu turns it on".to_string());
+            return;
+        };
+        self.status_message = Some(if memory.recall() {
+            format!("Recalling, {} left", memory.recalls_left)
+        } else {
+            "No recalls left".to_string()
+        });
+    }
+
+    /// Keeps the memory-mode timers up to date with the board.
+    pub fn update_memory(&mut self) {
+        if let (Some(memory), Some(sudoku)) = (self.memory.as_mut(), self.sudoku.as_ref()) {
+            memory.update(sudoku);
+        }
+    }
+
+    /// Whether memory mode hides the entry at (r, c) right now. Givens,
+    /// revealed cells and entries the game marks as invalid stay in view.
+    pub fn digit_hidden(&self, sudoku: &SudokuGrid, r: usize, c: usize) -> bool {
+        self.state == AppState::Running
+            && !self.show_solution
+            && !sudoku.is_fixed(r, c)
+            && !sudoku.is_revealed(r, c)
+            && self
+                .memory
+                .as_ref()
+                .is_some_and(|memory| memory.hidden(r, c))
+            && !self.entry_flagged(sudoku, r, c)
+    }
+
+    /// Switches what the previewed game will check entries against.
+    pub fn toggle_validation(&mut self) {
+        self.validation = self.validation.toggled();
+    }
+
+    /// Whether the entry at (r, c) is marked as invalid: for repeating a
+    /// peer's digit while the live check is on, or for differing from the
+    /// solution when the game checks against it.
+    pub fn entry_flagged(&self, sudoku: &SudokuGrid, r: usize, c: usize) -> bool {
+        !self.zen && flagged(self.game_validation, self.live_check, sudoku, r, c)
+    }
+
+    /// Celebrates and records a game whose board has just been completed.
+    fn finish_solved_game(&mut self) {
+        self.animate(Effect::Celebration);
+        self.record_tournament_result(ResultOutcome::Solved);
+        self.record_challenge_time();
+        // Tournaments and challenges go on to their own standings instead
+        if self.tournament.is_none() && self.challenge.is_none() {
+            self.win = self.win_summary();
+        }
+        self.record_game(Outcome::Solved);
+        if let Some(date) = self.daily_date {
+            self.complete_daily(date);
+        }
+        if let Some(rung) = self.ladder_rung {
+            self.complete_ladder_rung(rung);
+        }
+        self.state = AppState::Solved;
+    }
+
+    /// Empties the selected cell and notes both its entry and the digit typed over it.
+    fn swap_into_notes(&mut self, old: u8, new: u8) {
+        let (r, c) = self.cursor_pos;
+        let Some(sudoku) = self.sudoku.as_mut() else {
+            return;
+        };
+        sudoku.clear_number(r, c);
+        for digit in [old, new] {
+            sudoku.toggle_note(r, c, digit);
+        }
+        self.last_input_valid = true;
+        self.hint = None;
+        self.log_move(r, c, 0);
+        self.status_message = Some(format!("Kept {old} and {new} as notes"));
+    }
+
     /// Clears the current cell. Only active when state is Running.
     pub fn clear_current_cell(&mut self) {
-        if self.state != AppState::Running {
+        let before = self.cursor_cell_state();
+        self.clear_cell();
+        self.push_edit(before);
+    }
+
+    fn clear_cell(&mut self) {
+        if self.state != AppState::Running || self.cursor_on_revealed() {
             return;
         }
+        let (r, c) = self.cursor_pos;
         if let Some(sudoku) = self.sudoku.as_mut() {
-            let (r, c) = self.cursor_pos;
             if sudoku.clear_number(r, c) {
                 self.last_input_valid = true;
+                self.hint = None;
+                self.log_move(r, c, 0);
             } else {
                 self.last_input_valid = false; // Fixed cell
             }
@@ -190,13 +2971,335 @@ impl App {
     }
 
     /// Toggles solution view. Only active when state is Running or Solved.
+    /// In guarded mode a running game only gets a few timed peeks.
     pub fn toggle_solution(&mut self) {
         if (self.state == AppState::Running || self.state == AppState::Solved)
             && self.sudoku.is_some()
+            && self.assists_allowed()
         // Ensure sudoku exists
         {
+            let guarded =
+                self.state == AppState::Running && self.gameplay.peek == PeekMode::Guarded;
+            if guarded && !self.show_solution {
+                if self.peeks_used >= self.gameplay.max_peeks {
+                    self.status_message = Some("No solution peeks left this game".to_string());
+                    return;
+                }
+                self.peeks_used += 1;
+                self.peek_until =
+                    Some(Instant::now() + Duration::from_secs(self.gameplay.peek_seconds));
+            } else {
+                self.peek_until = None;
+            }
             self.show_solution = !self.show_solution;
             self.last_input_valid = true; // Validity doesn't apply to solution/toggling view
         }
     }
+
+    /// Toggles the overlay comparing the board with the solution, leaving the board as it is.
+    pub fn toggle_diff(&mut self) {
+        if self.state == AppState::Running && self.sudoku.is_some() && self.assists_allowed() {
+            self.show_diff = !self.show_diff;
+        }
+    }
+
+    /// Marks the entries that differ from the solution for a few seconds and
+    /// says how many there are, for a quick look without keeping the diff on.
+    pub fn check_progress(&mut self) {
+        if self.state != AppState::Running || !self.assists_allowed() {
+            return;
+        }
+        let Some(sudoku) = &self.sudoku else {
+            return;
+        };
+        let wrong: Vec<(usize, usize)> = (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .filter(|&(r, c)| sudoku.is_wrong(r, c))
+            .collect();
+        self.status_message = Some(match wrong.len() {
+            0 => "Every entry so far is right".to_string(),
+            1 => "1 entry is wrong".to_string(),
+            n => format!("{n} entries are wrong"),
+        });
+        self.checked = Some((Instant::now() + CHECK_SHOWN, wrong));
+    }
+
+    /// Clears the marks of a progress check once their time is up.
+    pub fn update_check(&mut self) {
+        if self
+            .checked
+            .as_ref()
+            .is_some_and(|(until, _)| Instant::now() >= *until)
+        {
+            self.checked = None;
+        }
+    }
+
+    pub fn toggle_legend(&mut self) {
+        self.show_legend = !self.show_legend;
+    }
+
+    /// Opens the popup listing every key and the rules of the game.
+    pub fn open_help(&mut self) {
+        self.show_help = true;
+    }
+
+    /// Toggles the overlay showing how the board on screen was generated.
+    pub fn toggle_debug(&mut self) {
+        self.show_debug = !self.show_debug;
+    }
+
+    /// Starts drawing a chain link at the selected cell, or stops when
+    /// pressed again there. The link is finished from its other cell.
+    pub fn start_link(&mut self) {
+        if self.state != AppState::Running || self.is_paused() {
+            return;
+        }
+        self.link_from = match self.link_from {
+            Some(from) if from == self.cursor_pos => None,
+            _ => Some(self.cursor_pos),
+        };
+    }
+
+    pub fn cancel_link(&mut self) {
+        self.link_from = None;
+    }
+
+    /// Links the cell the link was started at with the selected one for
+    /// `digit`. Drawing a link that is already there removes it, and drawing
+    /// it with the other strength changes it.
+    pub fn finish_link(&mut self, digit: u8, strong: bool) {
+        let Some(from) = self.link_from else {
+            return;
+        };
+        let to = self.cursor_pos;
+        if from == to {
+            self.status_message = Some("A link joins two cells; move to the other one".to_string());
+            return;
+        }
+        self.link_from = None;
+        let kind = if strong { "Strong" } else { "Weak" };
+        let cells = format!(
+            "r{}c{} and r{}c{}",
+            from.0 + 1,
+            from.1 + 1,
+            to.0 + 1,
+            to.1 + 1
+        );
+        let existing = self
+            .links
+            .iter()
+            .position(|link| link.digit == digit && link.other_end(from) == Some(to));
+        self.status_message = Some(match existing {
+            Some(i) if self.links[i].strong == strong => {
+                self.links.remove(i);
+                format!("Removed the link on {digit} between {cells}")
+            }
+            Some(i) => {
+                self.links[i].strong = strong;
+                format!("{kind} link on {digit} between {cells}")
+            }
+            None => {
+                self.links.push(Link {
+                    from,
+                    to,
+                    digit,
+                    strong,
+                });
+                format!("{kind} link on {digit} between {cells}")
+            }
+        });
+    }
+
+    /// Removes every chain link at once.
+    pub fn clear_links(&mut self) {
+        self.link_from = None;
+        self.status_message = Some(match self.links.len() {
+            0 => "There are no links to clear".to_string(),
+            1 => "Cleared 1 link".to_string(),
+            n => format!("Cleared {n} links"),
+        });
+        self.links.clear();
+    }
+
+    /// Lays another save of this puzzle over the board, such as a friend's,
+    /// coloring the cells by who has filled them; None stops comparing.
+    pub fn compare_with(&mut self, path: Option<&str>) {
+        let Some(path) = path else {
+            self.status_message = Some(match self.comparison.take() {
+                Some(comparison) => format!("Stopped comparing with {}", comparison.label),
+                None => "Not comparing with another save".to_string(),
+            });
+            return;
+        };
+        if self.state != AppState::Running || !self.assists_allowed() {
+            return;
+        }
+        let Some(sudoku) = self.sudoku.as_ref() else {
+            return;
+        };
+        self.status_message = Some(match Comparison::open(Path::new(path), sudoku) {
+            Ok(comparison) => {
+                let [_, _, theirs, disagree] = compare::counts(sudoku, &comparison.theirs);
+                let message = format!(
+                    "Comparing with {}: {theirs} only theirs, {disagree} disagree (compare off to stop)",
+                    comparison.label
+                );
+                self.comparison = Some(comparison);
+                message
+            }
+            Err(e) => format!("Could not compare: {e}"),
+        });
+    }
+
+    /// Opens the popup for giving each digit's pencil marks a color of its
+    /// own, such as tracing a chain through the candidates of one digit.
+    pub fn open_note_colors(&mut self) {
+        if self.state == AppState::Running {
+            self.note_color_popup = true;
+        }
+    }
+
+    pub fn close_note_colors(&mut self) {
+        self.note_color_popup = false;
+    }
+
+    /// Gives a digit's pencil marks the next color of the palette, or the
+    /// plain note color again after the last one.
+    pub fn cycle_note_color(&mut self, digit: u8) {
+        let Some(color) = self.note_colors.get_mut(digit as usize - 1) else {
+            return;
+        };
+        let next = match color.and_then(|c| NOTE_PALETTE.iter().position(|&p| p == c)) {
+            None => Some(0),
+            Some(i) => Some(i + 1).filter(|&i| i < NOTE_PALETTE.len()),
+        };
+        *color = next.map(|i| NOTE_PALETTE[i]);
+    }
+
+    pub fn clear_note_colors(&mut self) {
+        self.note_colors = [None; SIZE];
+    }
+
+    /// Switches between the compact grid and the tall one showing pencil marks in full.
+    pub fn toggle_note_grid(&mut self) {
+        self.note_grid = !self.note_grid;
+    }
+
+    /// Turns the board a quarter clockwise on screen.
+    pub fn rotate_view(&mut self) {
+        self.view.rotate();
+    }
+
+    /// Flips the board left to right on screen.
+    pub fn mirror_view(&mut self) {
+        self.view.toggle_mirror();
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.show_focus = !self.show_focus;
+    }
+
+    /// Toggles showing every digit the board still allows in each empty cell
+    /// in place of the pencil marks, which are kept. It is an assist, so
+    /// tournaments keep it off.
+    pub fn toggle_candidates(&mut self) {
+        if self.show_candidates || self.assists_allowed() {
+            self.show_candidates = !self.show_candidates;
+        }
+    }
+
+    pub fn toggle_digits(&mut self) {
+        self.show_digits = !self.show_digits;
+    }
+
+    /// Switches the marking of entries that clash with a peer on or off.
+    pub fn toggle_live_check(&mut self) {
+        self.live_check = !self.live_check;
+        self.status_message = Some(
+            if self.live_check {
+                "Live check on: clashing entries are marked"
+            } else {
+                "Live check off: clashes are left for you to find"
+            }
+            .to_string(),
+        );
+    }
+
+    /// Toggles the panel counting the moves available per technique. It is an
+    /// assist, so tournaments keep it closed.
+    pub fn toggle_radar(&mut self) {
+        if self.show_radar || self.assists_allowed() {
+            self.show_radar = !self.show_radar;
+        }
+    }
+
+    /// Recounts the available moves once the board has stayed the same for a
+    /// moment, so typing several digits in a row costs one count.
+    pub fn update_radar(&mut self) {
+        if !self.show_radar || self.tournament.is_some() || self.state != AppState::Running {
+            return;
+        }
+        let Some(sudoku) = &self.sudoku else {
+            return;
+        };
+        let board = sudoku.board();
+        if self.radar.is_some_and(|(counted, _)| counted == board) {
+            self.radar_changed = None;
+            return;
+        }
+        match self.radar_changed {
+            Some((changed, since)) if changed == board => {
+                if since.elapsed() >= RADAR_DEBOUNCE {
+                    let counts = Solver::new(board, sudoku.variant()).move_counts();
+                    self.radar = Some((board, counts));
+                    self.radar_changed = None;
+                }
+            }
+            _ => self.radar_changed = Some((board, Instant::now())),
+        }
+    }
+
+    /// Hides the solution again once a guarded peek runs out.
+    pub fn update_peek(&mut self) {
+        if self.peek_until.is_some_and(|until| Instant::now() >= until) {
+            self.peek_until = None;
+            self.show_solution = false;
+        }
+    }
+
+    /// Whole seconds left in the current guarded peek, rounded up.
+    pub fn peek_seconds_left(&self) -> Option<u64> {
+        self.peek_until.map(|until| {
+            let left = until.saturating_duration_since(Instant::now());
+            left.as_millis().div_ceil(1000) as u64
+        })
+    }
+}
+
+/// Whether an entry fails the validation of its game.
+fn flagged(
+    validation: Validation,
+    live_check: bool,
+    sudoku: &SudokuGrid,
+    r: usize,
+    c: usize,
+) -> bool {
+    match validation {
+        Validation::Conflicts => live_check && sudoku.clashes(r, c),
+        Validation::Solution => sudoku.is_wrong(r, c),
+    }
+}
+
+/// A row, column or box through (r, c) that is now filled in correctly.
+fn completed_region(sudoku: &SudokuGrid, r: usize, c: usize) -> Option<Region> {
+    let b = (r / BOX_SIZE) * BOX_SIZE + c / BOX_SIZE;
+    [Region::Row(r), Region::Column(c), Region::Box(b)]
+        .into_iter()
+        .find(|region| {
+            region
+                .cells()
+                .iter()
+                .all(|&(r, c)| sudoku.get_cell(r, c, false) == Some(sudoku.solution_value(r, c)))
+        })
 }
@@ -0,0 +1,48 @@
+use crate::puzzle_id::{difficulty_code, difficulty_from_code};
+use crate::sudoku::{Difficulty, SudokuGrid};
+use std::time::Duration;
+
+/// The classic, X and Windoku puzzles of one seed, sharing a single solution
+/// grid. They are played in order and the challenge is scored by the sum of
+/// the solve times.
+pub struct Challenge {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub puzzles: [SudokuGrid; 3],
+    /// Solve times of the puzzles finished so far, in order
+    pub times: Vec<Duration>,
+}
+
+impl Challenge {
+    pub fn new(difficulty: Difficulty, seed: u64) -> Self {
+        Challenge {
+            seed,
+            difficulty,
+            puzzles: SudokuGrid::cross_variant_set(difficulty, seed),
+            times: Vec::new(),
+        }
+    }
+
+    /// Reads a code as printed by `code`, e.g. `M-00c0ffee00c0ffee`, or
+    /// `30-00c0ffee00c0ffee` for 30 clues.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let (prefix, seed) = code.trim().split_once('-')?;
+        let difficulty = difficulty_from_code(prefix)?;
+        Some(Self::new(difficulty, u64::from_str_radix(seed, 16).ok()?))
+    }
+
+    /// Shareable code that rebuilds the same three puzzles.
+    pub fn code(&self) -> String {
+        format!("{}-{:016x}", difficulty_code(self.difficulty), self.seed)
+    }
+
+    /// Index of the next puzzle to play, if any are left.
+    pub fn next_index(&self) -> Option<usize> {
+        let index = self.times.len();
+        (index < self.puzzles.len()).then_some(index)
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.times.iter().sum()
+    }
+}
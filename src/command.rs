@@ -0,0 +1,111 @@
+use crate::sudoku::SIZE;
+
+/// One change typed on the command line, by row and column (0-based).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Entry {
+    Set(usize, usize, u8),
+    Clear(usize, usize),
+}
+
+/// What a command line asks for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Enter(Vec<Entry>),
+    /// Fill every cell left with a single candidate, until none is
+    FillSingles,
+    /// Remove every chain link drawn on the board
+    ClearLinks,
+    /// Show another save of the puzzle over the board, or stop with None
+    Compare(Option<String>),
+    /// Look for the wrong entries of the board as a timed mini-game
+    Hunt,
+    /// End the game as lost and show the solution
+    GiveUp,
+}
+
+/// Reads entries written the way books and videos name cells: `r5c3=7`
+/// enters a digit and `r5c3 clear` (or `r5c3=0`) empties the cell. Several
+/// entries can follow each other, separated by spaces, commas or semicolons.
+/// `singles` (or `fill singles`) asks for the naked singles to be filled, and
+/// `unlink` (or `clear links`) removes the chain links, `compare <file>`
+/// lays another save over the board until `compare off`, `hunt` starts a
+/// search for the board's wrong entries, and `give up` (or `forfeit`) ends
+/// the game as lost.
+pub fn parse(text: &str) -> Result<Command, String> {
+    let text = text.trim().trim_start_matches(':');
+    if let Some((word, path)) = text.split_once(char::is_whitespace)
+        && word.eq_ignore_ascii_case("compare")
+    {
+        return Ok(Command::Compare(
+            Some(path.trim())
+                .filter(|path| !path.eq_ignore_ascii_case("off"))
+                .map(String::from),
+        ));
+    }
+    if text.eq_ignore_ascii_case("compare") {
+        return Err("Give the save to compare with, as in compare friend.json".to_string());
+    }
+    let lowered = text.to_ascii_lowercase();
+    if matches!(
+        lowered.split_whitespace().collect::<Vec<_>>()[..],
+        ["singles"] | ["fill", "singles"]
+    ) {
+        return Ok(Command::FillSingles);
+    }
+    if matches!(
+        lowered.split_whitespace().collect::<Vec<_>>()[..],
+        ["unlink"] | ["clear", "links"]
+    ) {
+        return Ok(Command::ClearLinks);
+    }
+    if lowered == "hunt" {
+        return Ok(Command::Hunt);
+    }
+    if matches!(
+        lowered.split_whitespace().collect::<Vec<_>>()[..],
+        ["give", "up"] | ["forfeit"]
+    ) {
+        return Ok(Command::GiveUp);
+    }
+    let mut tokens = lowered
+        .split(|ch: char| ch.is_whitespace() || ch == ',' || ch == ';')
+        .filter(|token| !token.is_empty())
+        .peekable();
+    let mut entries = Vec::new();
+    while let Some(token) = tokens.next() {
+        let (cell, value) = match token.split_once('=') {
+            Some((cell, value)) => (cell, Some(value)),
+            None => (token, None),
+        };
+        let (r, c) =
+            parse_cell(cell).ok_or_else(|| format!("`{token}` is not a cell like r5c3"))?;
+        let value = match value {
+            Some(value) => value,
+            None if tokens.next_if_eq(&"clear").is_some() => "0",
+            None => return Err(format!("Give `{cell}` a digit, as in {cell}=7, or `clear`")),
+        };
+        entries.push(match value {
+            "0" | "." | "clear" => Entry::Clear(r, c),
+            _ => match value.parse::<u8>() {
+                Ok(digit @ 1..=9) => Entry::Set(r, c, digit),
+                _ => return Err(format!("`{value}` is not a digit 1-9")),
+            },
+        });
+    }
+    if entries.is_empty() {
+        return Err("Nothing to enter".to_string());
+    }
+    Ok(Command::Enter(entries))
+}
+
+/// A cell written `r<row>c<column>`, both counted from 1.
+fn parse_cell(text: &str) -> Option<(usize, usize)> {
+    let (row, column) = text.strip_prefix('r')?.split_once('c')?;
+    let index = |part: &str| {
+        part.parse::<usize>()
+            .ok()
+            .filter(|n| (1..=SIZE).contains(n))
+            .map(|n| n - 1)
+    };
+    Some((index(row)?, index(column)?))
+}
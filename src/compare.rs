@@ -0,0 +1,175 @@
+use crate::save::SavedGame;
+use crate::sudoku::{BOX_SIZE, SIZE, SudokuGrid};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// Who has filled a cell, when two positions of one puzzle are laid side by
+/// side: mine, being played or saved here, and theirs, from another save.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Given,
+    Empty,
+    /// Both hold the same digit
+    Both,
+    Mine,
+    Theirs,
+    /// Both are filled, with different digits
+    Disagree,
+}
+
+/// Another player's position, shown over the board being played.
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub theirs: SudokuGrid,
+    /// File name of their save, to label the comparison
+    pub label: String,
+}
+
+#[derive(Debug)]
+pub enum CompareError {
+    Io(PathBuf, io::Error),
+    NotASave(PathBuf),
+    /// There is no autosave here to compare against
+    NoSave,
+    /// The two saves hold different puzzles
+    OtherPuzzle,
+}
+
+impl fmt::Display for CompareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompareError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            CompareError::NotASave(path) => write!(f, "{} is not a saved game", path.display()),
+            CompareError::NoSave => write!(f, "there is no saved game here to compare with"),
+            CompareError::OtherPuzzle => write!(f, "the saves are of different puzzles"),
+        }
+    }
+}
+
+impl std::error::Error for CompareError {}
+
+/// Reads a saved game, such as an autosave copied from another machine.
+pub fn load(path: &Path) -> Result<SavedGame, CompareError> {
+    let contents = fs::read_to_string(path).map_err(|e| CompareError::Io(path.to_path_buf(), e))?;
+    serde_json::from_str(&contents).map_err(|_| CompareError::NotASave(path.to_path_buf()))
+}
+
+/// Whether both boards have the same variant and clues. Seeds are left out,
+/// so a puzzle typed in by hand matches the generated one it was copied from.
+pub fn same_puzzle(a: &SudokuGrid, b: &SudokuGrid) -> bool {
+    a.variant() == b.variant()
+        && (0..SIZE * SIZE).all(|i| {
+            let (r, c) = (i / SIZE, i % SIZE);
+            a.is_fixed(r, c) == b.is_fixed(r, c)
+                && (!a.is_fixed(r, c) || a.solution_value(r, c) == b.solution_value(r, c))
+        })
+}
+
+pub fn side(mine: &SudokuGrid, theirs: &SudokuGrid, r: usize, c: usize) -> Side {
+    if mine.is_fixed(r, c) {
+        return Side::Given;
+    }
+    match (mine.get_cell(r, c, false), theirs.get_cell(r, c, false)) {
+        (None, None) => Side::Empty,
+        (Some(a), Some(b)) if a == b => Side::Both,
+        (Some(_), Some(_)) => Side::Disagree,
+        (Some(_), None) => Side::Mine,
+        (None, Some(_)) => Side::Theirs,
+    }
+}
+
+impl Comparison {
+    pub fn open(path: &Path, mine: &SudokuGrid) -> Result<Self, CompareError> {
+        let theirs = load(path)?.sudoku;
+        if !same_puzzle(mine, &theirs) {
+            return Err(CompareError::OtherPuzzle);
+        }
+        let label = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        Ok(Comparison { theirs, label })
+    }
+}
+
+/// How many cells fall on each side, in the order both, mine, theirs and
+/// disagree.
+pub fn counts(mine: &SudokuGrid, theirs: &SudokuGrid) -> [usize; 4] {
+    let mut counts = [0; 4];
+    for i in 0..SIZE * SIZE {
+        match side(mine, theirs, i / SIZE, i % SIZE) {
+            Side::Both => counts[0] += 1,
+            Side::Mine => counts[1] += 1,
+            Side::Theirs => counts[2] += 1,
+            Side::Disagree => counts[3] += 1,
+            Side::Given | Side::Empty => {}
+        }
+    }
+    counts
+}
+
+/// The two boards as one plain-text grid for the terminal, followed by the
+/// counts and every disagreement. With `color`, entries only one side has
+/// are green (mine) or cyan (theirs) and disagreements red; without it they
+/// are marked `+`, `-` and `!` after the digit.
+pub fn text(mine: &SudokuGrid, theirs: &SudokuGrid, color: bool) -> String {
+    let separator = vec!["-".repeat(BOX_SIZE * 3 - 1); BOX_SIZE].join("-+-");
+    let mut text = String::new();
+    let mut disagreements = Vec::new();
+    for r in 0..SIZE {
+        if r > 0 && r % BOX_SIZE == 0 {
+            text.push_str(&separator);
+            text.push('\n');
+        }
+        let boxes: Vec<String> = (0..SIZE)
+            .collect::<Vec<_>>()
+            .chunks(BOX_SIZE)
+            .map(|cols| {
+                cols.iter()
+                    .map(|&c| {
+                        let side = side(mine, theirs, r, c);
+                        let digit = mine.get_cell(r, c, false).or(theirs.get_cell(r, c, false));
+                        if side == Side::Disagree {
+                            disagreements.push((r, c));
+                        }
+                        cell_text(side, digit, color)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+        text.push_str(&boxes.join(" | "));
+        text.push('\n');
+    }
+    let [both, only_mine, only_theirs, disagree] = counts(mine, theirs);
+    text.push_str(&format!(
+        "\nSame in both: {both}  Only mine: {only_mine}  Only theirs: {only_theirs}  Disagree: {disagree}\n"
+    ));
+    for (r, c) in disagreements {
+        let digit = |grid: &SudokuGrid| grid.get_cell(r, c, false).unwrap_or(0);
+        text.push_str(&format!(
+            "  r{}c{}: mine {}, theirs {}\n",
+            r + 1,
+            c + 1,
+            digit(mine),
+            digit(theirs)
+        ));
+    }
+    text
+}
+
+fn cell_text(side: Side, digit: Option<u8>, color: bool) -> String {
+    let digit = digit.map_or(".".to_string(), |d| d.to_string());
+    let (code, mark) = match side {
+        Side::Given | Side::Empty => return format!("{digit} "),
+        Side::Both => ("1", ' '),
+        Side::Mine => ("32", '+'),
+        Side::Theirs => ("36", '-'),
+        Side::Disagree => ("31", '!'),
+    };
+    if color {
+        format!("\x1b[{code}m{digit}\x1b[0m ")
+    } else {
+        format!("{digit}{mark}")
+    }
+}
@@ -0,0 +1,671 @@
+use crate::history_store::HistoryBackend;
+use crate::keymap::{Action, Keymap};
+use crate::source::SourceSpec;
+use crate::storage;
+use crate::style_rule::StyleRule;
+use crate::sudoku::{MAX_CLUES, MIN_CUSTOM_CLUES, Symmetry};
+use crate::theme::{Scheme, Theme};
+use crate::view::GridView;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, env, fmt, fs, io, path::PathBuf, str::FromStr, time::Duration};
+
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Settings loaded from `config.toml` in the config directory.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub theme: Theme,
+    /// Built-in colors the theme starts from
+    pub scheme: Scheme,
+    /// `[colors]` overrides, kept to reapply when the scheme is switched
+    pub colors: Vec<(String, Color)>,
+    /// Conditional cell colors applied over the theme, later rules winning
+    pub style_rules: Vec<StyleRule>,
+    /// Replace animations with static messages
+    pub reduced_motion: bool,
+    /// Start games with the enlarged view of the selected cell open
+    pub focus_panel: bool,
+    /// Show a row of clickable buttons for common commands below the game
+    pub soft_keys: bool,
+    /// Start games with the tall grid showing pencil marks in full
+    pub note_grid: bool,
+    /// Start games with the panel counting the moves available per technique
+    pub radar_panel: bool,
+    /// Show the row below the grid counting how many of each digit are left
+    pub digit_panel: bool,
+    /// Start games showing every legal candidate in place of the pencil marks
+    pub auto_candidates: bool,
+    /// Shade the row, column and box of the cursor cell
+    pub peer_highlight: bool,
+    /// Hide the board while a popup has stopped the clock
+    pub blur_behind_popups: bool,
+    /// Mark givens and wrong entries with underlines, reverse video and
+    /// symbols, not by color alone
+    pub colorblind: bool,
+    /// Show the game clock while playing
+    pub show_timer: bool,
+    /// Start games with the board turned or mirrored on screen
+    pub view: GridView,
+    /// Idle time on the menu before the screensaver starts, None to never show it
+    pub screensaver_after: Option<Duration>,
+    pub gameplay: Gameplay,
+    /// Extra puzzle sources offered in the menu after the built-in ones
+    pub sources: Vec<SourceSpec>,
+    /// Game screen keys by action name, as written; checked when the keymap is built
+    pub keys: BTreeMap<String, Vec<String>>,
+    /// Where the game history is kept
+    pub history_backend: HistoryBackend,
+}
+
+/// How the `s` key reveals the solution during a game.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeekMode {
+    /// Show the solution briefly, a limited number of times per game
+    Guarded,
+    /// Show and hide the solution freely
+    #[default]
+    Toggle,
+}
+
+/// How the points a hint costs are worked out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HintPricing {
+    /// Every hint costs the same, apart from escalation
+    #[default]
+    Flat,
+    /// Hints cost more early in the game and for harder techniques
+    Dynamic,
+}
+
+/// What typing a digit over a different entry does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverwriteMode {
+    /// Replace the entry
+    #[default]
+    Replace,
+    /// Refuse until the cell is cleared with `0` or Delete
+    ClearFirst,
+    /// Empty the cell and keep both digits as pencil marks
+    SwapIntoNotes,
+}
+
+/// What entries are checked against while a game is played.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Validation {
+    /// Only digits repeated in a row, column, box or variant region are
+    /// marked, while the live check is on
+    #[default]
+    Conflicts,
+    /// Every entry is marked right away if it differs from the solution
+    Solution,
+}
+
+impl Validation {
+    pub fn name(self) -> &'static str {
+        match self {
+            Validation::Conflicts => "conflicts only",
+            Validation::Solution => "against the solution",
+        }
+    }
+
+    /// The mode's value for `validation` in the config file.
+    pub fn key(self) -> &'static str {
+        match self {
+            Validation::Conflicts => "conflicts",
+            Validation::Solution => "solution",
+        }
+    }
+
+    /// The other mode, for switching on the preview and settings screens.
+    pub fn toggled(self) -> Self {
+        match self {
+            Validation::Conflicts => Validation::Solution,
+            Validation::Solution => Validation::Conflicts,
+        }
+    }
+}
+
+/// How much one undo takes back.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UndoSteps {
+    /// Every key press, so a group action undoes one cell at a time
+    Keystroke,
+    /// Changes in a row to the same cell, such as several pencil marks
+    /// typed into it
+    Cell,
+    /// Every key press, with a group action such as filling the naked
+    /// singles undone as a whole
+    #[default]
+    Action,
+}
+
+impl UndoSteps {
+    pub fn name(self) -> &'static str {
+        match self {
+            UndoSteps::Keystroke => "per keystroke",
+            UndoSteps::Cell => "per cell change",
+            UndoSteps::Action => "per action",
+        }
+    }
+
+    /// The mode's value for `undo_steps` in the config file.
+    pub fn key(self) -> &'static str {
+        match self {
+            UndoSteps::Keystroke => "keystroke",
+            UndoSteps::Cell => "cell",
+            UndoSteps::Action => "action",
+        }
+    }
+
+    /// The mode `delta` places away in the settings screen's order.
+    pub fn cycled(self, delta: isize) -> Self {
+        const ALL: [UndoSteps; 3] = [UndoSteps::Keystroke, UndoSteps::Cell, UndoSteps::Action];
+        let index = ALL.iter().position(|&steps| steps == self).unwrap_or(0) as isize;
+        ALL[(index + delta).rem_euclid(ALL.len() as isize) as usize]
+    }
+}
+
+/// The `[gameplay]` table.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Gameplay {
+    pub peek: PeekMode,
+    /// How long a guarded peek shows the solution
+    pub peek_seconds: u64,
+    /// Guarded peeks allowed per game
+    pub max_peeks: u32,
+    /// Game-clock seconds to wait after a hint before asking for a new one
+    pub hint_cooldown_seconds: u64,
+    /// Extra cost of each further hint, in percent of the first one's cost
+    pub hint_escalation_percent: u32,
+    /// Seconds added to the clock for a new hint, escalating like the score penalty
+    pub hint_time_penalty_seconds: u32,
+    pub hint_pricing: HintPricing,
+    /// Log every removed pencil mark with whether the solver could remove it, for study
+    pub log_eliminations: bool,
+    pub overwrite: OverwriteMode,
+    /// A full, correct board only ends the game once the player submits it
+    pub manual_submit: bool,
+    /// Seconds added to the clock for submitting a board that isn't solved
+    pub wrong_submit_penalty_seconds: u32,
+    /// Mark entries that repeat a digit in their row, column, box or variant region
+    pub live_check: bool,
+    /// Erase a placed digit from the pencil marks of its row, column and box
+    pub auto_clear_notes: bool,
+    /// Start with the menu's limited-mistakes option on
+    pub limited_mistakes: bool,
+    /// Digits against the solution that end a game with limited mistakes
+    pub mistake_limit: u32,
+    /// What entries are checked against, until changed on the preview screen
+    pub validation: Validation,
+    /// Pattern the clues of generated puzzles follow, until changed on the preview screen
+    pub symmetry: Symmetry,
+    pub undo_steps: UndoSteps,
+    /// Move the cursor to the next empty cell after placing a digit
+    pub auto_advance: bool,
+    /// Start with the menu's memory-mode option on
+    pub memory_mode: bool,
+    /// Seconds an entry stays in view in memory mode
+    pub memory_fade_seconds: u64,
+    /// Recalls of the hidden digits a memory-mode game allows
+    pub memory_recalls: u32,
+    /// Start with the menu's zen option on
+    pub zen_mode: bool,
+    /// Start with the menu's time-attack option on
+    pub time_attack: bool,
+    /// Clues of the menu's custom difficulty, from 17 to 60
+    pub custom_clues: u8,
+    /// Ask before giving up a game
+    pub confirm_give_up: bool,
+}
+
+impl Default for Gameplay {
+    fn default() -> Self {
+        Gameplay {
+            peek: PeekMode::Toggle,
+            peek_seconds: 5,
+            max_peeks: 3,
+            hint_cooldown_seconds: 20,
+            hint_escalation_percent: 50,
+            hint_time_penalty_seconds: 0,
+            hint_pricing: HintPricing::Flat,
+            log_eliminations: false,
+            overwrite: OverwriteMode::Replace,
+            manual_submit: false,
+            wrong_submit_penalty_seconds: 60,
+            live_check: true,
+            auto_clear_notes: true,
+            limited_mistakes: false,
+            mistake_limit: 3,
+            validation: Validation::Conflicts,
+            symmetry: Symmetry::None,
+            undo_steps: UndoSteps::Action,
+            auto_advance: false,
+            memory_mode: false,
+            memory_fade_seconds: 3,
+            memory_recalls: 3,
+            zen_mode: false,
+            time_attack: false,
+            custom_clues: 30,
+            confirm_give_up: true,
+        }
+    }
+}
+
+/// The file as written by the user, before validation.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawConfig {
+    /// Per-element color overrides, e.g. `cursor_bg = "#ffd75f"`
+    colors: BTreeMap<String, String>,
+    display: RawDisplay,
+    gameplay: Gameplay,
+    history: RawHistory,
+    sources: Vec<RawSource>,
+    rules: Vec<RawRule>,
+    keys: BTreeMap<String, RawKeys>,
+}
+
+/// A `[keys]` entry: one key, or a list of them.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawKeys {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl RawKeys {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            RawKeys::One(key) => vec![key],
+            RawKeys::Many(keys) => keys,
+        }
+    }
+}
+
+/// A `[[sources]]` table; exactly one of `file`, `url` and `command` is set.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSource {
+    name: String,
+    file: Option<String>,
+    url: Option<String>,
+    command: Option<String>,
+}
+
+impl RawSource {
+    fn into_spec(self) -> Result<SourceSpec, ConfigError> {
+        let name = self.name;
+        match (self.file, self.url, self.command) {
+            (Some(file), None, None) => Ok(SourceSpec::File {
+                name,
+                path: expand_home(&file),
+            }),
+            (None, Some(url), None) => Ok(SourceSpec::Http { name, url }),
+            (None, None, Some(command)) => Ok(SourceSpec::Command { name, command }),
+            _ => Err(ConfigError::InvalidValue {
+                key: "sources",
+                reason: "each source needs exactly one of `file`, `url` or `command`",
+            }),
+        }
+    }
+}
+
+/// A `[[rules]]` table, e.g. `when = "cursor and invalid"` with `bg = "red"`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRule {
+    when: String,
+    fg: Option<String>,
+    bg: Option<String>,
+    bold: Option<bool>,
+}
+
+impl RawRule {
+    fn into_rule(self) -> Result<StyleRule, ConfigError> {
+        let color = |key: &str, value: Option<String>| {
+            value
+                .map(|value| {
+                    Color::from_str(value.trim()).map_err(|_| ConfigError::InvalidColor {
+                        key: format!("rules.{key}"),
+                        value,
+                    })
+                })
+                .transpose()
+        };
+        let fg = color("fg", self.fg)?;
+        let bg = color("bg", self.bg)?;
+        StyleRule::new(&self.when, fg, bg, self.bold).map_err(|e| ConfigError::InvalidRule {
+            when: self.when,
+            reason: e.to_string(),
+        })
+    }
+}
+
+/// Resolves a leading `~/` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawHistory {
+    backend: HistoryBackend,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawDisplay {
+    reduced_motion: bool,
+    focus_panel: bool,
+    soft_keys: bool,
+    note_grid: bool,
+    radar_panel: bool,
+    digit_panel: bool,
+    auto_candidates: bool,
+    peer_highlight: bool,
+    blur_behind_popups: bool,
+    colorblind: bool,
+    show_timer: bool,
+    theme: Scheme,
+    /// Clockwise turn of the board in degrees
+    rotation: u16,
+    mirror: bool,
+    /// Idle seconds on the menu before the screensaver, 0 to turn it off
+    screensaver_seconds: u64,
+}
+
+impl Default for RawDisplay {
+    fn default() -> Self {
+        RawDisplay {
+            reduced_motion: false,
+            focus_panel: false,
+            soft_keys: false,
+            note_grid: false,
+            radar_panel: false,
+            digit_panel: true,
+            auto_candidates: false,
+            peer_highlight: true,
+            blur_behind_popups: true,
+            colorblind: false,
+            show_timer: true,
+            theme: Scheme::Default,
+            rotation: 0,
+            mirror: false,
+            screensaver_seconds: 120,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+    UnknownColorKey(String),
+    InvalidColor {
+        key: String,
+        value: String,
+    },
+    InvalidValue {
+        key: &'static str,
+        reason: &'static str,
+    },
+    InvalidRule {
+        when: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(path, e) => write!(f, "could not read {}: {e}", path.display()),
+            ConfigError::Parse(path, e) => write!(f, "could not parse {}: {e}", path.display()),
+            ConfigError::UnknownColorKey(key) => write!(
+                f,
+                "unknown color key `{key}` in [colors]; valid keys are: {}",
+                Theme::KEYS.join(", ")
+            ),
+            ConfigError::InvalidColor { key, value } => write!(
+                f,
+                "invalid color `{value}` for `{key}`; use a name such as `light_blue`, \
+                 a hex value such as `#ff8800` or an index from 0 to 255"
+            ),
+            ConfigError::InvalidValue { key, reason } => write!(f, "invalid `{key}`: {reason}"),
+            ConfigError::InvalidRule { when, reason } => {
+                write!(f, "invalid rule condition `{when}`: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// `contents` with the line `key = value` in `table`: the key's line is
+/// replaced if it is there, and the table is added at the end if it is not.
+/// Only plain `[table]` headers and `key = ...` lines are recognized.
+fn set_line(contents: &str, table: &str, key: &str, value: &str) -> String {
+    let mut lines: Vec<String> = contents.lines().map(String::from).collect();
+    let entry = format!("{key} = {value}");
+    let header = format!("[{table}]");
+    match lines.iter().position(|line| line.trim() == header) {
+        Some(start) => {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|line| line.trim_start().starts_with('['))
+                .map_or(lines.len(), |i| start + 1 + i);
+            let existing = (start + 1..end).find(|&i| {
+                lines[i]
+                    .split_once('=')
+                    .is_some_and(|(name, _)| name.trim() == key)
+            });
+            match existing {
+                Some(i) => lines[i] = entry,
+                None => lines.insert(start + 1, entry),
+            }
+        }
+        None => {
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(header);
+            lines.push(entry);
+        }
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// `contents` with `key = value` in `table`. The line edit keeps the file's
+/// comments, and is used when the edited file reads back as exactly the
+/// wanted settings; layouts it misreads, such as `[display] # note`,
+/// `[ display ]` or dotted keys, get the parsed settings written back
+/// instead. A file that does not parse is left alone rather than overwritten.
+fn set_key(contents: &str, table: &str, key: &str, value: toml::Value) -> io::Result<String> {
+    let mut document: toml::Table = toml::from_str(contents).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("the config file has an error: {e}"),
+        )
+    })?;
+    let section = document
+        .entry(table)
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("`{table}` is not a table"),
+            )
+        })?;
+    section.insert(key.to_string(), value.clone());
+    let edited = set_line(contents, table, key, &value.to_string());
+    match toml::from_str::<toml::Table>(&edited) {
+        Ok(read_back) if read_back == document => Ok(edited),
+        _ => toml::to_string(&document).map_err(io::Error::other),
+    }
+}
+
+impl Default for Config {
+    /// The settings of an empty config file.
+    fn default() -> Self {
+        Config::from_raw(RawConfig::default()).expect("the default settings are valid")
+    }
+}
+
+impl Config {
+    /// Loads the config file. A missing file gives the defaults.
+    pub fn load() -> Result<Self, ConfigError> {
+        let Some(path) = storage::config_dir().map(|dir| dir.join(CONFIG_FILE)) else {
+            return Ok(Config::default());
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(ConfigError::Read(path, e)),
+        };
+        let raw: RawConfig = toml::from_str(&contents).map_err(|e| ConfigError::Parse(path, e))?;
+        Config::from_raw(raw)
+    }
+
+    /// Writes one choice of the settings screen into the config file, so it
+    /// applies at the next launch. Only that key changes, and the rest of
+    /// the file is kept as written where it can be.
+    pub fn save_setting(table: &str, key: &str, value: toml::Value) -> io::Result<PathBuf> {
+        let dir = storage::config_dir()
+            .ok_or_else(|| io::Error::other("no config directory available"))?;
+        let path = dir.join(CONFIG_FILE);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let contents = set_key(&contents, table, key, value)?;
+        fs::create_dir_all(&dir)?;
+        let tmp = dir.join(format!("{CONFIG_FILE}.tmp"));
+        fs::write(&tmp, contents)?;
+        fs::rename(tmp, &path)?;
+        Ok(path)
+    }
+
+    /// The colors and game keys in effect, written out as `[colors]` and
+    /// `[keys]` tables, so a config file can start from the full list.
+    pub fn colors_and_keys_text(&self) -> String {
+        let quote = |text: String| toml::Value::String(text).to_string();
+        let mut text = String::from("[colors]\n");
+        for key in Theme::KEYS {
+            if let Some(color) = self.theme.color(key) {
+                text.push_str(&format!("{key} = {}\n", quote(color.to_string())));
+            }
+        }
+        text.push_str("\n[keys]\n");
+        let (keymap, _) = Keymap::build(&self.keys);
+        for action in Action::ALL {
+            let keys: Vec<String> = keymap.keys(action).map(|k| quote(k.to_string())).collect();
+            text.push_str(&format!("{} = [{}]\n", action.name(), keys.join(", ")));
+        }
+        text
+    }
+
+    fn from_raw(raw: RawConfig) -> Result<Self, ConfigError> {
+        let mut colors = Vec::new();
+        for (key, value) in raw.colors {
+            if !Theme::KEYS.contains(&key.as_str()) {
+                return Err(ConfigError::UnknownColorKey(key));
+            }
+            let color = Color::from_str(value.trim()).map_err(|_| ConfigError::InvalidColor {
+                key: key.clone(),
+                value,
+            })?;
+            colors.push((key, color));
+        }
+        let theme = raw.display.theme.theme().with_colors(&colors);
+        if raw.gameplay.peek_seconds == 0 {
+            return Err(ConfigError::InvalidValue {
+                key: "gameplay.peek_seconds",
+                reason: "must be at least 1",
+            });
+        }
+        if raw.gameplay.mistake_limit == 0 {
+            return Err(ConfigError::InvalidValue {
+                key: "gameplay.mistake_limit",
+                reason: "must be at least 1",
+            });
+        }
+        if raw.gameplay.memory_fade_seconds == 0 {
+            return Err(ConfigError::InvalidValue {
+                key: "gameplay.memory_fade_seconds",
+                reason: "must be at least 1",
+            });
+        }
+        if !(MIN_CUSTOM_CLUES..=MAX_CLUES).contains(&raw.gameplay.custom_clues) {
+            return Err(ConfigError::InvalidValue {
+                key: "gameplay.custom_clues",
+                reason: "must be from 23 to 60",
+            });
+        }
+        if cfg!(not(feature = "sqlite")) && raw.history.backend == HistoryBackend::Sqlite {
+            return Err(ConfigError::InvalidValue {
+                key: "history.backend",
+                reason: "this build leaves SQLite out; build with `--features sqlite`",
+            });
+        }
+        if !matches!(raw.display.rotation, 0 | 90 | 180 | 270) {
+            return Err(ConfigError::InvalidValue {
+                key: "display.rotation",
+                reason: "must be 0, 90, 180 or 270",
+            });
+        }
+        let sources = raw
+            .sources
+            .into_iter()
+            .map(RawSource::into_spec)
+            .collect::<Result<_, _>>()?;
+        let style_rules = raw
+            .rules
+            .into_iter()
+            .map(RawRule::into_rule)
+            .collect::<Result<_, _>>()?;
+        Ok(Config {
+            theme,
+            scheme: raw.display.theme,
+            colors,
+            style_rules,
+            reduced_motion: raw.display.reduced_motion,
+            focus_panel: raw.display.focus_panel,
+            soft_keys: raw.display.soft_keys,
+            note_grid: raw.display.note_grid,
+            radar_panel: raw.display.radar_panel,
+            digit_panel: raw.display.digit_panel,
+            auto_candidates: raw.display.auto_candidates,
+            peer_highlight: raw.display.peer_highlight,
+            blur_behind_popups: raw.display.blur_behind_popups,
+            colorblind: raw.display.colorblind,
+            show_timer: raw.display.show_timer,
+            view: GridView {
+                quarter_turns: (raw.display.rotation / 90) as u8,
+                mirrored: raw.display.mirror,
+            },
+            screensaver_after: (raw.display.screensaver_seconds > 0)
+                .then(|| Duration::from_secs(raw.display.screensaver_seconds)),
+            gameplay: raw.gameplay,
+            sources,
+            keys: raw
+                .keys
+                .into_iter()
+                .map(|(action, keys)| (action, keys.into_vec()))
+                .collect(),
+            history_backend: raw.history.backend,
+        })
+    }
+}
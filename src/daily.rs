@@ -0,0 +1,148 @@
+use crate::storage;
+use crate::sudoku::{Difficulty, SudokuGrid, Variant};
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+pub const DAILY_FILE: &str = "daily.json";
+
+/// Completing this many days in a row earns a streak freeze.
+pub const DAYS_PER_FREEZE: u32 = 7;
+/// Freezes stop accumulating beyond this many.
+pub const MAX_FREEZES: u32 = 3;
+
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Deterministic seed for a date, so everyone gets the same daily puzzle.
+pub fn seed_for(date: NaiveDate) -> u64 {
+    let days = date.to_epoch_days() as u64;
+    // SplitMix64 finalizer to spread consecutive days across the seed space
+    let mut z = days.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Difficulty of the daily puzzle on each day of the week, Monday first,
+/// building up to the hardest on Sunday. It is the same for everyone, so
+/// players know what to expect of a day's puzzle.
+pub const SCHEDULE: [Difficulty; 7] = [
+    Difficulty::Easy,
+    Difficulty::Easy,
+    Difficulty::Medium,
+    Difficulty::Medium,
+    Difficulty::Hard,
+    Difficulty::Hard,
+    Difficulty::Expert,
+];
+
+pub fn difficulty_for(date: NaiveDate) -> Difficulty {
+    scheduled(date.weekday())
+}
+
+pub fn scheduled(weekday: Weekday) -> Difficulty {
+    SCHEDULE[weekday.num_days_from_monday() as usize]
+}
+
+/// The daily puzzle for a date.
+pub fn puzzle_for(date: NaiveDate) -> SudokuGrid {
+    SudokuGrid::from_seed(difficulty_for(date), Variant::Classic, seed_for(date))
+}
+
+/// Persistent daily streak, including earned streak freezes.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DailyProgress {
+    pub streak: u32,
+    pub best_streak: u32,
+    /// Unused streak freezes
+    pub freezes: u32,
+    /// Last day that kept the streak alive, by completion or freeze
+    pub last_active: Option<NaiveDate>,
+    pub completed_days: Vec<NaiveDate>,
+    /// Missed days that a freeze covered
+    pub frozen_days: Vec<NaiveDate>,
+}
+
+impl DailyProgress {
+    /// Loads the saved progress, brought up to date for `today`.
+    pub fn load(today: NaiveDate) -> Self {
+        let mut progress: Self = storage::read_json(DAILY_FILE).unwrap_or_default();
+        progress.refresh(today);
+        progress
+    }
+
+    pub fn write(&self) -> io::Result<()> {
+        storage::write_json(DAILY_FILE, self)
+    }
+
+    pub fn completed(&self, date: NaiveDate) -> bool {
+        self.completed_days.contains(&date)
+    }
+
+    /// Spends freezes on days missed since the streak was last active, or
+    /// breaks the streak if there aren't enough. Today itself is never missed.
+    pub fn refresh(&mut self, today: NaiveDate) {
+        let Some(last) = self.last_active else {
+            return;
+        };
+        let missed = (today - last).num_days() - 1;
+        if missed <= 0 || self.streak == 0 {
+            return;
+        }
+        let missed = missed as u32;
+        if self.freezes >= missed {
+            self.freezes -= missed;
+            self.frozen_days
+                .extend(last.iter_days().skip(1).take(missed as usize));
+            self.last_active = today.pred_opt();
+        } else {
+            self.streak = 0;
+        }
+    }
+
+    /// Both records of daily play in one: every completed and frozen day of
+    /// either, and the current streak of the one active more recently.
+    pub fn merged(&self, other: &Self) -> Self {
+        let recent = if other.last_active > self.last_active {
+            other
+        } else {
+            self
+        };
+        let days = |a: &[NaiveDate], b: &[NaiveDate]| {
+            let mut days: Vec<NaiveDate> = a.iter().chain(b).copied().collect();
+            days.sort_unstable();
+            days.dedup();
+            days
+        };
+        DailyProgress {
+            streak: recent.streak,
+            best_streak: self.best_streak.max(other.best_streak),
+            freezes: recent.freezes,
+            last_active: recent.last_active,
+            completed_days: days(&self.completed_days, &other.completed_days),
+            frozen_days: days(&self.frozen_days, &other.frozen_days),
+        }
+    }
+
+    /// Records a solved daily puzzle. Returns true if a freeze was earned.
+    pub fn complete(&mut self, date: NaiveDate) -> bool {
+        self.refresh(date);
+        if self.completed(date) {
+            return false;
+        }
+        self.completed_days.push(date);
+        if self.last_active.is_some_and(|last| last >= date) {
+            return false;
+        }
+        self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+        self.last_active = Some(date);
+        if self.streak.is_multiple_of(DAYS_PER_FREEZE) && self.freezes < MAX_FREEZES {
+            self.freezes += 1;
+            return true;
+        }
+        false
+    }
+}
@@ -0,0 +1,90 @@
+use crate::daily;
+use crate::puzzle_id::{self, PuzzleId};
+use crate::sudoku::{BOX_SIZE, SIZE, SudokuGrid};
+use chrono::NaiveDate;
+
+/// Renders the puzzle's clues as plain text, with `.` for empty cells.
+pub fn puzzle_text(sudoku: &SudokuGrid) -> String {
+    let grid = grid_text(|r, c| sudoku.is_fixed(r, c).then(|| sudoku.solution_value(r, c)));
+    with_footer(grid, sudoku)
+}
+
+/// Renders the complete solution as plain text.
+pub fn solution_text(sudoku: &SudokuGrid) -> String {
+    with_footer(grid_text(|r, c| Some(sudoku.solution_value(r, c))), sudoku)
+}
+
+/// The clues on one line of 81 cells, with `.` for empty ones, the form most
+/// sites and solvers take.
+pub fn puzzle_line(sudoku: &SudokuGrid) -> String {
+    (0..SIZE * SIZE)
+        .map(|i| {
+            let (r, c) = (i / SIZE, i % SIZE);
+            if sudoku.is_fixed(r, c) {
+                char::from(b'0' + sudoku.solution_value(r, c))
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+/// Renders the board as it stands, clues and entries alike, as plain text.
+pub fn progress_text(sudoku: &SudokuGrid) -> String {
+    with_footer(grid_text(|r, c| sudoku.get_cell(r, c, false)), sudoku)
+}
+
+/// Appends the puzzle ID and app version under an exported grid, so a shared
+/// puzzle can be reloaded exactly (its ID or the whole export can be pasted
+/// into the import screen).
+fn with_footer(mut text: String, sudoku: &SudokuGrid) -> String {
+    text.push_str(&format!(
+        "{} {}  (tui_sudoku {})\n",
+        puzzle_id::FOOTER_LABEL,
+        PuzzleId::of(sudoku),
+        env!("CARGO_PKG_VERSION")
+    ));
+    text
+}
+
+fn grid_text(cell: impl Fn(usize, usize) -> Option<u8>) -> String {
+    let separator = vec!["-".repeat(BOX_SIZE * 2 - 1); BOX_SIZE].join("-+-");
+    let mut text = String::new();
+    for r in 0..SIZE {
+        if r > 0 && r % BOX_SIZE == 0 {
+            text.push_str(&separator);
+            text.push('\n');
+        }
+        let boxes: Vec<String> = (0..SIZE)
+            .collect::<Vec<_>>()
+            .chunks(BOX_SIZE)
+            .map(|cols| {
+                cols.iter()
+                    .map(|&c| cell(r, c).map_or(".".to_string(), |d| d.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+        text.push_str(&boxes.join(" | "));
+        text.push('\n');
+    }
+    text
+}
+
+/// Today's daily puzzle followed by yesterday's solution, for mail or motd.
+pub fn daily_text(today: NaiveDate) -> String {
+    let puzzle = daily::puzzle_for(today);
+    let mut text = format!(
+        "Daily Sudoku - {} ({:?})\n\n{}",
+        today.format("%A %Y-%m-%d"),
+        puzzle.difficulty(),
+        puzzle_text(&puzzle)
+    );
+    if let Some(yesterday) = today.pred_opt() {
+        text.push_str(&format!(
+            "\nSolution to {yesterday}:\n\n{}",
+            solution_text(&daily::puzzle_for(yesterday))
+        ));
+    }
+    text
+}
@@ -0,0 +1,133 @@
+use crate::history_store;
+use crate::save::{Comment, Elimination, Move};
+use crate::sudoku::{Difficulty, SIZE, SudokuGrid, complete_grid};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+pub const HISTORY_FILE: &str = "history.json";
+
+/// Solved games of a difficulty needed before their median is used as a pace.
+const PACE_MIN_GAMES: usize = 3;
+
+/// How long the solver may spend finishing an abandoned board.
+const SOLVER_TIME_LIMIT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Solved,
+    Abandoned,
+    /// Lost by reaching the mistake limit
+    Failed,
+    /// Given up, with the solution shown
+    GaveUp,
+    /// Lost by running out of time in time attack
+    TimedOut,
+}
+
+impl Outcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Solved => "Solved",
+            Outcome::Abandoned => "Abandoned",
+            Outcome::Failed => "Out of mistakes",
+            Outcome::GaveUp => "Gave up",
+            Outcome::TimedOut => "Out of time",
+        }
+    }
+}
+
+/// A finished (or given up) game kept in the history archive.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameRecord {
+    /// Wall-clock time the game ended (unix seconds)
+    pub finished_at: i64,
+    /// Board as it was left when the game ended
+    pub sudoku: SudokuGrid,
+    pub elapsed_secs: u64,
+    pub outcome: Outcome,
+    pub comments: Vec<Comment>,
+    /// Score points lost to hints
+    #[serde(default)]
+    pub penalty: u32,
+    /// Hints asked for, reveals included
+    #[serde(default)]
+    pub hints_used: u32,
+    /// Digits entered against the solution
+    #[serde(default)]
+    pub mistakes: u32,
+    /// Date of the daily puzzle, if this was one
+    #[serde(default)]
+    pub daily: Option<NaiveDate>,
+    /// Timestamped moves, for replays and speed metrics
+    #[serde(default)]
+    pub moves: Vec<Move>,
+    /// Logged pencil-mark removals, when elimination logging was on
+    #[serde(default)]
+    pub eliminations: Vec<Elimination>,
+    /// Abandoned board as finished by the solver, kept for later study
+    #[serde(default)]
+    pub solver_finish: Option<[[u8; SIZE]; SIZE]>,
+}
+
+/// Loads all records, oldest first, from the configured backend. A missing
+/// or unreadable history is an empty history.
+pub fn load() -> Vec<GameRecord> {
+    history_store::current().load().unwrap_or_default()
+}
+
+/// Median time of the solved games of a difficulty, once there are enough of them.
+pub fn median_solve_secs(records: &[GameRecord], difficulty: Difficulty) -> Option<u64> {
+    let mut times: Vec<u64> = records
+        .iter()
+        .filter(|record| {
+            record.outcome == Outcome::Solved && record.sudoku.difficulty() == difficulty
+        })
+        .map(|record| record.elapsed_secs)
+        .collect();
+    if times.len() < PACE_MIN_GAMES {
+        return None;
+    }
+    times.sort_unstable();
+    let middle = times.len() / 2;
+    Some(if times.len().is_multiple_of(2) {
+        (times[middle - 1] + times[middle]) / 2
+    } else {
+        times[middle]
+    })
+}
+
+/// Both histories in one, oldest first. A game in both, ended at the same
+/// moment on the same board, is kept once.
+pub fn merge(mut records: Vec<GameRecord>, other: Vec<GameRecord>) -> Vec<GameRecord> {
+    for record in other {
+        if !records.iter().any(|kept| {
+            kept.finished_at == record.finished_at && kept.sudoku.board() == record.sudoku.board()
+        }) {
+            records.push(record);
+        }
+    }
+    records.sort_by_key(|record| record.finished_at);
+    records
+}
+
+/// Appends a record to the history.
+pub fn append(record: GameRecord) -> io::Result<()> {
+    history_store::current().append(&record)
+}
+
+/// Archives an abandoned game on a background thread after letting the solver
+/// finish the board. Correct entries are kept and wrong ones replaced. If the
+/// solver runs out of time the game is archived without its completion.
+/// The thread's result tells whether the solver finished.
+pub fn append_solver_finished(mut record: GameRecord) -> JoinHandle<io::Result<bool>> {
+    thread::spawn(move || {
+        let sudoku = &record.sudoku;
+        let deadline = Instant::now() + SOLVER_TIME_LIMIT;
+        record.solver_finish = complete_grid(sudoku.correct_entries(), sudoku.variant(), deadline);
+        let finished = record.solver_finish.is_some();
+        append(record).map(|_| finished)
+    })
+}
@@ -0,0 +1,203 @@
+use crate::history::{GameRecord, HISTORY_FILE};
+use crate::storage;
+use serde::Deserialize;
+use std::io;
+use std::sync::OnceLock;
+
+/// Where the game history is kept. Every backend hands the records back
+/// oldest first, and a history never written to reads as empty.
+pub trait HistoryStore: Sync {
+    fn load(&self) -> io::Result<Vec<GameRecord>>;
+    fn append(&self, record: &GameRecord) -> io::Result<()>;
+    /// Replaces every record with `records`, such as the history a profile
+    /// import settled on.
+    fn replace(&self, records: &[GameRecord]) -> io::Result<()>;
+}
+
+/// The backend picked in `[history]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryBackend {
+    /// One JSON file, rewritten on every game
+    #[default]
+    Json,
+    /// An SQLite database, needing the `sqlite` feature and the `sqlite3` command
+    Sqlite,
+}
+
+/// Set once at startup from the config; see `use_backend`.
+static BACKEND: OnceLock<HistoryBackend> = OnceLock::new();
+
+/// Makes the history read from and written to the backend for the rest of
+/// the run. Only the first call counts.
+pub fn use_backend(backend: HistoryBackend) {
+    let _ = BACKEND.set(backend);
+}
+
+/// The store of the backend in use, the JSON file unless another was picked.
+pub fn current() -> &'static dyn HistoryStore {
+    match BACKEND.get().copied().unwrap_or_default() {
+        #[cfg(feature = "sqlite")]
+        HistoryBackend::Sqlite => &sqlite::SqliteStore,
+        // Without the feature the config refuses `sqlite`
+        _ => &JsonStore,
+    }
+}
+
+/// The history as one JSON array in `history.json`.
+pub struct JsonStore;
+
+impl HistoryStore for JsonStore {
+    fn load(&self) -> io::Result<Vec<GameRecord>> {
+        Ok(storage::read_json(HISTORY_FILE).unwrap_or_default())
+    }
+
+    fn append(&self, record: &GameRecord) -> io::Result<()> {
+        let mut records = self.load()?;
+        records.push(record.clone());
+        storage::write_json(HISTORY_FILE, &records)
+    }
+
+    fn replace(&self, records: &[GameRecord]) -> io::Result<()> {
+        storage::write_json(HISTORY_FILE, &records)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::{HistoryStore, JsonStore};
+    use crate::history::GameRecord;
+    use crate::storage;
+    use std::fs;
+    use std::io::{self, Write};
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Stdio};
+
+    const DATABASE_FILE: &str = "history.db";
+
+    /// A row per game, its figures in columns for queries of your own and
+    /// the whole record as JSON in `record`.
+    const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS games (
+        finished_at INTEGER NOT NULL,
+        difficulty TEXT NOT NULL,
+        variant TEXT NOT NULL,
+        outcome TEXT NOT NULL,
+        elapsed_secs INTEGER NOT NULL,
+        hints_used INTEGER NOT NULL,
+        mistakes INTEGER NOT NULL,
+        daily TEXT,
+        record TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS games_by_difficulty ON games (difficulty, outcome);\n";
+
+    /// The history in `history.db`, read and written through the `sqlite3`
+    /// command. A database made where the JSON file already holds games starts
+    /// with a copy of them.
+    pub struct SqliteStore;
+
+    impl HistoryStore for SqliteStore {
+        fn load(&self) -> io::Result<Vec<GameRecord>> {
+            let Some(path) = database()? else {
+                return Ok(Vec::new());
+            };
+            let sql = format!("{SCHEMA}SELECT record FROM games ORDER BY finished_at, rowid;");
+            let output = run(&path, &sql)?;
+            output
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| serde_json::from_str(line).map_err(io::Error::other))
+                .collect()
+        }
+
+        fn append(&self, record: &GameRecord) -> io::Result<()> {
+            let Some(path) = database()? else {
+                return Ok(());
+            };
+            run(&path, &format!("{SCHEMA}{}", insert(record)?)).map(|_| ())
+        }
+
+        fn replace(&self, records: &[GameRecord]) -> io::Result<()> {
+            let Some(path) = database()? else {
+                return Ok(());
+            };
+            let mut sql = format!("BEGIN;\n{SCHEMA}DELETE FROM games;\n");
+            for record in records {
+                sql.push_str(&insert(record)?);
+            }
+            sql.push_str("COMMIT;\n");
+            run(&path, &sql).map(|_| ())
+        }
+    }
+
+    /// The database, created and filled from the JSON history on first use.
+    /// None in guest mode.
+    fn database() -> io::Result<Option<PathBuf>> {
+        let Some(dir) = storage::data_dir() else {
+            return Ok(None);
+        };
+        let path = dir.join(DATABASE_FILE);
+        if !path.exists() {
+            fs::create_dir_all(&dir)?;
+            let mut sql = format!("BEGIN;\n{SCHEMA}");
+            for record in JsonStore.load()? {
+                sql.push_str(&insert(&record)?);
+            }
+            sql.push_str("COMMIT;\n");
+            // A failed copy is started over next time rather than half kept
+            if let Err(e) = run(&path, &sql) {
+                let _ = fs::remove_file(&path);
+                return Err(e);
+            }
+        }
+        Ok(Some(path))
+    }
+
+    fn insert(record: &GameRecord) -> io::Result<String> {
+        let json = serde_json::to_string(record).map_err(io::Error::other)?;
+        Ok(format!(
+            "INSERT INTO games VALUES ({}, {}, {}, {}, {}, {}, {}, {}, {});\n",
+            record.finished_at,
+            quoted(&format!("{:?}", record.sudoku.difficulty())),
+            quoted(record.sudoku.variant().name()),
+            quoted(&format!("{:?}", record.outcome)),
+            record.elapsed_secs,
+            record.hints_used,
+            record.mistakes,
+            record
+                .daily
+                .map_or_else(|| "NULL".to_string(), |date| quoted(&date.to_string())),
+            quoted(&json)
+        ))
+    }
+
+    /// An SQL string literal.
+    fn quoted(text: &str) -> String {
+        format!("'{}'", text.replace('\'', "''"))
+    }
+
+    /// Runs statements against the database, returning what they print.
+    fn run(path: &Path, sql: &str) -> io::Result<String> {
+        let mut child = Command::new("sqlite3")
+            .args(["-batch", "-bail"])
+            .arg(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| io::Error::other(format!("could not run sqlite3: {e}")))?;
+        // Statements that print much take little input, so writing it all
+        // first can't leave both sides waiting on a full pipe
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(sql.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(io::Error::other(format!(
+                "sqlite3 failed: {}",
+                stderr.trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
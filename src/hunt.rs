@@ -0,0 +1,218 @@
+use crate::sudoku::{Difficulty, SIZE, SudokuGrid, Variant};
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Time to find every wrong entry of a round.
+pub const TIME_LIMIT: Duration = Duration::from_secs(90);
+/// Points for each second left when the last wrong entry is found.
+const POINTS_PER_SECOND: u32 = 10;
+/// Points lost for each correct cell picked as the mistake.
+const MISS_PENALTY: u32 = 50;
+/// Share of a generated puzzle's open cells filled in, in percent, so the
+/// position looks like a game in progress.
+const FILLED_PERCENT: u32 = 60;
+
+/// A "find the mistake" round: a position with wrong entries among correct
+/// ones, to be picked out before the time runs out.
+pub struct Hunt {
+    pub board: SudokuGrid,
+    /// Wrong entries not found yet
+    pub hidden: Vec<(usize, usize)>,
+    pub found: Vec<(usize, usize)>,
+    /// Correct cells picked as the mistake
+    pub misses: Vec<(usize, usize)>,
+    pub cursor: (usize, usize),
+    started: Instant,
+    /// Time spent, once the round is over
+    pub finished: Option<Duration>,
+    /// Points of the rounds played so far, this one once it is over
+    pub score: u32,
+    pub rounds: u32,
+    /// Difficulty of the generated positions; None for a board of the
+    /// player's, which has no next round
+    pub difficulty: Option<Difficulty>,
+    /// The next generated position, being made while this round plays
+    next: Option<JoinHandle<Hunt>>,
+    /// Whether the next round was asked for before it was ready
+    next_asked: bool,
+}
+
+impl Hunt {
+    /// A round on a generated position, made on a background thread so the
+    /// menu or the finished round stays responsive. Start it with `started`.
+    pub fn in_background(difficulty: Difficulty) -> JoinHandle<Self> {
+        thread::spawn(move || Hunt::generated(difficulty, rand::random()))
+    }
+
+    /// A round on a generated position of the difficulty.
+    pub fn generated(difficulty: Difficulty, seed: u64) -> Self {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let puzzle = SudokuGrid::from_seed(difficulty, Variant::Classic, rng.random());
+        let mut hunt = Hunt::new(mutated(&puzzle, rng));
+        hunt.difficulty = Some(difficulty);
+        hunt
+    }
+
+    /// A round on a board of the player's, if it holds any wrong entry.
+    pub fn from_board(board: &SudokuGrid) -> Option<Self> {
+        (board.wrong_entry_count() > 0).then(|| Hunt::new(board.clone()))
+    }
+
+    fn new(board: SudokuGrid) -> Self {
+        let hidden = (0..SIZE * SIZE)
+            .map(|i| (i / SIZE, i % SIZE))
+            .filter(|&(r, c)| board.is_wrong(r, c))
+            .collect();
+        Hunt {
+            board,
+            hidden,
+            found: Vec::new(),
+            misses: Vec::new(),
+            cursor: (0, 0),
+            started: Instant::now(),
+            finished: None,
+            score: 0,
+            rounds: 1,
+            difficulty: None,
+            next: None,
+            next_asked: false,
+        }
+    }
+
+    /// The round with its clock starting now, as it is shown, and for a
+    /// generated one the position after it under way.
+    pub fn started(self) -> Self {
+        Hunt {
+            started: Instant::now(),
+            next: self.difficulty.map(Hunt::in_background),
+            ..self
+        }
+    }
+
+    /// Moves on to a fresh generated position, keeping the score. One still
+    /// being made is moved on to by `update` once it is done; returns
+    /// whether the new round has already begun.
+    pub fn next_round(&mut self) -> bool {
+        if self.difficulty.is_none() {
+            return false;
+        }
+        self.next_asked = true;
+        self.take_next()
+    }
+
+    /// Swaps in the next position if it was asked for and is ready. Should
+    /// its thread have failed, another is started.
+    fn take_next(&mut self) -> bool {
+        if !self.next_asked {
+            return false;
+        }
+        match self.next.take() {
+            Some(next) if next.is_finished() => match next.join() {
+                Ok(next) => {
+                    *self = Hunt {
+                        score: self.score,
+                        rounds: self.rounds + 1,
+                        ..next.started()
+                    };
+                    return true;
+                }
+                Err(_) => self.next = self.difficulty.map(Hunt::in_background),
+            },
+            Some(next) => self.next = Some(next),
+            None => self.next = self.difficulty.map(Hunt::in_background),
+        }
+        false
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.finished.is_some()
+    }
+
+    pub fn time_left(&self) -> Duration {
+        let spent = self.finished.unwrap_or_else(|| self.started.elapsed());
+        TIME_LIMIT.saturating_sub(spent)
+    }
+
+    /// Ends the round once the time has run out, showing what was missed,
+    /// and begins the next one asked for once it is ready. Returns whether
+    /// a new round began.
+    pub fn update(&mut self) -> bool {
+        if !self.is_over() && self.time_left().is_zero() {
+            self.finished = Some(TIME_LIMIT);
+        }
+        self.take_next()
+    }
+
+    pub fn move_cursor(&mut self, dr: isize, dc: isize) {
+        let (r, c) = self.cursor;
+        self.cursor = (
+            (r as isize + dr).rem_euclid(SIZE as isize) as usize,
+            (c as isize + dc).rem_euclid(SIZE as isize) as usize,
+        );
+    }
+
+    /// Picks the cell under the cursor as a mistake. Returns whether it was
+    /// one; finding the last ends the round and scores it.
+    pub fn pick(&mut self) -> bool {
+        let cell = self.cursor;
+        if self.is_over() || self.found.contains(&cell) || self.misses.contains(&cell) {
+            return false;
+        }
+        let Some(index) = self.hidden.iter().position(|&hidden| hidden == cell) else {
+            if self.board.get_cell(cell.0, cell.1, false).is_some() {
+                self.misses.push(cell);
+            }
+            return false;
+        };
+        self.found.push(self.hidden.remove(index));
+        if self.hidden.is_empty() {
+            let left = self.time_left().as_secs() as u32;
+            self.score +=
+                (left * POINTS_PER_SECOND).saturating_sub(self.misses.len() as u32 * MISS_PENALTY);
+            self.finished = Some(self.started.elapsed());
+        }
+        true
+    }
+}
+
+/// The puzzle partly filled from its solution, with one of the entries
+/// replaced by a wrong digit. The digit clashes with no peer, so the mistake
+/// can only be told by working the position, not by spotting a repeat.
+fn mutated<R: Rng + ?Sized>(puzzle: &SudokuGrid, rng: &mut R) -> SudokuGrid {
+    let mut board = puzzle.clone();
+    let mut open: Vec<(usize, usize)> = (0..SIZE * SIZE)
+        .map(|i| (i / SIZE, i % SIZE))
+        .filter(|&(r, c)| !puzzle.is_fixed(r, c))
+        .collect();
+    open.shuffle(rng);
+    let filled = open.len() * FILLED_PERCENT as usize / 100;
+    for &(r, c) in &open[..filled] {
+        board.set_number(r, c, board.solution_value(r, c));
+    }
+    // Emptying entries one at a time frees digits, until one can go wrong
+    let mut entries = open[..filled].to_vec();
+    while !entries.is_empty() {
+        let wrong: Vec<(usize, usize, u8)> = entries
+            .iter()
+            .flat_map(|&(r, c)| (1..=9).map(move |digit| (r, c, digit)))
+            .filter(|&(r, c, digit)| {
+                digit != board.solution_value(r, c) && board.is_valid_move(r, c, digit)
+            })
+            .collect();
+        if let Some(&(r, c, digit)) = wrong.choose(rng) {
+            board.set_number(r, c, digit);
+            return board;
+        }
+        if let Some((r, c)) = entries.pop() {
+            board.clear_number(r, c);
+        }
+    }
+    // Too few entries to hide a mistake among; any wrong digit will do
+    if let Some(&(r, c)) = open.first() {
+        let digit = board.solution_value(r, c) % 9 + 1;
+        board.set_number(r, c, digit);
+    }
+    board
+}
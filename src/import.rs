@@ -0,0 +1,122 @@
+use crate::sudoku::{SIZE, Variant, conflicting_cells, count_solutions};
+
+/// Characters read as an empty cell. Everything else that isn't 1-9 is ignored,
+/// so grid lines like `|`, `+` and `---` can stay in the paste.
+const BLANKS: &[char] = &['0', '.', '*', '_', 'x', 'X', '?'];
+
+/// Result of reading a pasted puzzle, along with anything that had to be guessed.
+pub struct ParsedPuzzle {
+    pub clues: [[u8; SIZE]; SIZE],
+    pub warnings: Vec<String>,
+}
+
+/// Reads a loosely formatted puzzle: one row per line with any spacing or grid
+/// decoration, or all 81 cells run together. Rows of the wrong length are padded
+/// or cut to fit and reported as warnings for the player to fix.
+pub fn parse_loose(text: &str) -> Option<ParsedPuzzle> {
+    let rows: Vec<Vec<u8>> = text
+        .lines()
+        // Skip titles and notes; a lone `x` may still mark a blank
+        .filter(|line| !has_word(line))
+        .map(|line| {
+            line.chars()
+                .filter_map(|ch| match ch {
+                    '1'..='9' => ch.to_digit(10).map(|d| d as u8),
+                    _ if BLANKS.contains(&ch) => Some(0),
+                    _ => None,
+                })
+                .collect::<Vec<u8>>()
+        })
+        // Separator lines such as `------+------` carry no cells
+        .filter(|row| !row.is_empty())
+        .collect();
+    let total: usize = rows.iter().map(Vec::len).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut warnings = Vec::new();
+    let cells: Vec<u8> = if total == SIZE * SIZE || rows.len() != SIZE {
+        // Ignore the line structure and fill the board in reading order
+        if total != SIZE * SIZE {
+            warnings.push(format!(
+                "Found {total} cells in {} lines instead of {}; filled in reading order",
+                rows.len(),
+                SIZE * SIZE
+            ));
+        }
+        rows.into_iter().flatten().collect()
+    } else {
+        rows.into_iter()
+            .enumerate()
+            .flat_map(|(r, mut row)| {
+                if row.len() != SIZE {
+                    warnings.push(format!("Row {} had {} cells", r + 1, row.len()));
+                }
+                row.resize(SIZE, 0);
+                row
+            })
+            .collect()
+    };
+
+    let mut clues = [[0; SIZE]; SIZE];
+    for (i, value) in cells.into_iter().take(SIZE * SIZE).enumerate() {
+        clues[i / SIZE][i % SIZE] = value;
+    }
+    Some(ParsedPuzzle { clues, warnings })
+}
+
+fn has_word(line: &str) -> bool {
+    line.as_bytes()
+        .windows(2)
+        .any(|pair| pair.iter().all(u8::is_ascii_alphabetic))
+}
+
+/// A parsed paste under review, where misread cells can be fixed before playing.
+pub struct ImportReview {
+    pub clues: [[u8; SIZE]; SIZE],
+    pub cursor: (usize, usize),
+    pub warnings: Vec<String>,
+    /// Clues that clash with another clue
+    pub conflicts: Vec<(usize, usize)>,
+    /// Number of solutions, capped at 2
+    pub solutions: usize,
+}
+
+impl ImportReview {
+    pub fn new(parsed: ParsedPuzzle) -> Self {
+        let mut review = ImportReview {
+            clues: parsed.clues,
+            cursor: (0, 0),
+            warnings: parsed.warnings,
+            conflicts: Vec::new(),
+            solutions: 0,
+        };
+        review.recheck();
+        review
+    }
+
+    /// Sets the cell under the cursor (0 clears it).
+    pub fn set(&mut self, digit: u8) {
+        let (r, c) = self.cursor;
+        self.clues[r][c] = digit;
+        self.recheck();
+    }
+
+    pub fn move_cursor(&mut self, dr: isize, dc: isize) {
+        let (r, c) = self.cursor;
+        self.cursor = (
+            (r as isize + dr).rem_euclid(SIZE as isize) as usize,
+            (c as isize + dc).rem_euclid(SIZE as isize) as usize,
+        );
+    }
+
+    pub fn clue_count(&self) -> usize {
+        self.clues.iter().flatten().filter(|&&v| v != 0).count()
+    }
+
+    fn recheck(&mut self) {
+        self.conflicts = conflicting_cells(&self.clues, Variant::Classic);
+        self.solutions = count_solutions(self.clues, Variant::Classic, 2).0;
+    }
+}
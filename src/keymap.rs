@@ -0,0 +1,633 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{collections::BTreeMap, fmt};
+
+/// A command of the game screen that can be bound to keys in `[keys]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    Quit,
+    Up,
+    Down,
+    Left,
+    Right,
+    Clear,
+    Undo,
+    Redo,
+    Hint,
+    Reveal,
+    Comment,
+    Command,
+    Copy,
+    CopyBoard,
+    Diff,
+    Check,
+    LiveCheck,
+    Legend,
+    Help,
+    Focus,
+    Radar,
+    Digits,
+    Candidates,
+    NoteGrid,
+    NoteColors,
+    Link,
+    Rotate,
+    Mirror,
+    Debug,
+    Notes,
+    Pause,
+    Solution,
+    Submit,
+    Recall,
+    GiveUp,
+    Menu,
+}
+
+impl Action {
+    pub const ALL: [Action; 36] = [
+        Action::Quit,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Clear,
+        Action::Undo,
+        Action::Redo,
+        Action::Hint,
+        Action::Reveal,
+        Action::Comment,
+        Action::Command,
+        Action::Copy,
+        Action::CopyBoard,
+        Action::Diff,
+        Action::Check,
+        Action::LiveCheck,
+        Action::Legend,
+        Action::Help,
+        Action::Focus,
+        Action::Radar,
+        Action::Digits,
+        Action::Candidates,
+        Action::NoteGrid,
+        Action::NoteColors,
+        Action::Link,
+        Action::Rotate,
+        Action::Mirror,
+        Action::Debug,
+        Action::Notes,
+        Action::Pause,
+        Action::Solution,
+        Action::Submit,
+        Action::Recall,
+        Action::GiveUp,
+        Action::Menu,
+    ];
+
+    /// The name used in the `[keys]` table.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Clear => "clear",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::Hint => "hint",
+            Action::Reveal => "reveal",
+            Action::Comment => "comment",
+            Action::Command => "command",
+            Action::Copy => "copy",
+            Action::CopyBoard => "copy_board",
+            Action::Diff => "diff",
+            Action::Check => "check",
+            Action::LiveCheck => "live_check",
+            Action::Legend => "legend",
+            Action::Help => "help",
+            Action::Focus => "focus",
+            Action::Radar => "radar",
+            Action::Digits => "digits",
+            Action::Candidates => "candidates",
+            Action::NoteGrid => "note_grid",
+            Action::NoteColors => "note_colors",
+            Action::Link => "link",
+            Action::Rotate => "rotate",
+            Action::Mirror => "mirror",
+            Action::Debug => "debug",
+            Action::Notes => "notes",
+            Action::Pause => "pause",
+            Action::Solution => "solution",
+            Action::Submit => "submit",
+            Action::Recall => "recall",
+            Action::GiveUp => "give_up",
+            Action::Menu => "menu",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "Esc"],
+            Action::Up => &["Up", "k"],
+            Action::Down => &["Down", "j"],
+            Action::Left => &["Left", "h"],
+            Action::Right => &["Right", "l"],
+            Action::Clear => &["0", "Backspace", "Delete"],
+            Action::Undo => &["u"],
+            Action::Redo => &["U", "Ctrl+r"],
+            Action::Hint => &["H"],
+            Action::Reveal => &["v"],
+            Action::Comment => &["m"],
+            Action::Command => &[":"],
+            Action::Copy => &["y"],
+            Action::CopyBoard => &["C"],
+            Action::Diff => &["d"],
+            Action::Check => &["c"],
+            Action::LiveCheck => &["x"],
+            Action::Legend => &["K"],
+            Action::Help => &["?"],
+            Action::Focus => &["z"],
+            Action::Radar => &["a"],
+            Action::Digits => &["D"],
+            Action::Candidates => &["A"],
+            Action::NoteGrid => &["g"],
+            Action::NoteColors => &["o"],
+            Action::Link => &["L"],
+            Action::Rotate => &["R"],
+            Action::Mirror => &["M"],
+            Action::Debug => &["F12"],
+            Action::Notes => &["N"],
+            Action::Pause => &["p"],
+            Action::Solution => &["s"],
+            Action::Submit => &["Enter"],
+            Action::Recall => &["e"],
+            Action::GiveUp => &["G"],
+            Action::Menu => &["n"],
+        }
+    }
+
+    /// What the action does, for the help popup.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit, saving the game",
+            Action::Up => "Move up",
+            Action::Down => "Move down",
+            Action::Left => "Move left",
+            Action::Right => "Move right",
+            Action::Clear => "Clear the cell",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::Hint => "Hint, again for more",
+            Action::Reveal => "Reveal the cell",
+            Action::Comment => "Add a comment",
+            Action::Command => "Enter cells by name",
+            Action::Copy => "Copy the puzzle",
+            Action::CopyBoard => "Copy the board",
+            Action::Diff => "Diff against solution",
+            Action::Check => "Check entries briefly",
+            Action::LiveCheck => "Live conflict check",
+            Action::Legend => "Color legend",
+            Action::Help => "This help",
+            Action::Focus => "Zoomed cell panel",
+            Action::Radar => "Technique radar",
+            Action::Digits => "Digits left",
+            Action::Candidates => "Show all candidates",
+            Action::NoteGrid => "Tall note grid",
+            Action::NoteColors => "Color pencil marks",
+            Action::Link => "Draw a chain link",
+            Action::Rotate => "Turn the board",
+            Action::Mirror => "Mirror the board",
+            Action::Debug => "Generation details",
+            Action::Notes => "Note mode",
+            Action::Pause => "Pause",
+            Action::Solution => "Show the solution",
+            Action::Submit => "Submit the board",
+            Action::Recall => "Recall hidden digits",
+            Action::GiveUp => "Give up the game",
+            Action::Menu => "New game menu",
+        }
+    }
+
+    /// Whether the action still works once the board is solved.
+    pub fn works_when_solved(self) -> bool {
+        matches!(
+            self,
+            Action::Quit
+                | Action::Menu
+                | Action::Solution
+                | Action::Copy
+                | Action::CopyBoard
+                | Action::Legend
+                | Action::Help
+                | Action::Focus
+                | Action::Digits
+                | Action::NoteGrid
+                | Action::Rotate
+                | Action::Mirror
+                | Action::Debug
+        )
+    }
+
+    /// Whether the action works while the game is paused: resuming and leaving.
+    pub fn works_while_paused(self) -> bool {
+        matches!(
+            self,
+            Action::Pause | Action::Quit | Action::Menu | Action::Help
+        )
+    }
+}
+
+/// One key with the modifiers that matter for matching. Shift is part of the
+/// character itself, so `U` is Shift+u.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyBinding {
+    code: KeyCode,
+    ctrl: bool,
+    alt: bool,
+}
+
+const NAMED_KEYS: [(&str, KeyCode); 12] = [
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Esc", KeyCode::Esc),
+    ("Enter", KeyCode::Enter),
+    ("Tab", KeyCode::Tab),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("Space", KeyCode::Char(' ')),
+];
+
+impl KeyBinding {
+    fn from_event(key: &KeyEvent) -> Self {
+        KeyBinding {
+            code: key.code,
+            ctrl: key.modifiers.contains(KeyModifiers::CONTROL),
+            alt: key.modifiers.contains(KeyModifiers::ALT),
+        }
+    }
+
+    fn char(c: char) -> Self {
+        KeyBinding {
+            code: KeyCode::Char(c),
+            ctrl: false,
+            alt: false,
+        }
+    }
+
+    /// Reads keys like `x`, `X`, `Ctrl+r`, `Alt+Left`, `F5` or `Esc`.
+    /// Key names and modifiers are case-insensitive, single characters are not.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (mut ctrl, mut alt) = (false, false);
+        let mut rest = text.trim();
+        while let Some((modifier, key)) = rest.split_once('+').filter(|(_, key)| !key.is_empty()) {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => ctrl = true,
+                "alt" => alt = true,
+                _ => return None,
+            }
+            rest = key;
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(if ctrl { c.to_ascii_lowercase() } else { c }),
+            _ => NAMED_KEYS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(rest))
+                .map(|&(_, code)| code)
+                .or_else(|| {
+                    let n = rest.strip_prefix(['F', 'f'])?.parse().ok()?;
+                    (1..=12).contains(&n).then_some(KeyCode::F(n))
+                })?,
+        };
+        Some(KeyBinding { code, ctrl, alt })
+    }
+
+    /// Digits enter numbers and Shift or Alt with a digit toggles notes, so
+    /// those keys can't be rebound.
+    fn is_reserved(self) -> bool {
+        match self.code {
+            KeyCode::Char(c) if !self.ctrl => ('1'..='9').contains(&c) || "!@#$%^&*(".contains(c),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::F(n) => write!(f, "F{n}"),
+            code => match NAMED_KEYS.iter().find(|&&(_, named)| named == code) {
+                Some((name, _)) => write!(f, "{name}"),
+                None => match code {
+                    KeyCode::Char(c) => write!(f, "{c}"),
+                    other => write!(f, "{other:?}"),
+                },
+            },
+        }
+    }
+}
+
+/// Something wrong with the `[keys]` table, found when the keymap is built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeymapProblem {
+    UnknownAction {
+        name: String,
+        suggestion: Option<Action>,
+    },
+    BadKey {
+        action: Action,
+        key: String,
+    },
+    Reserved {
+        action: Action,
+        key: KeyBinding,
+    },
+    /// Two actions want the same key; `kept` gets it
+    Conflict {
+        key: KeyBinding,
+        kept: Action,
+        dropped: Action,
+        suggestion: Option<KeyBinding>,
+    },
+    /// An action ended up with no key at all
+    Unbound {
+        action: Action,
+        suggestion: Option<KeyBinding>,
+    },
+}
+
+impl KeymapProblem {
+    pub fn description(&self) -> String {
+        match self {
+            KeymapProblem::UnknownAction { name, .. } => format!("`{name}` is not an action"),
+            KeymapProblem::BadKey { action, key } => {
+                format!("`{key}` for {} is not a key", action.name())
+            }
+            KeymapProblem::Reserved { action, key } => format!(
+                "`{key}` for {} is reserved for entering digits and notes",
+                action.name()
+            ),
+            KeymapProblem::Conflict {
+                key, kept, dropped, ..
+            } => format!(
+                "`{key}` is bound to both {} and {}; {} keeps it",
+                kept.name(),
+                dropped.name(),
+                kept.name()
+            ),
+            KeymapProblem::Unbound { action, .. } => {
+                format!("{} has no key and can't be used", action.name())
+            }
+        }
+    }
+
+    pub fn fix(&self) -> String {
+        let bind = |action: &Action, key: &Option<KeyBinding>| match key {
+            Some(key) => format!("set `{} = \"{key}\"`", action.name()),
+            None => format!("give {} a free key", action.name()),
+        };
+        match self {
+            KeymapProblem::UnknownAction {
+                suggestion: Some(action),
+                ..
+            } => format!("Did you mean `{}`?", action.name()),
+            KeymapProblem::UnknownAction { .. } => {
+                format!("Use one of: {}", Action::ALL.map(Action::name).join(", "))
+            }
+            KeymapProblem::BadKey { .. } => "Use a character such as `x`, a name such as `Esc`, \
+                 `Space` or `F5`, optionally after `Ctrl+` or `Alt+`"
+                .to_string(),
+            KeymapProblem::Reserved { action, .. } => {
+                let mut fix = bind(action, &None);
+                fix[..1].make_ascii_uppercase();
+                format!("{fix}; digits and Shift+digits always enter numbers and notes")
+            }
+            KeymapProblem::Conflict {
+                dropped,
+                suggestion,
+                ..
+            } => format!("To keep both, {}", bind(dropped, suggestion)),
+            KeymapProblem::Unbound { action, suggestion } => {
+                let mut fix = bind(action, suggestion);
+                fix[..1].make_ascii_uppercase();
+                fix
+            }
+        }
+    }
+}
+
+/// Keys of the game screen's actions: the defaults, with `[keys]` applied.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::build(&BTreeMap::new()).0
+    }
+}
+
+impl Keymap {
+    /// Applies the `[keys]` table over the defaults. The keymap is always
+    /// usable; whatever had to be dropped or left unbound is reported.
+    pub fn build(custom: &BTreeMap<String, Vec<String>>) -> (Self, Vec<KeymapProblem>) {
+        let mut problems = Vec::new();
+        let mut chosen: BTreeMap<Action, Vec<KeyBinding>> = BTreeMap::new();
+        for (name, keys) in custom {
+            let Some(action) = Action::from_name(name) else {
+                problems.push(KeymapProblem::UnknownAction {
+                    name: name.clone(),
+                    suggestion: closest_action(name),
+                });
+                continue;
+            };
+            let mut bound = Vec::new();
+            for key in keys {
+                match KeyBinding::parse(key) {
+                    None => problems.push(KeymapProblem::BadKey {
+                        action,
+                        key: key.clone(),
+                    }),
+                    Some(binding) if binding.is_reserved() => {
+                        problems.push(KeymapProblem::Reserved {
+                            action,
+                            key: binding,
+                        })
+                    }
+                    Some(binding) if !bound.contains(&binding) => bound.push(binding),
+                    Some(_) => {}
+                }
+            }
+            chosen.insert(action, bound);
+        }
+
+        // Chosen keys go first so they win over defaults they collide with
+        let mut wanted: Vec<(KeyBinding, Action)> = chosen
+            .iter()
+            .flat_map(|(&action, keys)| keys.iter().map(move |&key| (key, action)))
+            .collect();
+        wanted.extend(
+            Action::ALL
+                .into_iter()
+                .filter(|action| !chosen.contains_key(action))
+                .flat_map(|action| {
+                    action
+                        .default_keys()
+                        .iter()
+                        .filter_map(|key| KeyBinding::parse(key))
+                        .map(move |key| (key, action))
+                }),
+        );
+        let mut bindings: Vec<(KeyBinding, Action)> = Vec::new();
+        let mut conflicts = Vec::new();
+        for (key, action) in wanted {
+            match bindings.iter().find(|(bound, _)| *bound == key) {
+                Some(&(_, kept)) => conflicts.push((key, kept, action)),
+                None => bindings.push((key, action)),
+            }
+        }
+
+        let keymap = Keymap { bindings };
+        // Each suggested key is offered once, so the fixes can all be applied
+        let mut suggested = Vec::new();
+        for (key, kept, dropped) in conflicts {
+            let suggestion = keymap.free_key(dropped, &suggested);
+            suggested.extend(suggestion);
+            problems.push(KeymapProblem::Conflict {
+                key,
+                kept,
+                dropped,
+                suggestion,
+            });
+        }
+        // An action that lost its only key in a conflict already has a fix
+        let dropped: Vec<Action> = problems
+            .iter()
+            .filter_map(|problem| match problem {
+                KeymapProblem::Conflict { dropped, .. } => Some(*dropped),
+                _ => None,
+            })
+            .collect();
+        for action in Action::ALL {
+            if keymap.keys(action).next().is_none() && !dropped.contains(&action) {
+                let suggestion = keymap.free_key(action, &suggested);
+                suggested.extend(suggestion);
+                problems.push(KeymapProblem::Unbound { action, suggestion });
+            }
+        }
+        (keymap, problems)
+    }
+
+    /// The action bound to a key press, if any.
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        let pressed = KeyBinding::from_event(key);
+        self.bindings
+            .iter()
+            .find(|(key, _)| *key == pressed)
+            .map(|&(_, action)| action)
+    }
+
+    pub fn keys(&self, action: Action) -> impl Iterator<Item = KeyBinding> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(_, bound)| *bound == action)
+            .map(|&(key, _)| key)
+    }
+
+    /// The first key of an action, for help lines and tips.
+    pub fn label(&self, action: Action) -> String {
+        self.keys(action)
+            .next()
+            .map_or_else(|| "-".to_string(), |key| key.to_string())
+    }
+
+    /// The four movement keys in one word, like `Arrows/hjkl` for the defaults.
+    pub fn movement_label(&self) -> String {
+        // In vi order, left, down, up, right
+        const MOVES: [(Action, KeyCode); 4] = [
+            (Action::Left, KeyCode::Left),
+            (Action::Down, KeyCode::Down),
+            (Action::Up, KeyCode::Up),
+            (Action::Right, KeyCode::Right),
+        ];
+        let arrows = MOVES.iter().all(|&(action, arrow)| {
+            self.keys(action)
+                .any(|key| key.code == arrow && !key.ctrl && !key.alt)
+        });
+        let letters: Option<String> = MOVES
+            .iter()
+            .map(|&(action, _)| {
+                self.keys(action).find_map(|key| match key.code {
+                    KeyCode::Char(c) if !key.ctrl && !key.alt => Some(c),
+                    _ => None,
+                })
+            })
+            .collect();
+        match (arrows, letters) {
+            (true, Some(letters)) => format!("Arrows/{letters}"),
+            (true, None) => "Arrows".to_string(),
+            (false, Some(letters)) => letters,
+            (false, None) => MOVES
+                .iter()
+                .map(|&(action, _)| self.label(action))
+                .collect::<Vec<_>>()
+                .join("/"),
+        }
+    }
+
+    /// An unbound character to suggest for an action, preferring its letters.
+    fn free_key(&self, action: Action, suggested: &[KeyBinding]) -> Option<KeyBinding> {
+        let letters = action.name().chars().filter(char::is_ascii_alphabetic);
+        letters
+            .clone()
+            .chain(letters.map(|c| c.to_ascii_uppercase()))
+            .chain('a'..='z')
+            .chain('A'..='Z')
+            .map(KeyBinding::char)
+            .find(|key| {
+                !suggested.contains(key) && !self.bindings.iter().any(|(bound, _)| bound == key)
+            })
+    }
+}
+
+/// The action whose name is a few edits away from a misspelt one.
+fn closest_action(name: &str) -> Option<Action> {
+    let name = name.to_ascii_lowercase();
+    Action::ALL
+        .into_iter()
+        .map(|action| (edit_distance(&name, action.name()), action))
+        .filter(|&(distance, _)| distance <= 2)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, action)| action)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1)
+                .min(row[j] + 1)
+                .min(diagonal + usize::from(ca != cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
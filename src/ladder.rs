@@ -0,0 +1,90 @@
+use crate::puzzle_id::fnv1a;
+use crate::storage;
+use crate::sudoku::{Difficulty, SudokuGrid, Variant};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+pub const LADDER_FILE: &str = "ladder.json";
+
+/// Puzzles of the ladder, climbed in order.
+pub const RUNGS: usize = 20;
+
+/// Difficulty of a rung: the ladder climbs through the named difficulties,
+/// an equal stretch of rungs each.
+pub fn difficulty_for(rung: usize) -> Difficulty {
+    Difficulty::ALL[rung * Difficulty::ALL.len() / RUNGS]
+}
+
+/// Seed of a rung, the same for everyone and across builds.
+pub fn seed_for(rung: usize) -> u64 {
+    fnv1a(format!("ladder rung {rung}").as_bytes())
+}
+
+/// The puzzle of a rung, counted from 0.
+pub fn puzzle_for(rung: usize) -> SudokuGrid {
+    SudokuGrid::from_seed(difficulty_for(rung), Variant::Classic, seed_for(rung))
+}
+
+/// How far up the ladder the player has come and how fast each rung went.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LadderProgress {
+    /// Best solve time of each cleared rung in seconds, lowest rung first;
+    /// rungs are cleared in order, so its length is the next rung to play
+    pub best_secs: Vec<u64>,
+}
+
+impl LadderProgress {
+    pub fn load() -> Self {
+        storage::read_json(LADDER_FILE).unwrap_or_default()
+    }
+
+    pub fn write(&self) -> io::Result<()> {
+        storage::write_json(LADDER_FILE, self)
+    }
+
+    /// Rungs cleared so far.
+    pub fn cleared(&self) -> usize {
+        self.best_secs.len()
+    }
+
+    /// Whether a rung can be played: any cleared one, and the next.
+    pub fn unlocked(&self, rung: usize) -> bool {
+        rung <= self.cleared() && rung < RUNGS
+    }
+
+    pub fn best(&self, rung: usize) -> Option<u64> {
+        self.best_secs.get(rung).copied()
+    }
+
+    /// Records a solve of an unlocked rung. Returns true for a rung cleared
+    /// for the first time, false for a replay.
+    pub fn complete(&mut self, rung: usize, secs: u64) -> bool {
+        let cleared = self.cleared();
+        match self.best_secs.get_mut(rung) {
+            Some(best) => {
+                *best = (*best).min(secs);
+                false
+            }
+            None if rung == cleared => {
+                self.best_secs.push(secs);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Both climbs in one: the further one, with the faster time of each
+    /// rung cleared in both.
+    pub fn merged(&self, other: &Self) -> Self {
+        let (longer, shorter) = if other.cleared() > self.cleared() {
+            (other, self)
+        } else {
+            (self, other)
+        };
+        let mut best_secs = longer.best_secs.clone();
+        for (best, &secs) in best_secs.iter_mut().zip(&shorter.best_secs) {
+            *best = (*best).min(secs);
+        }
+        LadderProgress { best_secs }
+    }
+}
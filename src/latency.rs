@@ -0,0 +1,42 @@
+use crate::storage;
+use std::time::Duration;
+
+/// How long an event may take, from being read to the frame showing its
+/// effect, before it counts as slow. Leaves room within a 60 Hz frame.
+pub const BUDGET: Duration = Duration::from_millis(15);
+
+/// Events over the budget, for finding handlers that belong off the hot path
+const LATENCY_LOG: &str = "latency.log";
+
+/// Input-to-render times of the events handled this session.
+#[derive(Clone, Debug, Default)]
+pub struct Latency {
+    pub events: u32,
+    pub over_budget: u32,
+    pub last: Option<Duration>,
+    /// The slowest event so far, with what it was
+    pub worst: Option<(String, Duration)>,
+}
+
+impl Latency {
+    /// Records how long `event` took to show on screen, logging it when it
+    /// went over the budget.
+    pub fn record(&mut self, event: &str, took: Duration) {
+        self.events += 1;
+        self.last = Some(took);
+        if self.worst.as_ref().is_none_or(|(_, worst)| took > *worst) {
+            self.worst = Some((event.to_string(), took));
+        }
+        if took > BUDGET {
+            self.over_budget += 1;
+            let line = format!(
+                "{} {event} took={}ms budget={}ms version={}",
+                chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+                took.as_millis(),
+                BUDGET.as_millis(),
+                env!("CARGO_PKG_VERSION")
+            );
+            let _ = storage::append_line(LATENCY_LOG, &line);
+        }
+    }
+}
@@ -1,30 +1,244 @@
 use crossterm::{
     event::{
-        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
-        MouseEventKind,
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind,
     },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, prelude::*};
-use std::{error::Error, io};
+use std::io::{self, IsTerminal, Write};
+use std::time::Instant;
+use std::{env, error::Error, path::Path};
 
+mod animation;
 mod app;
+mod challenge;
+mod clipboard;
+mod command;
+mod compare;
+mod config;
+mod daily;
+mod export;
+mod history;
+mod history_store;
+mod hunt;
+mod import;
+mod keymap;
+mod ladder;
+mod latency;
+mod memory;
+mod metrics;
+mod pregen;
+mod profile;
+mod puzzle_id;
+mod quiz;
+mod save;
+mod score;
+mod screensaver;
+mod settings;
+mod source;
+mod stats;
+mod storage;
+mod style_rule;
 mod sudoku;
+mod theme;
+mod tournament;
 mod ui;
+mod undo;
+mod update;
+mod verify;
+mod view;
+mod win;
 
-use app::{App, AppState};
+use app::{App, AppState, Confirm};
+use challenge::Challenge;
+use config::Config;
+use daily::DailyProgress;
+use keymap::Action;
+use profile::{Conflict, Resolution};
+use save::SavedGame;
+use sudoku::{Difficulty, SudokuGrid, Variant};
+use sudoku_engine::solver;
+use tournament::{Tournament, TournamentRun};
 
 fn main() -> Result<(), Box<dyn Error>> {
+    // ---- Headless Commands ----
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let mut tournament = None;
+    let mut challenge = None;
+    let mut flags = Flags::default();
+    // Commands reading or writing the history use the backend the config picks, if it loads
+    if matches!(args.as_slice(), ["stats", "report", ..] | ["profile", ..])
+        && let Ok(config) = Config::load()
+    {
+        history_store::use_backend(config.history_backend);
+    }
+    match args.as_slice() {
+        [] => {}
+        [first, ..] if first.starts_with("--") => match Flags::parse(&args) {
+            Ok(parsed) => flags = parsed,
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
+        },
+        ["config", "--print"] => match Config::load() {
+            Ok(config) => {
+                print!("{}", config.colors_and_keys_text());
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("Config error: {e}");
+                std::process::exit(1);
+            }
+        },
+        ["profile", "export", file] => match profile::export(Path::new(file)) {
+            Ok(files) => {
+                println!("Exported {files} files to {file}");
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("Profile error: {e}");
+                std::process::exit(1);
+            }
+        },
+        ["profile", "import", file] => match profile::import(Path::new(file), ask_resolution) {
+            Ok((manifest, changes)) => {
+                println!("Profile from {file} (version {}):", manifest.version);
+                for (name, resolution) in &changes {
+                    let what = match resolution {
+                        Resolution::KeepLocal => "kept this machine's",
+                        Resolution::KeepArchive => "taken from the archive",
+                        Resolution::Merge => "merged",
+                    };
+                    println!("  {name}: {what}");
+                }
+                if changes.is_empty() {
+                    println!("  nothing differs");
+                } else if changes.iter().any(|&(_, r)| r != Resolution::KeepLocal) {
+                    println!("Replaced files were kept with a .bak suffix");
+                }
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("Profile error: {e}");
+                std::process::exit(1);
+            }
+        },
+        ["compare", files @ ..] if matches!(files.len(), 1 | 2) => match compare_saves(files) {
+            Ok(text) => {
+                print!("{text}");
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("Compare error: {e}");
+                std::process::exit(1);
+            }
+        },
+        ["verify", "--result", file] => match verify::load(Path::new(file)) {
+            Ok(records) => {
+                let (text, verified) = verify::text(&records, chrono::Utc::now().timestamp());
+                print!("{text}");
+                if verified {
+                    return Ok(());
+                }
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Verify error: {e}");
+                std::process::exit(1);
+            }
+        },
+        ["stats", "report", "--week"] => {
+            let today = daily::today();
+            let report = stats::weekly_report(&history::load(), &DailyProgress::load(today), today);
+            print!("{report}");
+            return Ok(());
+        }
+        ["daily", "--print"] => {
+            print!("{}", export::daily_text(daily::today()));
+            return Ok(());
+        }
+        ["challenge", code] => match Challenge::from_code(code) {
+            Some(set) => challenge = Some(set),
+            None => {
+                eprintln!("Not a challenge code: {code}");
+                std::process::exit(2);
+            }
+        },
+        ["tournament", "open", file] => match Tournament::open(Path::new(file)) {
+            Ok(bundle) => tournament = Some(TournamentRun::new(bundle, Path::new(file))),
+            Err(e) => {
+                eprintln!("Tournament error: {e}");
+                std::process::exit(1);
+            }
+        },
+        ["tournament", "sign", file] => match Tournament::sign_file(Path::new(file)) {
+            Ok(bundle) => {
+                println!("Signed {file}: {}", bundle.signature);
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("Tournament error: {e}");
+                std::process::exit(1);
+            }
+        },
+        _ => {
+            eprintln!(
+                "Usage: tui_sudoku [--difficulty <level>] [--variant <rule>] [--seed <n>] [--puzzle <81 cells>] [--zen] [--check-update] [--no-persist]\n       \
+                 tui_sudoku config --print | daily --print | stats report --week | compare [<mine>] <theirs> | verify --result <file> | challenge <code> | tournament open|sign <file> | profile export|import <file>"
+            );
+            std::process::exit(2);
+        }
+    }
+
+    if flags.no_persist {
+        storage::disable_persistence();
+    }
+
+    // ---- Config (reported before the terminal is taken over) ----
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Config error: {e}");
+            std::process::exit(1);
+        }
+    };
+    history_store::use_backend(config.history_backend);
+
     // ---- Terminal Setup ----
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // ---- App Creation ----
-    let mut app = App::new();
+    let mut app = App::new(config);
+    if let Some(run) = tournament {
+        app.open_tournament(run);
+    } else if let Some(set) = challenge {
+        app.open_challenge(set);
+    } else if flags.puzzle.is_some() || flags.quick_play() {
+        // `--zen` applies to the launched game, leaving the menu option as configured
+        let zen_mode = app.zen_mode;
+        app.zen_mode |= flags.zen;
+        match flags.puzzle.take() {
+            Some((sudoku, solutions)) => app.play_imported(sudoku, solutions),
+            None => app.quick_play(flags.difficulty, flags.variant, flags.seed),
+        }
+        app.zen_mode = zen_mode;
+    }
+    if flags.check_update {
+        app.check_for_update();
+    }
 
     // ---- Main Loop ----
     let res = run_app(&mut terminal, &mut app);
@@ -34,84 +248,680 @@ fn main() -> Result<(), Box<dyn Error>> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
     if let Err(err) = res {
         println!("Error running app: {err:?}");
+        // Keep the game for the resume prompt rather than losing it with the terminal
+        if let Err(e) = app.autosave() {
+            eprintln!("Could not save the game in progress: {e}");
+        }
+    }
+
+    if let Some(archiving) = app.archiving.take() {
+        if !archiving.is_finished() {
+            eprintln!("Letting the solver finish the abandoned game...");
+        }
+        match archiving.join() {
+            Ok(Ok(true)) => {}
+            Ok(Ok(false)) => {
+                eprintln!("The solver could not finish the board; the game was archived as left")
+            }
+            Ok(Err(e)) => eprintln!("Could not update history: {e}"),
+            Err(_) => eprintln!("The solver stopped unexpectedly; the game was not archived"),
+        }
     }
 
     Ok(())
 }
 
 fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
+    // The last input still to be shown on screen, and when it was read
+    let mut pending: Option<(String, Instant)> = None;
     loop {
+        app.update_popup_pause();
         app.update_timer();
+        app.poll_sources();
+        app.poll_update_check();
+        app.update_animation();
+        app.update_peek();
+        app.update_picker();
+        app.update_check();
+        app.update_hunt();
+        app.update_ladder();
+        app.update_memory();
+        app.update_radar();
+        app.update_screensaver();
+        app.check_tournament_time();
         terminal.draw(|f| ui::draw(f, app))?;
+        if let Some((event, read_at)) = pending.take() {
+            app.latency.record(&event, read_at.elapsed());
+        }
 
         if event::poll(std::time::Duration::from_millis(100))? {
-            match event::read()? {
+            let event = event::read()?;
+            if is_activity(&event) {
+                pending = Some((describe_event(&event), Instant::now()));
+            }
+            if is_activity(&event) && app.wake() {
+                continue;
+            }
+            match event {
                 Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    // Ctrl+C leaves from anywhere, keeping a game in progress for next launch
+                    if key.code == KeyCode::Char('c')
+                        && key.modifiers.contains(KeyModifiers::CONTROL)
+                    {
+                        app.autosave()?;
+                        return Ok(());
+                    }
+                    // Problems with the configured keys are reported before anything else
+                    if !app.keymap_problems.is_empty() {
+                        match key.code {
+                            KeyCode::Enter | KeyCode::Esc => app.keymap_problems.clear(),
+                            KeyCode::Char('q') => return Ok(()),
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    // Any key closes the help popup
+                    if app.show_help {
+                        app.show_help = false;
+                        continue;
+                    }
+                    // The comment popup captures all typing while open
+                    if app.comment_input.is_some() {
+                        match key.code {
+                            KeyCode::Enter => app.submit_comment(),
+                            KeyCode::Esc => app.cancel_comment(),
+                            KeyCode::Backspace => app.pop_comment_char(),
+                            KeyCode::Char(c) => app.push_comment_char(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    if app.command_input.is_some() {
+                        match key.code {
+                            KeyCode::Enter => app.submit_command(),
+                            KeyCode::Esc => app.cancel_command(),
+                            KeyCode::Backspace => app.pop_command_char(),
+                            KeyCode::Char(c) => app.push_command_char(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    // Digits pick colors rather than enter while the note colors are open
+                    if app.note_color_popup {
+                        match key.code {
+                            KeyCode::Char(c @ '1'..='9') => {
+                                app.cycle_note_color(c.to_digit(10).unwrap() as u8)
+                            }
+                            KeyCode::Char('0') => app.clear_note_colors(),
+                            KeyCode::Enter | KeyCode::Esc => app.close_note_colors(),
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    if app.seed_input.is_some() {
+                        match key.code {
+                            KeyCode::Enter => app.submit_seed(),
+                            KeyCode::Esc => app.cancel_seed(),
+                            KeyCode::Backspace => app.pop_seed_digit(),
+                            KeyCode::Char(c) => app.push_seed_digit(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    if app.quit_prompt {
+                        match key.code {
+                            KeyCode::Char('s') | KeyCode::Enter => {
+                                app.autosave()?;
+                                return Ok(());
+                            }
+                            KeyCode::Char('a') => {
+                                app.abandon_game(false);
+                                return Ok(());
+                            }
+                            KeyCode::Char('f') => {
+                                app.abandon_game(true);
+                                return Ok(());
+                            }
+                            KeyCode::Esc => app.quit_prompt = false,
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    if let Some(confirm) = app.confirm {
+                        match key.code {
+                            KeyCode::Char('y') | KeyCode::Enter => {
+                                app.confirm = None;
+                                match confirm {
+                                    Confirm::Quit => return Ok(()),
+                                    Confirm::LeaveGame { .. } => {
+                                        app.return_to_difficulty_selection()
+                                    }
+                                    Confirm::GiveUp => app.give_up(),
+                                }
+                            }
+                            KeyCode::Char('s') if confirm.offers_save() => {
+                                app.confirm = None;
+                                app.save_and_leave();
+                            }
+                            KeyCode::Char('n') | KeyCode::Esc => app.confirm = None,
+                            _ => {}
+                        }
+                        continue;
+                    }
                     // State-dependent key handling
                     match app.state {
+                        AppState::ResumePrompt => match key.code {
+                            KeyCode::Char('r') | KeyCode::Enter => app.resume_saved_game(),
+                            KeyCode::Char('d') => app.discard_saved_game(),
+                            KeyCode::Char('k') | KeyCode::Esc => app.keep_saved_game(),
+                            KeyCode::Char('q') => return Ok(()),
+                            _ => {}
+                        },
                         AppState::SelectingDifficulty => match key.code {
                             KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
                             KeyCode::Up | KeyCode::Char('k') => app.move_difficulty_selection(-1),
                             KeyCode::Down | KeyCode::Char('j') => app.move_difficulty_selection(1),
+                            KeyCode::Left | KeyCode::Char('h') => app.cycle_variant(-1),
+                            KeyCode::Right | KeyCode::Char('l') => app.cycle_variant(1),
+                            KeyCode::Char('H') => app.open_history(),
+                            KeyCode::Char('T') => app.open_stats(),
+                            KeyCode::Char('O') => app.open_settings(),
+                            KeyCode::Char('D') => app.open_daily(),
+                            KeyCode::Char('P') => app.open_ladder(),
+                            KeyCode::Char('I') => app.open_import(),
+                            KeyCode::Char('C') => app.new_challenge(),
+                            KeyCode::Char('F') => app.new_hunt(),
+                            KeyCode::Char('U') => app.check_for_update(),
+                            KeyCode::Char('S') => app.open_seed_input(),
+                            KeyCode::Char('L') => app.toggle_limited_mistakes(),
+                            KeyCode::Char('M') => app.toggle_memory_mode(),
+                            KeyCode::Char('Z') => app.toggle_zen_mode(),
+                            KeyCode::Char('A') => app.toggle_time_attack_mode(),
+                            KeyCode::Char('+') | KeyCode::Char('=') => app.adjust_custom_clues(1),
+                            KeyCode::Char('-') => app.adjust_custom_clues(-1),
+                            KeyCode::Tab => app.cycle_source(1),
+                            KeyCode::BackTab => app.cycle_source(-1),
+                            KeyCode::Enter => app.open_preview(),
+                            _ => {}
+                        },
+                        AppState::Tournament => match key.code {
+                            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                            KeyCode::Enter => app.play_next_tournament_game(),
+                            _ => {}
+                        },
+                        AppState::Hunt => match key.code {
+                            // Back in the game it came from, quitting asks as usual
+                            KeyCode::Char('q') => {
+                                app.close_hunt();
+                                if app.request_quit() {
+                                    app.autosave()?;
+                                    return Ok(());
+                                }
+                            }
+                            KeyCode::Esc | KeyCode::Char('b') => app.close_hunt(),
+                            KeyCode::Up | KeyCode::Char('k') => app.move_hunt_cursor(-1, 0),
+                            KeyCode::Down | KeyCode::Char('j') => app.move_hunt_cursor(1, 0),
+                            KeyCode::Left | KeyCode::Char('h') => app.move_hunt_cursor(0, -1),
+                            KeyCode::Right | KeyCode::Char('l') => app.move_hunt_cursor(0, 1),
+                            KeyCode::Char(' ') => app.hunt_pick(),
+                            KeyCode::Enter if app.hunt.as_ref().is_some_and(|h| h.is_over()) => {
+                                app.next_hunt_round()
+                            }
+                            KeyCode::Enter => app.hunt_pick(),
+                            _ => {}
+                        },
+                        AppState::Challenge => match key.code {
+                            KeyCode::Char('q') => return Ok(()),
+                            KeyCode::Esc | KeyCode::Char('b') => app.close_challenge(),
+                            KeyCode::Enter => app.play_next_challenge_game(),
+                            _ => {}
+                        },
+                        AppState::Ladder => match key.code {
+                            KeyCode::Char('q') => return Ok(()),
+                            KeyCode::Esc | KeyCode::Char('b') => {
+                                app.return_to_difficulty_selection()
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => app.move_rung_selection(-1),
+                            KeyCode::Down | KeyCode::Char('j') => app.move_rung_selection(1),
+                            KeyCode::Enter => app.start_ladder_rung(),
+                            _ => {}
+                        },
+                        AppState::Daily => match key.code {
+                            KeyCode::Char('q') => return Ok(()),
+                            KeyCode::Esc | KeyCode::Char('b') => {
+                                app.return_to_difficulty_selection()
+                            }
+                            KeyCode::Enter => app.start_daily(),
+                            _ => {}
+                        },
+                        AppState::Import if app.import_review.is_some() => {
+                            app.status_message = None;
+                            let Some(review) = app.import_review.as_mut() else {
+                                continue;
+                            };
+                            match key.code {
+                                KeyCode::Esc => app.cancel_import_review(),
+                                KeyCode::Enter => app.start_import(),
+                                KeyCode::Up | KeyCode::Char('k') => review.move_cursor(-1, 0),
+                                KeyCode::Down | KeyCode::Char('j') => review.move_cursor(1, 0),
+                                KeyCode::Left | KeyCode::Char('h') => review.move_cursor(0, -1),
+                                KeyCode::Right | KeyCode::Char('l') => review.move_cursor(0, 1),
+                                KeyCode::Char(c @ '1'..='9') => {
+                                    review.set(c.to_digit(10).unwrap() as u8)
+                                }
+                                KeyCode::Char('0' | '.') | KeyCode::Backspace | KeyCode::Delete => {
+                                    review.set(0)
+                                }
+                                _ => {}
+                            }
+                        }
+                        AppState::Import => {
+                            app.status_message = None;
+                            match key.code {
+                                KeyCode::Esc => app.return_to_difficulty_selection(),
+                                KeyCode::Tab => app.review_import(),
+                                KeyCode::Enter => app.push_import_char('\n'),
+                                KeyCode::Backspace => app.pop_import_char(),
+                                KeyCode::Char(c) => app.push_import_char(c),
+                                _ => {}
+                            }
+                        }
+                        AppState::Settings => match key.code {
+                            KeyCode::Char('q') => return Ok(()),
+                            KeyCode::Esc | KeyCode::Char('b') => {
+                                app.return_to_difficulty_selection()
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => app.move_settings_selection(-1),
+                            KeyCode::Down | KeyCode::Char('j') => app.move_settings_selection(1),
+                            KeyCode::Left | KeyCode::Char('h') => app.adjust_setting(-1),
+                            KeyCode::Right
+                            | KeyCode::Char('l')
+                            | KeyCode::Enter
+                            | KeyCode::Char(' ') => app.adjust_setting(1),
+                            _ => {}
+                        },
+                        AppState::Stats => match key.code {
+                            KeyCode::Char('q') => return Ok(()),
+                            KeyCode::Esc | KeyCode::Char('b') => {
+                                app.return_to_difficulty_selection()
+                            }
+                            _ => {}
+                        },
+                        AppState::History => match key.code {
+                            KeyCode::Char('q') => return Ok(()),
+                            KeyCode::Esc | KeyCode::Char('b') => {
+                                app.return_to_difficulty_selection()
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => app.move_history_selection(-1),
+                            KeyCode::Down | KeyCode::Char('j') => app.move_history_selection(1),
+                            KeyCode::Char('p') => app.practice_history_game(),
+                            _ => {}
+                        },
+                        AppState::Previewing => match key.code {
+                            KeyCode::Char('q') => return Ok(()),
+                            KeyCode::Esc | KeyCode::Char('b') => {
+                                app.return_to_difficulty_selection()
+                            }
+                            KeyCode::Char('r') => app.reroll_preview(),
+                            KeyCode::Char('v') => app.toggle_validation(),
+                            KeyCode::Char('s') => app.cycle_symmetry(),
+                            KeyCode::F(12) => app.toggle_debug(),
                             KeyCode::Enter => app.start_game(),
                             _ => {}
                         },
+                        AppState::Failed => match key.code {
+                            KeyCode::Char('q') => return Ok(()),
+                            KeyCode::Char('r') | KeyCode::Enter => app.retry_game(),
+                            KeyCode::Char('n') | KeyCode::Esc => {
+                                app.return_to_difficulty_selection()
+                            }
+                            _ => {}
+                        },
+                        AppState::Solved if app.win.as_ref().is_some_and(|win| win.quizzing) => {
+                            let Some(quiz) = app.quiz_mut() else {
+                                continue;
+                            };
+                            match key.code {
+                                KeyCode::Up | KeyCode::Char('k') => quiz.move_selection(-1),
+                                KeyCode::Down | KeyCode::Char('j') => quiz.move_selection(1),
+                                KeyCode::Char(c @ '1'..='9') => {
+                                    quiz.pick(c.to_digit(10).unwrap() as usize - 1)
+                                }
+                                KeyCode::Enter | KeyCode::Char(' ') if quiz.picked.is_none() => {
+                                    quiz.pick(quiz.selected)
+                                }
+                                KeyCode::Enter
+                                | KeyCode::Char(' ')
+                                | KeyCode::Esc
+                                | KeyCode::Char('b') => app.close_quiz(),
+                                KeyCode::Char('q') => return Ok(()),
+                                _ => {}
+                            }
+                        }
+                        AppState::Solved if app.win.is_some() => match key.code {
+                            KeyCode::Up | KeyCode::Char('k') => app.move_win_selection(-1),
+                            KeyCode::Down | KeyCode::Char('j') => app.move_win_selection(1),
+                            KeyCode::Enter | KeyCode::Char(' ') if app.choose_win() => {
+                                return Ok(());
+                            }
+                            KeyCode::Esc => app.win = None,
+                            KeyCode::Char('q') => return Ok(()),
+                            _ => {}
+                        },
                         AppState::Running | AppState::Solved => {
-                            // Don't allow input if solved, except 'q' or 'n' or 's'
+                            let action = app.keymap.action(&key);
+                            // Once solved only viewing and leaving are allowed
                             if app.state == AppState::Solved
-                                && ![
-                                    KeyCode::Char('q'),
-                                    KeyCode::Char('n'),
-                                    KeyCode::Char('s'),
-                                    KeyCode::Esc,
-                                ]
-                                .contains(&key.code)
-                            // Added 's' and Esc check
+                                && !action.is_some_and(Action::works_when_solved)
                             {
                                 continue;
                             }
-                            match key.code {
-                                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
-                                KeyCode::Up | KeyCode::Char('k') => app.move_cursor(-1, 0),
-                                KeyCode::Down | KeyCode::Char('j') => app.move_cursor(1, 0),
-                                KeyCode::Left | KeyCode::Char('h') => app.move_cursor(0, -1),
-                                KeyCode::Right | KeyCode::Char('l') => app.move_cursor(0, 1),
-                                KeyCode::Char(c @ '1'..='9') => {
-                                    // Only allow setting number if Running
-                                    if app.state == AppState::Running {
-                                        app.set_current_cell(c.to_digit(10).unwrap() as u8);
+                            // While paused only resuming and leaving are allowed
+                            if app.is_paused() && !action.is_some_and(Action::works_while_paused) {
+                                continue;
+                            }
+                            app.status_message = None;
+                            // While a link is drawn, a digit finishes it and Esc drops it
+                            if app.link_from.is_some() {
+                                let link = match key.code {
+                                    KeyCode::Char(c @ '1'..='9') => {
+                                        Some((c.to_digit(10).unwrap() as u8, true))
                                     }
+                                    KeyCode::Char(c) => shifted_digit(c).map(|d| (d, false)),
+                                    _ => None,
+                                };
+                                if let Some((digit, strong)) = link {
+                                    app.finish_link(digit, strong);
+                                    continue;
                                 }
-                                KeyCode::Char('0') | KeyCode::Backspace | KeyCode::Delete => {
-                                    // Only allow clearing number if Running
-                                    if app.state == AppState::Running {
-                                        app.clear_current_cell();
-                                    }
+                                if key.code == KeyCode::Esc {
+                                    app.cancel_link();
+                                    continue;
+                                }
+                            }
+                            match key.code {
+                                KeyCode::Char(c @ '1'..='9') if app.note_mode => {
+                                    app.toggle_note(c.to_digit(10).unwrap() as u8);
+                                }
+                                // Shift+digit (or Alt+digit) notes without switching modes
+                                KeyCode::Char(c) if shifted_digit(c).is_some() => {
+                                    app.toggle_note(shifted_digit(c).unwrap());
+                                }
+                                KeyCode::Char(c @ '1'..='9')
+                                    if key.modifiers.contains(KeyModifiers::ALT) =>
+                                {
+                                    app.toggle_note(c.to_digit(10).unwrap() as u8);
+                                }
+                                // Only allow setting number if Running
+                                KeyCode::Char(c @ '1'..='9') if app.state == AppState::Running => {
+                                    app.set_current_cell(c.to_digit(10).unwrap() as u8);
                                 }
-                                KeyCode::Char('s') => app.toggle_solution(), // Allowed in Running or Solved
-                                KeyCode::Char('n') => app.return_to_difficulty_selection(), // Return to menu
                                 _ => {}
                             }
+                            // Everything else goes through the configurable keymap
+                            match action {
+                                Some(Action::Quit) => {
+                                    if app.request_quit() {
+                                        app.autosave()?;
+                                        return Ok(());
+                                    }
+                                }
+                                Some(Action::Up) => app.move_cursor(-1, 0),
+                                Some(Action::Down) => app.move_cursor(1, 0),
+                                Some(Action::Left) => app.move_cursor(0, -1),
+                                Some(Action::Right) => app.move_cursor(0, 1),
+                                Some(Action::Clear) if app.note_mode => app.clear_notes(),
+                                Some(Action::Clear) => app.clear_current_cell(),
+                                Some(Action::Undo) => app.undo(),
+                                Some(Action::Redo) => app.redo(),
+                                Some(Action::Hint) => app.request_hint(),
+                                Some(Action::Reveal) => app.reveal_current_cell(),
+                                Some(Action::Comment) if app.state == AppState::Running => {
+                                    app.open_comment_input()
+                                }
+                                Some(Action::Command) => app.open_command_input(),
+                                Some(Action::Copy) => app.copy_puzzle(),
+                                Some(Action::CopyBoard) => app.copy_board(),
+                                Some(Action::Diff) => app.toggle_diff(),
+                                Some(Action::Check) => app.check_progress(),
+                                Some(Action::LiveCheck) => app.toggle_live_check(),
+                                Some(Action::Legend) => app.toggle_legend(),
+                                Some(Action::Help) => app.open_help(),
+                                Some(Action::Focus) => app.toggle_focus(),
+                                Some(Action::Radar) => app.toggle_radar(),
+                                Some(Action::Digits) => app.toggle_digits(),
+                                Some(Action::Candidates) => app.toggle_candidates(),
+                                Some(Action::NoteGrid) => app.toggle_note_grid(),
+                                Some(Action::NoteColors) => app.open_note_colors(),
+                                Some(Action::Link) => app.start_link(),
+                                Some(Action::Rotate) => app.rotate_view(),
+                                Some(Action::Mirror) => app.mirror_view(),
+                                Some(Action::Debug) => app.toggle_debug(),
+                                Some(Action::Notes) => app.toggle_note_mode(),
+                                Some(Action::Pause) => app.toggle_pause(),
+                                Some(Action::Solution) => app.toggle_solution(), // Allowed in Running or Solved
+                                Some(Action::Submit) => app.submit_board(),
+                                Some(Action::Recall) => app.recall_digits(),
+                                Some(Action::GiveUp) => app.request_give_up(),
+                                Some(Action::Menu) => app.request_menu(), // Return to menu
+                                Some(Action::Comment) | None => {}
+                            }
                         }
                     }
                 }
-                Event::Mouse(mouse_event) => {
-                    // Handle mouse clicks only when Running
-                    if app.state == AppState::Running
-                        && mouse_event.kind == MouseEventKind::Down(MouseButton::Left)
-                    {
-                        app.handle_mouse_click(mouse_event.column, mouse_event.row);
+                // Handle mouse clicks only in a game, not over its win screen
+                // or a popup
+                Event::Mouse(mouse_event)
+                    if matches!(app.state, AppState::Running | AppState::Solved)
+                        && app.win.is_none()
+                        && !app.popup_open()
+                        && mouse_event.kind == MouseEventKind::Down(MouseButton::Left) =>
+                {
+                    app.handle_mouse_click(mouse_event.column, mouse_event.row);
+                }
+                Event::Mouse(mouse_event) if app.picker.is_some() && !app.popup_open() => {
+                    match mouse_event.kind {
+                        MouseEventKind::Drag(MouseButton::Left) => {
+                            app.drag_picker(mouse_event.column, mouse_event.row)
+                        }
+                        MouseEventKind::Up(MouseButton::Left) => {
+                            app.release_picker(mouse_event.column, mouse_event.row)
+                        }
+                        _ => {}
                     }
                 }
-                Event::Resize(_, _) => {} // Re-rendering handled automatically
-                _ => {}                   // Ignore other events
+                Event::Paste(text) if app.state == AppState::Import => app.paste_import(&text),
+                Event::Resize(width, height) => app.resize(width, height),
+                _ => {} // Ignore other events
             }
         }
     }
 }
+
+/// Options given as `--flags`. Any of the game ones skips the menu and starts
+/// a game straight away, e.g. `--difficulty hard --variant x --seed 99 --zen`.
+#[derive(Default)]
+struct Flags {
+    difficulty: Option<Difficulty>,
+    variant: Option<Variant>,
+    seed: Option<u64>,
+    /// A puzzle given as one line of 81 cells, with its solution count
+    puzzle: Option<(SudokuGrid, usize)>,
+    zen: bool,
+    check_update: bool,
+    /// Guest mode: nothing is read from or written to disk
+    no_persist: bool,
+}
+
+impl Flags {
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        let mut flags = Flags::default();
+        let mut puzzle = None;
+        let mut args = args.iter();
+        while let Some(&flag) = args.next() {
+            let mut value = || {
+                args.next()
+                    .copied()
+                    .ok_or_else(|| format!("{flag} needs a value"))
+            };
+            match flag {
+                "--difficulty" => {
+                    let value = value()?;
+                    flags.difficulty = Some(
+                        Difficulty::ALL
+                            .into_iter()
+                            .chain([Difficulty::Minimal])
+                            .find(|d| format!("{d:?}").eq_ignore_ascii_case(value))
+                            .ok_or_else(|| {
+                                format!(
+                                    "Unknown difficulty `{value}`; use easy, medium, hard, expert or minimal"
+                                )
+                            })?,
+                    );
+                }
+                "--variant" => {
+                    let value = value()?;
+                    flags.variant = Some(variant_from_arg(value).ok_or_else(|| {
+                        format!("Unknown variant `{value}`; use classic, x, windoku or anti-knight")
+                    })?);
+                }
+                "--seed" => {
+                    let value = value()?;
+                    flags.seed =
+                        Some(value.parse().map_err(|_| {
+                            format!("The seed must be a whole number, not `{value}`")
+                        })?);
+                }
+                "--puzzle" => puzzle = Some(sudoku::parse_line(value()?)?),
+                "--zen" => flags.zen = true,
+                "--check-update" => flags.check_update = true,
+                "--no-persist" | "--guest" => flags.no_persist = true,
+                _ => return Err(format!("Unknown option `{flag}`")),
+            }
+        }
+        if let Some(clues) = puzzle {
+            if flags.difficulty.is_some() || flags.seed.is_some() {
+                return Err("--puzzle can't be combined with --difficulty or --seed".to_string());
+            }
+            let variant = flags.variant.unwrap_or(Variant::Classic);
+            if !sudoku::conflicting_cells(&clues, variant).is_empty() {
+                return Err(format!(
+                    "The puzzle repeats a digit under the {} rules",
+                    variant.name()
+                ));
+            }
+            flags.puzzle = Some(
+                SudokuGrid::from_clues(clues, variant)
+                    .ok_or_else(|| "The puzzle has no solution".to_string())?,
+            );
+        }
+        Ok(flags)
+    }
+
+    fn quick_play(&self) -> bool {
+        self.difficulty.is_some() || self.variant.is_some() || self.seed.is_some() || self.zen
+    }
+}
+
+/// A variant as typed on the command line, by name or puzzle ID letter.
+fn variant_from_arg(arg: &str) -> Option<Variant> {
+    match arg.to_ascii_lowercase().as_str() {
+        "classic" | "c" => Some(Variant::Classic),
+        "x" | "diagonal" | "diagonals" => Some(Variant::Diagonal),
+        "windoku" | "w" => Some(Variant::Windoku),
+        "anti-knight" | "antiknight" | "knight" | "k" => Some(Variant::AntiKnight),
+        _ => None,
+    }
+}
+
+/// Lays two saves of one puzzle over each other for `compare`. Given one
+/// file, it is compared with this machine's autosave.
+fn compare_saves(files: &[&str]) -> Result<String, compare::CompareError> {
+    let (mine, theirs) = match files {
+        [theirs] => (
+            SavedGame::load().ok_or(compare::CompareError::NoSave)?,
+            compare::load(Path::new(theirs))?,
+        ),
+        [mine, theirs, ..] => (
+            compare::load(Path::new(mine))?,
+            compare::load(Path::new(theirs))?,
+        ),
+        [] => return Err(compare::CompareError::NoSave),
+    };
+    if !compare::same_puzzle(&mine.sudoku, &theirs.sudoku) {
+        return Err(compare::CompareError::OtherPuzzle);
+    }
+    let color = io::stdout().is_terminal();
+    Ok(compare::text(&mine.sudoku, &theirs.sudoku, color))
+}
+
+/// Asks on the terminal how to settle a file that differs between this
+/// machine and an imported profile. Without a terminal to ask on, or at the
+/// end of input, this machine's file is kept.
+fn ask_resolution(conflict: &Conflict) -> Resolution {
+    println!(
+        "{} differs between this machine and the archive:",
+        conflict.name
+    );
+    println!("  l  keep this machine's: {}", conflict.local);
+    println!("  r  take the archive's:  {}", conflict.archive);
+    if let Some(merged) = &conflict.merged {
+        println!("  m  merge both:          {merged}");
+    }
+    if !io::stdin().is_terminal() {
+        println!("Not asked without a terminal; keeping this machine's");
+        return Resolution::KeepLocal;
+    }
+    let choices = if conflict.merged.is_some() {
+        "l/r/m"
+    } else {
+        "l/r"
+    };
+    loop {
+        print!("Keep which? [{choices}] ");
+        let _ = io::stdout().flush();
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).unwrap_or(0) == 0 {
+            return Resolution::KeepLocal;
+        }
+        match answer.trim() {
+            "l" => return Resolution::KeepLocal,
+            "r" => return Resolution::KeepArchive,
+            "m" if conflict.merged.is_some() => return Resolution::Merge,
+            _ => {}
+        }
+    }
+}
+
+/// Whether an event counts as the player doing something, e.g. to keep the
+/// screensaver away. Key releases and mouse moves don't.
+fn is_activity(event: &Event) -> bool {
+    match event {
+        Event::Key(key) => key.kind == KeyEventKind::Press,
+        Event::Mouse(mouse) => !matches!(mouse.kind, MouseEventKind::Moved),
+        Event::Paste(_) => true,
+        _ => false,
+    }
+}
+
+/// Names an input event for the latency log, e.g. `key H`.
+fn describe_event(event: &Event) -> String {
+    match event {
+        Event::Key(key) => format!("key {}", key.code),
+        Event::Mouse(mouse) => format!("mouse {:?}", mouse.kind),
+        Event::Paste(_) => "paste".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+/// The digit whose key types `c` with Shift held on a US layout, e.g. `!` for 1.
+fn shifted_digit(c: char) -> Option<u8> {
+    "!@#$%^&*(".find(c).map(|i| i as u8 + 1)
+}
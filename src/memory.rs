@@ -0,0 +1,74 @@
+use crate::sudoku::{SIZE, SudokuGrid};
+use std::time::{Duration, Instant};
+
+/// How long a recall shows the hidden digits again.
+pub const RECALL_DURATION: Duration = Duration::from_secs(2);
+
+/// Memory mode of a game: entries hide a few seconds after they are made,
+/// and a limited number of recalls show them again for a moment.
+#[derive(Clone, Debug)]
+pub struct Memory {
+    fade: Duration,
+    /// When each cell was last filled or changed, None for empty cells
+    entered: [[Option<Instant>; SIZE]; SIZE],
+    /// The board as last seen, to tell which cells changed
+    seen: [[u8; SIZE]; SIZE],
+    pub recalls_left: u32,
+    recall_until: Option<Instant>,
+}
+
+impl Memory {
+    /// Starts on the board as it is; entries already on it are hidden from
+    /// the start, as when resuming a saved game.
+    pub fn new(sudoku: &SudokuGrid, fade: Duration, recalls: u32) -> Self {
+        let seen = sudoku.board();
+        let start = Instant::now().checked_sub(fade);
+        Memory {
+            fade,
+            entered: seen.map(|row| row.map(|digit| start.filter(|_| digit != 0))),
+            seen,
+            recalls_left: recalls,
+            recall_until: None,
+        }
+    }
+
+    /// Starts the timer of every cell whose digit changed since the last
+    /// update, however it was entered, and ends a recall whose time is up.
+    pub fn update(&mut self, sudoku: &SudokuGrid) {
+        let now = Instant::now();
+        let board = sudoku.board();
+        for (r, row) in board.iter().enumerate() {
+            for (c, &digit) in row.iter().enumerate() {
+                if digit != self.seen[r][c] {
+                    self.entered[r][c] = (digit != 0).then_some(now);
+                }
+            }
+        }
+        self.seen = board;
+        if self.recall_until.is_some_and(|until| now >= until) {
+            self.recall_until = None;
+        }
+    }
+
+    /// Whether the digit at (r, c) has faded from view. Only the timers are
+    /// looked at; which entries hide at all is up to the caller.
+    pub fn hidden(&self, r: usize, c: usize) -> bool {
+        self.recall_until.is_none()
+            && self.entered[r][c].is_some_and(|at| at.elapsed() >= self.fade)
+    }
+
+    pub fn recalling(&self) -> bool {
+        self.recall_until.is_some()
+    }
+
+    /// Shows the hidden digits for a moment, spending a recall. Returns false
+    /// when none are left.
+    pub fn recall(&mut self) -> bool {
+        if self.recalls_left == 0 {
+            return false;
+        }
+        self.recalls_left -= 1;
+        self.recall_until = Some(Instant::now() + RECALL_DURATION);
+        true
+    }
+}
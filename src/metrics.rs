@@ -0,0 +1,61 @@
+use crate::save::{Elimination, Move};
+
+/// Upper bounds (seconds) of the think-time histogram buckets; the last bucket is open-ended.
+pub const THINK_BUCKETS: [u64; 4] = [5, 15, 30, 60];
+
+/// Pace statistics derived from a game's move timestamps.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metrics {
+    /// Digits entered (clearing a cell doesn't count)
+    pub entries: usize,
+    pub cells_per_minute: f64,
+    pub avg_secs_per_entry: f64,
+    /// Longest gap before a move, in seconds
+    pub longest_think_secs: u64,
+    /// Gaps before each entry, counted per `THINK_BUCKETS` bucket
+    pub histogram: [usize; THINK_BUCKETS.len() + 1],
+}
+
+impl Metrics {
+    /// Computes metrics for the moves made within `elapsed_secs` of play.
+    pub fn from_moves(moves: &[Move], elapsed_secs: u64) -> Self {
+        let mut metrics = Metrics::default();
+        let mut previous_ms = 0;
+        for mv in moves {
+            let think_secs = mv.elapsed_ms.saturating_sub(previous_ms) / 1000;
+            previous_ms = mv.elapsed_ms;
+            metrics.longest_think_secs = metrics.longest_think_secs.max(think_secs);
+            if mv.digit == 0 {
+                continue;
+            }
+            metrics.entries += 1;
+            let bucket = THINK_BUCKETS
+                .iter()
+                .position(|&limit| think_secs < limit)
+                .unwrap_or(THINK_BUCKETS.len());
+            metrics.histogram[bucket] += 1;
+        }
+        if metrics.entries > 0 && elapsed_secs > 0 {
+            metrics.cells_per_minute = metrics.entries as f64 * 60.0 / elapsed_secs as f64;
+            metrics.avg_secs_per_entry = elapsed_secs as f64 / metrics.entries as f64;
+        }
+        metrics
+    }
+
+    /// Label of a histogram bucket, e.g. `5-15s`.
+    pub fn bucket_label(index: usize) -> String {
+        match index {
+            0 => format!("<{}s", THINK_BUCKETS[0]),
+            i if i < THINK_BUCKETS.len() => {
+                format!("{}-{}s", THINK_BUCKETS[i - 1], THINK_BUCKETS[i])
+            }
+            _ => format!("{}s+", THINK_BUCKETS[THINK_BUCKETS.len() - 1]),
+        }
+    }
+}
+
+/// Logged eliminations the solver agreed with, and the number logged.
+pub fn elimination_accuracy(log: &[Elimination]) -> (usize, usize) {
+    let justified = log.iter().filter(|e| e.justified()).count();
+    (justified, log.len())
+}
@@ -0,0 +1,95 @@
+use crate::sudoku::{Difficulty, SudokuGrid, Symmetry, Variant};
+use std::collections::{HashMap, VecDeque};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+/// What the worker is asked to generate.
+type Spec = (Difficulty, Variant, Symmetry);
+
+/// Generates puzzles on a background thread so the next board for each
+/// difficulty, variant and symmetry is usually ready before it is asked for.
+pub struct Pregenerator {
+    requests: Sender<Spec>,
+    results: Receiver<(Spec, SudokuGrid)>,
+    ready: HashMap<Spec, SudokuGrid>,
+    /// Specs sent to the worker and not yet back, in the order it makes
+    /// them, so the front is the one in progress
+    queue: VecDeque<Spec>,
+}
+
+impl Pregenerator {
+    pub fn new() -> Self {
+        let (request_tx, request_rx) = mpsc::channel::<Spec>();
+        let (result_tx, result_rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            // Exits once the Pregenerator (and its Sender) is dropped
+            for spec @ (difficulty, variant, symmetry) in request_rx {
+                let grid = SudokuGrid::symmetric(difficulty, variant, symmetry, rand::random());
+                if result_tx.send((spec, grid)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Pregenerator {
+            requests: request_tx,
+            results: result_rx,
+            ready: HashMap::new(),
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Asks the worker to prepare a puzzle if none is ready or queued.
+    pub fn request(&mut self, difficulty: Difficulty, variant: Variant, symmetry: Symmetry) {
+        let spec = (difficulty, variant, symmetry);
+        if self.ready.contains_key(&spec) || self.queue.contains(&spec) {
+            return;
+        }
+        if self.requests.send(spec).is_ok() {
+            self.queue.push_back(spec);
+        }
+    }
+
+    /// Collects any puzzles the worker has finished. Never blocks.
+    pub fn poll(&mut self) {
+        while let Ok((spec, grid)) = self.results.try_recv() {
+            self.store(spec, grid);
+        }
+    }
+
+    /// Takes a puzzle and queues its replacement. Waits only for a puzzle
+    /// the worker is making right now; one still queued behind other work,
+    /// or asked of a worker that has stopped, is generated inline, and the
+    /// queued one is kept for next time.
+    pub fn take(
+        &mut self,
+        difficulty: Difficulty,
+        variant: Variant,
+        symmetry: Symmetry,
+    ) -> SudokuGrid {
+        let spec = (difficulty, variant, symmetry);
+        self.poll();
+        while !self.ready.contains_key(&spec) && self.queue.front() == Some(&spec) {
+            match self.results.recv() {
+                Ok((s, grid)) => self.store(s, grid),
+                Err(_) => {
+                    self.queue.clear();
+                    break;
+                }
+            }
+        }
+        let grid = self.ready.remove(&spec).unwrap_or_else(|| {
+            SudokuGrid::symmetric(difficulty, variant, symmetry, rand::random())
+        });
+        self.request(difficulty, variant, symmetry);
+        grid
+    }
+
+    fn store(&mut self, spec: Spec, grid: SudokuGrid) {
+        if let Some(index) = self.queue.iter().position(|&queued| queued == spec) {
+            self.queue.remove(index);
+        }
+        self.ready.insert(spec, grid);
+    }
+}
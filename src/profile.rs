@@ -0,0 +1,423 @@
+use crate::config::CONFIG_FILE;
+use crate::daily::{DAILY_FILE, DailyProgress};
+use crate::history::{self, GameRecord, HISTORY_FILE, Outcome};
+use crate::history_store;
+use crate::ladder::{self, LADDER_FILE, LadderProgress};
+use crate::save::{SAVE_FILE, SavedGame};
+use crate::{storage, update};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fmt, fs, io};
+
+/// Describes the archive, and is checked before anything is imported.
+const MANIFEST_FILE: &str = "profile.json";
+const APP_NAME: &str = "tui_sudoku";
+/// Layout of the archive, raised whenever files move inside it
+const FORMAT: u32 = 1;
+/// Files of the data directory that make up a profile. The history holds the
+/// moves of every finished game, so replays travel with it. It is read from
+/// and written to the history backend in use, and travels as JSON whichever
+/// that is.
+const DATA_FILES: [&str; 4] = [SAVE_FILE, HISTORY_FILE, DAILY_FILE, LADDER_FILE];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub app: String,
+    /// App version that wrote the archive
+    pub version: String,
+    pub format: u32,
+}
+
+/// A profile file that differs between this machine and the archive, with
+/// what each way of settling it leaves behind.
+pub struct Conflict {
+    pub name: &'static str,
+    pub local: String,
+    pub archive: String,
+    /// What merging both gives, for files that can be merged
+    pub merged: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    KeepLocal,
+    KeepArchive,
+    Merge,
+}
+
+/// Every file an import touched, with how it was settled.
+pub type Changes = Vec<(&'static str, Resolution)>;
+
+#[derive(Debug)]
+pub enum ProfileError {
+    /// No data or config directory, e.g. without a home directory
+    NoDirectory,
+    Io(PathBuf, io::Error),
+    /// `tar` is missing or failed
+    Tar(String),
+    Empty,
+    NotAProfile,
+    UnknownFormat(u32),
+    /// The archive comes from a newer version than this one
+    Newer(String),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::NoDirectory => write!(f, "no data or config directory available"),
+            ProfileError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            ProfileError::Tar(reason) => write!(f, "tar failed: {reason}"),
+            ProfileError::Empty => write!(f, "there is no config, save or history to export yet"),
+            ProfileError::NotAProfile => write!(f, "the archive is not a {APP_NAME} profile"),
+            ProfileError::UnknownFormat(format) => {
+                write!(
+                    f,
+                    "the archive uses profile format {format}, this version reads {FORMAT}"
+                )
+            }
+            ProfileError::Newer(version) => write!(
+                f,
+                "the archive comes from version {version}, newer than this {}; update first",
+                env!("CARGO_PKG_VERSION")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+/// Packs the config file, autosave, history and daily streak into one
+/// `.tar.gz` at `path`, returning how many files went in. Missing files are
+/// left out.
+pub fn export(path: &Path) -> Result<usize, ProfileError> {
+    let (data_dir, config_dir) = directories()?;
+    let staging = Staging::new()?;
+    let mut files = 0;
+    for (dir, part, name) in parts(&data_dir, &config_dir) {
+        let from = match name {
+            HISTORY_FILE => match local_history(&staging)? {
+                Some(path) => path,
+                None => continue,
+            },
+            _ => dir.join(name),
+        };
+        if !from.exists() {
+            continue;
+        }
+        let to = staging.path.join(part).join(name);
+        fs::copy(&from, &to).map_err(|e| ProfileError::Io(from, e))?;
+        files += 1;
+    }
+    if files == 0 {
+        return Err(ProfileError::Empty);
+    }
+    let manifest = Manifest {
+        app: APP_NAME.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        format: FORMAT,
+    };
+    let contents = serde_json::to_string_pretty(&manifest).map_err(io::Error::other);
+    let manifest_path = staging.path.join(MANIFEST_FILE);
+    contents
+        .and_then(|contents| fs::write(&manifest_path, contents))
+        .map_err(|e| ProfileError::Io(manifest_path, e))?;
+    let archive = absolute(path)?;
+    tar(&[
+        "-czf",
+        &archive,
+        "-C",
+        &staging.display(),
+        MANIFEST_FILE,
+        "data",
+        "config",
+    ])?;
+    Ok(files)
+}
+
+/// Unpacks an archive written by `export` into the current profile. The
+/// manifest is checked first, so nothing changes for an archive of an unknown
+/// format or from a newer version. Files missing here are copied in; for each
+/// one that differs, `resolve` picks which side to keep or whether to merge
+/// them, so neither is lost unasked. Files that get replaced are kept beside
+/// the new ones with a `.bak` suffix. Returns what happened to every file
+/// that changed.
+pub fn import(
+    path: &Path,
+    mut resolve: impl FnMut(&Conflict) -> Resolution,
+) -> Result<(Manifest, Changes), ProfileError> {
+    let (data_dir, config_dir) = directories()?;
+    let staging = Staging::new()?;
+    tar(&["-xzf", &absolute(path)?, "-C", &staging.display()])?;
+    let manifest: Manifest = fs::read_to_string(staging.path.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .filter(|manifest: &Manifest| manifest.app == APP_NAME)
+        .ok_or(ProfileError::NotAProfile)?;
+    if manifest.format != FORMAT {
+        return Err(ProfileError::UnknownFormat(manifest.format));
+    }
+    if update::is_newer(&manifest.version, env!("CARGO_PKG_VERSION")) {
+        return Err(ProfileError::Newer(manifest.version));
+    }
+    let mut changes = Vec::new();
+    for (dir, part, name) in parts(&data_dir, &config_dir) {
+        let from = staging.path.join(part).join(name);
+        let Ok(incoming) = fs::read(&from) else {
+            continue;
+        };
+        let to = dir.join(name);
+        // The history is compared as the backend hands it out
+        let local = match name {
+            HISTORY_FILE => local_history(&staging)?,
+            _ => Some(to.clone()),
+        };
+        let contents = match local.as_deref().map(|local| (local, fs::read(local))) {
+            None | Some((_, Err(_))) => incoming,
+            Some((_, Ok(current))) if current == incoming => continue,
+            Some((local, Ok(_))) => {
+                let (conflict, merged) = compare(name, local, &from);
+                match resolve(&conflict) {
+                    Resolution::KeepLocal => {
+                        changes.push((name, Resolution::KeepLocal));
+                        continue;
+                    }
+                    Resolution::Merge if let Some(merged) = merged => {
+                        changes.push((name, Resolution::Merge));
+                        merged.into_bytes()
+                    }
+                    _ => {
+                        changes.push((name, Resolution::KeepArchive));
+                        incoming
+                    }
+                }
+            }
+        };
+        fs::create_dir_all(dir).map_err(|e| ProfileError::Io(dir.to_path_buf(), e))?;
+        let backup = dir.join(format!("{name}.bak"));
+        match local.filter(|local| local.exists()) {
+            // The history's copy in staging goes, the backend keeps the original
+            Some(local) if name == HISTORY_FILE => {
+                fs::copy(&local, &backup).map_err(|e| ProfileError::Io(backup, e))?;
+            }
+            Some(local) => fs::rename(&local, &backup).map_err(|e| ProfileError::Io(backup, e))?,
+            None => changes.push((name, Resolution::KeepArchive)),
+        }
+        if name == HISTORY_FILE {
+            let records: Vec<GameRecord> = serde_json::from_slice(&contents)
+                .map_err(|e| ProfileError::Io(from.clone(), io::Error::other(e)))?;
+            history_store::current()
+                .replace(&records)
+                .map_err(|e| ProfileError::Io(to, e))?;
+        } else {
+            fs::write(&to, contents).map_err(|e| ProfileError::Io(to, e))?;
+        }
+    }
+    Ok((manifest, changes))
+}
+
+/// Writes this machine's history, as the backend in use hands it out, to a
+/// JSON file in `staging`. None when there are no games yet.
+fn local_history(staging: &Staging) -> Result<Option<PathBuf>, ProfileError> {
+    let path = staging.path.join("local").join(HISTORY_FILE);
+    let records = history_store::current()
+        .load()
+        .map_err(|e| ProfileError::Io(path.clone(), e))?;
+    if records.is_empty() {
+        return Ok(None);
+    }
+    fs::write(&path, to_json(&records)).map_err(|e| ProfileError::Io(path.clone(), e))?;
+    Ok(Some(path))
+}
+
+/// Describes both sides of a differing file, along with the merged contents
+/// for the files that can be merged: the history, the daily streak and the
+/// ladder.
+fn compare(name: &'static str, local: &Path, archive: &Path) -> (Conflict, Option<String>) {
+    let describe = |path| describe(name, path).unwrap_or_else(|| "unreadable".to_string());
+    let (merged, contents) = merge(name, local, archive).unzip();
+    let conflict = Conflict {
+        name,
+        local: describe(local),
+        archive: describe(archive),
+        merged,
+    };
+    (conflict, contents)
+}
+
+fn describe(name: &str, path: &Path) -> Option<String> {
+    match name {
+        HISTORY_FILE => read::<Vec<GameRecord>>(path).map(|records| describe_history(&records)),
+        DAILY_FILE => read::<DailyProgress>(path).map(|progress| describe_daily(&progress)),
+        LADDER_FILE => read::<LadderProgress>(path).map(|progress| describe_ladder(&progress)),
+        SAVE_FILE => read::<SavedGame>(path).map(|save| describe_save(&save)),
+        _ => {
+            let lines = fs::read_to_string(path).ok()?.lines().count();
+            Some(format!("{} of settings", count(lines, "line")))
+        }
+    }
+}
+
+/// The merged file's description and contents, when both sides can be read
+/// and the file is one that merges.
+fn merge(name: &str, local: &Path, archive: &Path) -> Option<(String, String)> {
+    match name {
+        HISTORY_FILE => {
+            let records = history::merge(read(local)?, read(archive)?);
+            Some((describe_history(&records), to_json(&records)))
+        }
+        DAILY_FILE => {
+            let progress = read::<DailyProgress>(local)?.merged(&read(archive)?);
+            Some((describe_daily(&progress), to_json(&progress)))
+        }
+        LADDER_FILE => {
+            let progress = read::<LadderProgress>(local)?.merged(&read(archive)?);
+            Some((describe_ladder(&progress), to_json(&progress)))
+        }
+        _ => None,
+    }
+}
+
+fn read<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+fn describe_history(records: &[GameRecord]) -> String {
+    let solved = records
+        .iter()
+        .filter(|record| record.outcome == Outcome::Solved)
+        .count();
+    let last = records
+        .iter()
+        .map(|record| record.finished_at)
+        .max()
+        .map_or(String::new(), |ts| format!(", last on {}", date(ts)));
+    format!("{}, {solved} solved{last}", count(records.len(), "game"))
+}
+
+fn describe_daily(progress: &DailyProgress) -> String {
+    format!(
+        "streak {}, best {}, {} solved",
+        progress.streak,
+        progress.best_streak,
+        count(progress.completed_days.len(), "daily")
+    )
+}
+
+fn describe_ladder(progress: &LadderProgress) -> String {
+    format!(
+        "{} of {} cleared",
+        count(progress.cleared(), "rung"),
+        ladder::RUNGS
+    )
+}
+
+fn describe_save(save: &SavedGame) -> String {
+    format!(
+        "{} {} game {}% filled, saved {}",
+        save.sudoku.difficulty().name(),
+        save.sudoku.variant().name(),
+        save.sudoku.progress_percent(),
+        date(save.saved_at)
+    )
+}
+
+/// `n` and the noun, in the plural unless there is one.
+fn count(n: usize, noun: &str) -> String {
+    match (n, noun.strip_suffix('y')) {
+        (1, _) => format!("1 {noun}"),
+        (_, Some(stem)) => format!("{n} {stem}ies"),
+        _ => format!("{n} {noun}s"),
+    }
+}
+
+fn date(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .unwrap_or_else(|| "????-??-??".to_string())
+}
+
+fn directories() -> Result<(PathBuf, PathBuf), ProfileError> {
+    storage::data_dir()
+        .zip(storage::config_dir())
+        .ok_or(ProfileError::NoDirectory)
+}
+
+/// Every profile file with its home directory and its folder in the archive.
+fn parts<'a>(
+    data_dir: &'a Path,
+    config_dir: &'a Path,
+) -> impl Iterator<Item = (&'a Path, &'static str, &'static str)> {
+    DATA_FILES
+        .into_iter()
+        .map(move |name| (data_dir, "data", name))
+        .chain([(config_dir, "config", CONFIG_FILE)])
+}
+
+/// `path` resolved against the working directory, since tar runs elsewhere.
+fn absolute(path: &Path) -> Result<String, ProfileError> {
+    let cwd = env::current_dir().map_err(|e| ProfileError::Io(path.to_path_buf(), e))?;
+    Ok(cwd.join(path).display().to_string())
+}
+
+fn tar(args: &[&str]) -> Result<(), ProfileError> {
+    let output = Command::new("tar")
+        .args(args)
+        .output()
+        .map_err(|e| ProfileError::Tar(e.to_string()))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(ProfileError::Tar(stderr.trim().to_string()))
+    }
+}
+
+/// Scratch directory the archive is assembled in or unpacked to, removed
+/// again when dropped.
+struct Staging {
+    path: PathBuf,
+}
+
+impl Staging {
+    /// Creates the directory under a random name, failing rather than
+    /// reusing one that already exists, readable by the user alone.
+    fn new() -> Result<Self, ProfileError> {
+        let mut builder = fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        let mut attempts = 0;
+        let path = loop {
+            let path =
+                env::temp_dir().join(format!("{APP_NAME}-profile-{:016x}", rand::random::<u64>()));
+            match builder.create(&path) {
+                Ok(()) => break path,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempts < 8 => attempts += 1,
+                Err(e) => return Err(ProfileError::Io(path, e)),
+            }
+        };
+        for part in ["data", "config", "local"] {
+            fs::create_dir_all(path.join(part)).map_err(|e| ProfileError::Io(path.clone(), e))?;
+        }
+        Ok(Staging { path })
+    }
+
+    fn display(&self) -> String {
+        self.path.display().to_string()
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::sudoku::{Difficulty, MAX_CLUES, MIN_CLUES, SIZE, SudokuGrid, Symmetry, Variant};
+use std::fmt;
+
+/// Label in front of the ID in export footers.
+pub const FOOTER_LABEL: &str = "Puzzle ID:";
+
+/// FNV-1a, a small stable hash for checksums that must match across builds.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Short hash of the puzzle and every digit on the board, for checking that
+/// two clients show the same game, e.g. a stream and a viewer following it.
+/// Pencil marks and how the board is turned on screen don't count.
+pub fn board_hash(sudoku: &SudokuGrid) -> String {
+    let mut text = PuzzleId::of(sudoku).to_string();
+    text.push(':');
+    for row in sudoku.board() {
+        text.extend(row.iter().map(|&digit| char::from(b'0' + digit)));
+    }
+    let hash = fnv1a(text.as_bytes());
+    format!("{:08x}", (hash ^ hash >> 32) as u32)
+}
+
+/// Shareable identifier that reloads a puzzle exactly.
+///
+/// Generated puzzles are named by their seed, e.g. `CM-00c0ffee00c0ffee`
+/// (variant letter, difficulty letter, seed in hex). A custom difficulty
+/// takes its clue count in place of the letter, as in `C30-00c0ffee00c0ffee`,
+/// and symmetric clues add `-R` (rotational) or `-M` (mirror) at the end. Seeds depend on the
+/// generator, so exports print the app version next to the ID. Imported and
+/// practice puzzles have no seed and are named by their clues instead, e.g.
+/// `C-53..7....6..195...` with all 81 cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PuzzleId {
+    Seeded {
+        variant: Variant,
+        difficulty: Difficulty,
+        seed: u64,
+        symmetry: Symmetry,
+    },
+    Clues {
+        variant: Variant,
+        clues: [[u8; SIZE]; SIZE],
+    },
+}
+
+impl PuzzleId {
+    pub fn of(sudoku: &SudokuGrid) -> Self {
+        let variant = sudoku.variant();
+        match sudoku.seed() {
+            Some(seed) => PuzzleId::Seeded {
+                variant,
+                difficulty: sudoku.difficulty(),
+                seed,
+                symmetry: sudoku.symmetry(),
+            },
+            None => PuzzleId::Clues {
+                variant,
+                clues: std::array::from_fn(|r| {
+                    std::array::from_fn(|c| {
+                        if sudoku.is_fixed(r, c) {
+                            sudoku.solution_value(r, c)
+                        } else {
+                            0
+                        }
+                    })
+                }),
+            },
+        }
+    }
+
+    /// Reads an ID as printed by `Display`, ignoring surrounding whitespace.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (prefix, body) = text.trim().split_once('-')?;
+        let mut letters = prefix.chars();
+        let variant = variant_from_letter(letters.next()?)?;
+        match letters.as_str() {
+            "" => {
+                let cells: Vec<u8> = body
+                    .chars()
+                    .map(|ch| match ch {
+                        '.' => Some(0),
+                        '1'..='9' => ch.to_digit(10).map(|d| d as u8),
+                        _ => None,
+                    })
+                    .collect::<Option<_>>()?;
+                if cells.len() != SIZE * SIZE {
+                    return None;
+                }
+                Some(PuzzleId::Clues {
+                    variant,
+                    clues: std::array::from_fn(|r| std::array::from_fn(|c| cells[r * SIZE + c])),
+                })
+            }
+            code => {
+                let (seed, symmetry) = match body.split_once('-') {
+                    Some((seed, letter)) => (seed, symmetry_from_letter(letter)?),
+                    None => (body, Symmetry::None),
+                };
+                Some(PuzzleId::Seeded {
+                    variant,
+                    difficulty: difficulty_from_code(code)?,
+                    seed: u64::from_str_radix(seed, 16).ok()?,
+                    symmetry,
+                })
+            }
+        }
+    }
+
+    /// Finds the first ID in pasted text, such as the footer of an export.
+    pub fn find(text: &str) -> Option<Self> {
+        text.lines().find_map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix(FOOTER_LABEL).unwrap_or(line);
+            Self::parse(line.split_whitespace().next()?)
+        })
+    }
+
+    /// Rebuilds the puzzle, or None if the clues have no solution.
+    pub fn load(&self) -> Option<SudokuGrid> {
+        match *self {
+            PuzzleId::Seeded {
+                variant,
+                difficulty,
+                seed,
+                symmetry,
+            } => Some(SudokuGrid::symmetric(difficulty, variant, symmetry, seed)),
+            PuzzleId::Clues { variant, clues } => {
+                SudokuGrid::from_clues(clues, variant).map(|(sudoku, _)| sudoku)
+            }
+        }
+    }
+}
+
+impl fmt::Display for PuzzleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PuzzleId::Seeded {
+                variant,
+                difficulty,
+                seed,
+                symmetry,
+            } => {
+                write!(
+                    f,
+                    "{}{}-{seed:016x}",
+                    variant_letter(*variant),
+                    difficulty_code(*difficulty)
+                )?;
+                match symmetry {
+                    Symmetry::None => Ok(()),
+                    Symmetry::Rotational => write!(f, "-R"),
+                    Symmetry::Mirror => write!(f, "-M"),
+                }
+            }
+            PuzzleId::Clues { variant, clues } => {
+                write!(f, "{}-", variant_letter(*variant))?;
+                for &value in clues.iter().flatten() {
+                    if value == 0 {
+                        write!(f, ".")?;
+                    } else {
+                        write!(f, "{value}")?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn variant_letter(variant: Variant) -> char {
+    match variant {
+        Variant::Classic => 'C',
+        Variant::Diagonal => 'X',
+        Variant::Windoku => 'W',
+        Variant::AntiKnight => 'K',
+    }
+}
+
+fn variant_from_letter(letter: char) -> Option<Variant> {
+    Variant::ALL
+        .into_iter()
+        .find(|&variant| variant_letter(variant) == letter.to_ascii_uppercase())
+}
+
+fn symmetry_from_letter(letter: &str) -> Option<Symmetry> {
+    match letter.to_ascii_uppercase().as_str() {
+        "R" => Some(Symmetry::Rotational),
+        "M" => Some(Symmetry::Mirror),
+        _ => None,
+    }
+}
+
+/// A letter for a named or minimal difficulty, the clue count for a custom one.
+pub fn difficulty_code(difficulty: Difficulty) -> String {
+    match difficulty {
+        Difficulty::Easy => "E".to_string(),
+        Difficulty::Medium => "M".to_string(),
+        Difficulty::Hard => "H".to_string(),
+        Difficulty::Expert => "X".to_string(),
+        Difficulty::Minimal => "N".to_string(),
+        Difficulty::Custom(clues) => clues.to_string(),
+    }
+}
+
+pub fn difficulty_from_code(code: &str) -> Option<Difficulty> {
+    if let Ok(clues) = code.parse::<u8>() {
+        return (MIN_CLUES..=MAX_CLUES)
+            .contains(&clues)
+            .then_some(Difficulty::Custom(clues));
+    }
+    Difficulty::ALL
+        .into_iter()
+        .chain([Difficulty::Minimal])
+        .find(|&difficulty| difficulty_code(difficulty).eq_ignore_ascii_case(code))
+}
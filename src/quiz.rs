@@ -0,0 +1,101 @@
+use crate::save::Move;
+use crate::solver::{Board, Solver, Technique};
+use crate::sudoku::SudokuGrid;
+use rand::Rng;
+use rand::seq::{IndexedRandom, SliceRandom};
+
+/// Answers offered for each question.
+const CHOICES: usize = 4;
+
+/// A question on one position of a solved game: which technique finds the
+/// digit the player placed in `cell` there.
+#[derive(Clone, Debug)]
+pub struct Quiz {
+    /// The correct entries and givens just before the move, 0 for empty cells
+    pub board: Board,
+    pub cell: (usize, usize),
+    pub digit: u8,
+    pub answer: Technique,
+    pub choices: Vec<Technique>,
+    pub selected: usize,
+    /// The choice given, once answered
+    pub picked: Option<usize>,
+}
+
+impl Quiz {
+    /// Picks a position from the game's log: one of the hand entries whose
+    /// digit could be found logically, the hardest to find preferred, so the
+    /// question is not always a naked single. None when no entry qualifies.
+    pub fn from_game(sudoku: &SudokuGrid, moves: &[Move], rng: &mut impl Rng) -> Option<Self> {
+        let mut board = sudoku.restarted().board();
+        let mut found = Vec::new();
+        for entry in moves {
+            let (r, c) = (entry.row, entry.col);
+            let correct = entry.digit != 0 && entry.digit == sudoku.solution_value(r, c);
+            if correct && !entry.assisted && board[r][c] != entry.digit {
+                // Wrong entries standing at the time are left out, as the
+                // solver reasons from correct digits only
+                let position = strip_wrong(sudoku, board);
+                let solver = Solver::new(position, sudoku.variant());
+                if let Some((digit, technique)) = solver.technique_for((r, c))
+                    && digit == entry.digit
+                {
+                    found.push((position, (r, c), digit, technique));
+                }
+            }
+            board[r][c] = entry.digit;
+        }
+        let hardest = found.iter().map(|&(.., technique)| technique).max()?;
+        found.retain(|&(.., technique)| technique == hardest);
+        let &(board, cell, digit, answer) = found.choose(rng)?;
+        let mut others: Vec<Technique> = Technique::ALL
+            .into_iter()
+            .filter(|&technique| technique != answer)
+            .collect();
+        others.shuffle(rng);
+        let mut choices: Vec<Technique> = others.into_iter().take(CHOICES - 1).collect();
+        choices.push(answer);
+        choices.shuffle(rng);
+        Some(Quiz {
+            board,
+            cell,
+            digit,
+            answer,
+            choices,
+            selected: 0,
+            picked: None,
+        })
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.picked.is_none() {
+            let count = self.choices.len() as isize;
+            self.selected = (self.selected as isize + delta).rem_euclid(count) as usize;
+        }
+    }
+
+    /// Answers with the choice at `index`; only the first answer counts.
+    pub fn pick(&mut self, index: usize) {
+        if self.picked.is_none() && index < self.choices.len() {
+            self.selected = index;
+            self.picked = Some(index);
+        }
+    }
+
+    /// Whether the answer given, if any, was right.
+    pub fn correct(&self) -> Option<bool> {
+        self.picked.map(|index| self.choices[index] == self.answer)
+    }
+}
+
+/// The position with every entry that differs from the solution emptied.
+fn strip_wrong(sudoku: &SudokuGrid, mut board: Board) -> Board {
+    for (r, row) in board.iter_mut().enumerate() {
+        for (c, digit) in row.iter_mut().enumerate() {
+            if *digit != sudoku.solution_value(r, c) {
+                *digit = 0;
+            }
+        }
+    }
+    board
+}
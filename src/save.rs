@@ -0,0 +1,144 @@
+use crate::config::Validation;
+use crate::storage;
+use crate::sudoku::SudokuGrid;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+pub const SAVE_FILE: &str = "autosave.json";
+
+/// A free-text note the player attached to a game.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Comment {
+    /// Game clock when the note was written
+    pub elapsed_secs: u64,
+    /// Wall-clock time the note was written (unix seconds)
+    pub written_at: i64,
+    pub text: String,
+}
+
+/// A link the player drew between the same digit in two cells, while
+/// working out a chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Link {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+    pub digit: u8,
+    /// At least one of the cells holds the digit; a weak link only says
+    /// they can't both
+    pub strong: bool,
+}
+
+impl Link {
+    /// The cell at the other end from `cell`, if the link touches it.
+    pub fn other_end(&self, cell: (usize, usize)) -> Option<(usize, usize)> {
+        if self.from == cell {
+            Some(self.to)
+        } else if self.to == cell {
+            Some(self.from)
+        } else {
+            None
+        }
+    }
+}
+
+/// One entry or clearing of a cell, time-stamped with the game clock.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Move {
+    pub elapsed_ms: u64,
+    pub row: usize,
+    pub col: usize,
+    /// Digit entered, or 0 when the cell was cleared
+    pub digit: u8,
+    /// Filled by the naked singles assist rather than by hand
+    #[serde(default)]
+    pub assisted: bool,
+}
+
+/// A pencil mark the player removed, logged when elimination logging is on.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Elimination {
+    pub elapsed_ms: u64,
+    pub row: usize,
+    pub col: usize,
+    pub digit: u8,
+    /// Digits the solver could rule out of the cell at that moment
+    pub removable: Vec<u8>,
+}
+
+impl Elimination {
+    /// Whether the solver agreed the removed digit could go.
+    pub fn justified(&self) -> bool {
+        self.removable.contains(&self.digit)
+    }
+}
+
+/// An unfinished game, written on quit so it can be picked up later.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub sudoku: SudokuGrid,
+    pub elapsed_secs: u64,
+    pub comments: Vec<Comment>,
+    /// Score points lost to hints so far
+    #[serde(default)]
+    pub penalty: u32,
+    /// Date of the daily puzzle, if this is one
+    #[serde(default)]
+    pub daily: Option<NaiveDate>,
+    /// Every move made so far, oldest first
+    #[serde(default)]
+    pub moves: Vec<Move>,
+    /// Hints asked for so far, which sets the cost of the next one
+    #[serde(default)]
+    pub hints_used: u32,
+    /// Guarded solution peeks used so far
+    #[serde(default)]
+    pub peeks_used: u32,
+    /// Logged pencil-mark removals, oldest first
+    #[serde(default)]
+    pub eliminations: Vec<Elimination>,
+    /// Boards submitted before they were solved, in manual-submit mode
+    #[serde(default)]
+    pub wrong_submissions: u32,
+    /// Digits entered against the solution so far
+    #[serde(default)]
+    pub mistakes: u32,
+    /// Mistakes that end the game, when it was started with limited mistakes
+    #[serde(default)]
+    pub mistake_limit: Option<u32>,
+    /// Chain links drawn between cells
+    #[serde(default)]
+    pub links: Vec<Link>,
+    /// Recalls left, when the game is played in memory mode
+    #[serde(default)]
+    pub memory_recalls: Option<u32>,
+    /// Played as a zen game, without clock, validation feedback or mistakes
+    #[serde(default)]
+    pub zen: bool,
+    /// Seconds the game must be solved in, when played as time attack
+    #[serde(default)]
+    pub countdown: Option<u64>,
+    /// Rung of the practice ladder the game is, counted from 0
+    #[serde(default)]
+    pub ladder_rung: Option<usize>,
+    /// What entries are checked against in this game
+    #[serde(default)]
+    pub validation: Validation,
+    /// Wall-clock time of the save (unix seconds)
+    pub saved_at: i64,
+}
+
+impl SavedGame {
+    pub fn load() -> Option<Self> {
+        storage::read_json(SAVE_FILE)
+    }
+
+    pub fn write(&self) -> io::Result<()> {
+        storage::write_json(SAVE_FILE, self)
+    }
+
+    /// Deletes the saved game, e.g. once it has been finished.
+    pub fn discard() -> io::Result<()> {
+        storage::remove(SAVE_FILE)
+    }
+}
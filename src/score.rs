@@ -0,0 +1,70 @@
+use crate::solver::Technique;
+use crate::sudoku::Difficulty;
+
+/// Points deducted when each hint tier is revealed: region, technique, placement.
+pub const HINT_TIER_PENALTIES: [u32; 3] = [10, 25, 50];
+
+/// Scales a per-hint cost for the `nth` hint of a game (1-based): every hint
+/// after the first costs another `escalation_percent` of the base cost.
+pub fn escalated(base: u32, nth: u32, escalation_percent: u32) -> u32 {
+    base.saturating_add(base * escalation_percent / 100 * nth.saturating_sub(1))
+}
+
+/// Points deducted for revealing `tier` (1-based) of the `nth` hint.
+pub fn hint_penalty(tier: usize, nth: u32, escalation_percent: u32) -> u32 {
+    escalated(HINT_TIER_PENALTIES[tier - 1], nth, escalation_percent)
+}
+
+/// Points deducted for revealing a cell's digit as the `nth` hint: the price
+/// of a hint taken through all its tiers.
+pub fn reveal_penalty(nth: u32, escalation_percent: u32) -> u32 {
+    (1..=HINT_TIER_PENALTIES.len())
+        .map(|tier| hint_penalty(tier, nth, escalation_percent))
+        .sum()
+}
+
+/// Price of a hint in percent of the flat price, under dynamic pricing. The
+/// technique's weight is scaled from 150% on an empty board down to 50% on
+/// a nearly full one, so a missed single late in the game is cheap and a
+/// pair early on is dear. `None`, for a reveal or a step beyond the solver,
+/// is priced like the hardest technique.
+pub fn dynamic_percent(technique: Option<Technique>, progress_percent: usize) -> u32 {
+    let weight = match technique {
+        Some(Technique::NakedSingle) => 50,
+        Some(Technique::HiddenSingle) => 75,
+        Some(Technique::LockedCandidates) => 150,
+        Some(Technique::NakedPair) => 200,
+        Some(Technique::HiddenPair) | None => 250,
+    };
+    let earliness = 150 - progress_percent.min(100) as u32;
+    weight * earliness / 100
+}
+
+/// A cost scaled by `percent`, rounded to the nearest point and at least one
+/// when the cost itself isn't zero.
+pub fn scaled(cost: u32, percent: u32) -> u32 {
+    match cost {
+        0 => 0,
+        _ => ((cost * percent + 50) / 100).max(1),
+    }
+}
+
+/// Starting points for a game before time and penalties are deducted.
+pub fn base_points(difficulty: Difficulty) -> u32 {
+    match difficulty.rated() {
+        Difficulty::Easy => 1000,
+        Difficulty::Medium => 2000,
+        Difficulty::Hard => 3000,
+        // Minimal and custom difficulties are rated as named ones, so this is never reached for them
+        Difficulty::Expert | Difficulty::Minimal | Difficulty::Custom(_) => 4000,
+    }
+}
+
+/// Score for a game: the difficulty's base points, minus a point per second
+/// of play, minus accumulated penalties. Never goes below zero.
+pub fn score(difficulty: Difficulty, elapsed_secs: u64, penalty: u32) -> u32 {
+    let elapsed = u32::try_from(elapsed_secs).unwrap_or(u32::MAX);
+    base_points(difficulty)
+        .saturating_sub(elapsed)
+        .saturating_sub(penalty)
+}
@@ -0,0 +1,122 @@
+use crate::animation::{Animation, Effect};
+use crate::solver::{Solver, Technique};
+use crate::sudoku::{Difficulty, SIZE, SudokuGrid, Variant};
+use rand::seq::IndexedRandom;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Time between two digits placed by the demo solver.
+const PLACEMENT: Duration = Duration::from_millis(300);
+
+/// Ambient demo for an idle menu: random boards filled in by the logical
+/// solver one digit at a time, each finished with the celebration effect.
+pub struct Screensaver {
+    pub puzzle: SudokuGrid,
+    /// Digits in the order the solver places them, with the technique that found each
+    placements: Vec<((usize, usize, u8), Option<Technique>)>,
+    started: Instant,
+    /// Plays once the board is full; a new board follows when it ends
+    pub celebration: Option<Animation>,
+    /// The board after this one, being made while this one plays
+    next: Option<JoinHandle<Screensaver>>,
+}
+
+impl Screensaver {
+    /// Makes a screensaver on a background thread, since generating the
+    /// board and working out the solver's placements would stall the menu.
+    /// Show it with `started` once it is done.
+    pub fn in_background() -> JoinHandle<Self> {
+        thread::spawn(Screensaver::new)
+    }
+
+    fn new() -> Self {
+        let rng = &mut rand::rng();
+        let difficulty = *Difficulty::ALL.choose(rng).unwrap_or(&Difficulty::Medium);
+        let variant = *Variant::ALL.choose(rng).unwrap_or(&Variant::Classic);
+        let puzzle = SudokuGrid::new(difficulty, variant);
+        Screensaver {
+            placements: placements(&puzzle),
+            puzzle,
+            started: Instant::now(),
+            celebration: None,
+            next: None,
+        }
+    }
+
+    /// The screensaver with its solver starting now, as it is shown, and the
+    /// board after it under way.
+    pub fn started(self) -> Self {
+        Screensaver {
+            started: Instant::now(),
+            next: Some(Screensaver::in_background()),
+            ..self
+        }
+    }
+
+    /// Digits placed so far.
+    fn placed(&self) -> usize {
+        let steps = self.started.elapsed().as_millis() / PLACEMENT.as_millis();
+        (steps as usize).min(self.placements.len())
+    }
+
+    /// The board as far as the solver has got, 0 for empty.
+    pub fn board(&self) -> [[u8; SIZE]; SIZE] {
+        let mut board = self.puzzle.board();
+        for &((r, c, digit), _) in &self.placements[..self.placed()] {
+            board[r][c] = digit;
+        }
+        board
+    }
+
+    /// The digit placed last, with the technique that found it unless it was
+    /// taken from the solution.
+    pub fn latest(&self) -> Option<((usize, usize, u8), Option<Technique>)> {
+        self.placed()
+            .checked_sub(1)
+            .map(|index| self.placements[index])
+    }
+
+    /// Celebrates a full board, then moves on to a new one. The full board
+    /// stays up should the next one still be in the making.
+    pub fn update(&mut self) {
+        match &self.celebration {
+            Some(celebration) if celebration.is_finished() => match self.next.take() {
+                Some(next) if next.is_finished() => {
+                    if let Ok(next) = next.join() {
+                        *self = next.started();
+                    }
+                }
+                Some(next) => self.next = Some(next),
+                None => self.next = Some(Screensaver::in_background()),
+            },
+            Some(_) => {}
+            None if self.placed() == self.placements.len() => {
+                self.celebration = Some(Animation::new(Effect::Celebration));
+            }
+            None => {}
+        }
+    }
+}
+
+/// The solver's placements for a puzzle. Should its techniques run out, the
+/// remaining cells are taken from the solution in reading order.
+fn placements(puzzle: &SudokuGrid) -> Vec<((usize, usize, u8), Option<Technique>)> {
+    let mut solver = Solver::new(puzzle.board(), puzzle.variant());
+    let mut placements = Vec::new();
+    while let Some((step, technique)) = solver.next_placement() {
+        if let Some(placement) = step.placement {
+            placements.push((placement, Some(technique)));
+        }
+    }
+    for r in 0..SIZE {
+        for c in 0..SIZE {
+            let placed = placements
+                .iter()
+                .any(|&((pr, pc, _), _)| (pr, pc) == (r, c));
+            if !puzzle.is_fixed(r, c) && !placed {
+                placements.push(((r, c, puzzle.solution_value(r, c)), None));
+            }
+        }
+    }
+    placements
+}
@@ -0,0 +1,50 @@
+/// An option of the settings screen. Each is kept in the config file, under
+/// the table and key a user would write it with by hand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    Theme,
+    PeerHighlight,
+    Validation,
+    ShowTimer,
+    AutoAdvance,
+    Colorblind,
+    UndoSteps,
+}
+
+impl Setting {
+    /// In the order the screen lists them.
+    pub const ALL: [Setting; 7] = [
+        Setting::Theme,
+        Setting::PeerHighlight,
+        Setting::Validation,
+        Setting::ShowTimer,
+        Setting::AutoAdvance,
+        Setting::Colorblind,
+        Setting::UndoSteps,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Setting::Theme => "Color scheme",
+            Setting::PeerHighlight => "Highlight row, column and box",
+            Setting::Validation => "Check entries against",
+            Setting::ShowTimer => "Show the clock",
+            Setting::AutoAdvance => "Move on after a digit",
+            Setting::Colorblind => "Colorblind markers",
+            Setting::UndoSteps => "Undo steps",
+        }
+    }
+
+    /// The table and key holding the setting in the config file.
+    pub fn config_key(self) -> (&'static str, &'static str) {
+        match self {
+            Setting::Theme => ("display", "theme"),
+            Setting::PeerHighlight => ("display", "peer_highlight"),
+            Setting::Validation => ("gameplay", "validation"),
+            Setting::ShowTimer => ("display", "show_timer"),
+            Setting::AutoAdvance => ("gameplay", "auto_advance"),
+            Setting::Colorblind => ("display", "colorblind"),
+            Setting::UndoSteps => ("gameplay", "undo_steps"),
+        }
+    }
+}
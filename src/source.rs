@@ -0,0 +1,455 @@
+use crate::daily;
+use crate::history;
+use crate::import;
+use crate::pregen::Pregenerator;
+use crate::puzzle_id::PuzzleId;
+use crate::sudoku::{Difficulty, SudokuGrid, Symmetry, Variant};
+use rand::seq::IndexedRandom;
+use std::io::{self, Read};
+use std::process::{Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use std::{fmt, fs, path::PathBuf};
+
+/// How long a download or command may run before it is stopped.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Somewhere a new game's puzzle can come from. The menu lists the built-in
+/// sources followed by those registered in the config file.
+pub trait PuzzleSource {
+    fn name(&self) -> &str;
+
+    /// One line for the menu explaining what the source serves.
+    fn description(&self) -> String;
+
+    /// Produces a puzzle for the difficulty and variant selected in the menu,
+    /// with its clues in the symmetry picked before the game. Sources serving
+    /// fixed puzzles may ignore all three.
+    fn fetch(
+        &mut self,
+        difficulty: Difficulty,
+        variant: Variant,
+        symmetry: Symmetry,
+    ) -> Result<SudokuGrid, SourceError>;
+
+    /// Called as the menu selection changes, so slow sources can get ahead.
+    fn prepare(&mut self, _difficulty: Difficulty, _variant: Variant, _symmetry: Symmetry) {}
+
+    /// Collects any background work without blocking.
+    fn poll(&mut self) {}
+}
+
+/// A source registered in the `[[sources]]` tables of the config file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SourceSpec {
+    /// A text file of puzzles
+    File { name: String, path: PathBuf },
+    /// Puzzles downloaded from a URL with `curl`
+    Http { name: String, url: String },
+    /// A shell command printing puzzles, such as a third-party generator
+    Command { name: String, command: String },
+}
+
+#[derive(Debug)]
+pub enum SourceError {
+    Io(String, io::Error),
+    Failed(String, String),
+    NoPuzzles(String),
+    Unsolvable(String),
+    /// Still downloading or running in the background
+    Pending(String),
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Io(name, e) => write!(f, "{name}: {e}"),
+            SourceError::Failed(name, reason) => write!(f, "{name} failed: {reason}"),
+            SourceError::NoPuzzles(name) => write!(f, "{name} has no puzzles to offer"),
+            SourceError::Unsolvable(name) => {
+                write!(f, "{name} gave a puzzle that has no solution")
+            }
+            SourceError::Pending(name) => {
+                write!(f, "{name} is still fetching puzzles; try again in a moment")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+/// The built-in sources, then the registered ones in config order. The
+/// generator starts on a board of each of the menu's `difficulties`, the
+/// `selected` one first.
+pub fn all_sources(
+    registered: &[SourceSpec],
+    difficulties: &[Difficulty],
+    selected: Difficulty,
+    symmetry: Symmetry,
+) -> Vec<Box<dyn PuzzleSource>> {
+    let mut sources: Vec<Box<dyn PuzzleSource>> = vec![
+        Box::new(Generated::new(difficulties, selected, symmetry)),
+        Box::new(DailySeed),
+        Box::new(Archive),
+    ];
+    sources.extend(registered.iter().map(|spec| -> Box<dyn PuzzleSource> {
+        match spec.clone() {
+            SourceSpec::File { name, path } => Box::new(FileSource { name, path }),
+            SourceSpec::Http { name, url } => Box::new(HttpSource {
+                name,
+                url,
+                fetch: Fetch::default(),
+            }),
+            SourceSpec::Command { name, command } => Box::new(CommandSource {
+                name,
+                command,
+                fetch: Fetch::default(),
+            }),
+        }
+    }));
+    sources
+}
+
+/// Fresh boards from the generator, prepared in the background.
+pub struct Generated {
+    pregen: Pregenerator,
+}
+
+impl Generated {
+    /// Queues a classic board of each difficulty in the symmetry new games
+    /// start with, so the first game of any of them needn't wait. The
+    /// `selected` difficulty goes first, being the likeliest first game.
+    pub fn new(difficulties: &[Difficulty], selected: Difficulty, symmetry: Symmetry) -> Self {
+        let mut pregen = Pregenerator::new();
+        for &difficulty in std::iter::once(&selected).chain(difficulties) {
+            pregen.request(difficulty, Variant::Classic, symmetry);
+        }
+        Generated { pregen }
+    }
+}
+
+impl PuzzleSource for Generated {
+    fn name(&self) -> &str {
+        "Generated"
+    }
+
+    fn description(&self) -> String {
+        "A new random board".to_string()
+    }
+
+    fn fetch(
+        &mut self,
+        difficulty: Difficulty,
+        variant: Variant,
+        symmetry: Symmetry,
+    ) -> Result<SudokuGrid, SourceError> {
+        Ok(self.pregen.take(difficulty, variant, symmetry))
+    }
+
+    fn prepare(&mut self, difficulty: Difficulty, variant: Variant, symmetry: Symmetry) {
+        self.pregen.request(difficulty, variant, symmetry);
+    }
+
+    fn poll(&mut self) {
+        self.pregen.poll();
+    }
+}
+
+/// Today's seed applied to the selected settings, so everyone gets the same
+/// board for them each day. Unlike the daily puzzle it keeps no streak.
+pub struct DailySeed;
+
+impl PuzzleSource for DailySeed {
+    fn name(&self) -> &str {
+        "Daily seed"
+    }
+
+    fn description(&self) -> String {
+        "Everyone's board for these settings today".to_string()
+    }
+
+    fn fetch(
+        &mut self,
+        difficulty: Difficulty,
+        variant: Variant,
+        symmetry: Symmetry,
+    ) -> Result<SudokuGrid, SourceError> {
+        // Named difficulties count from 0, so their boards are kept apart from custom ones
+        let difficulty_bits = match difficulty {
+            Difficulty::Custom(clues) => 0x100 | u64::from(clues),
+            Difficulty::Minimal => 0x200,
+            named => Difficulty::ALL
+                .iter()
+                .position(|&d| d == named)
+                .unwrap_or(0) as u64,
+        };
+        let settings = difficulty_bits << 8 | variant as u64;
+        let seed = daily::seed_for(daily::today()) ^ settings;
+        Ok(SudokuGrid::symmetric(difficulty, variant, symmetry, seed))
+    }
+}
+
+/// A puzzle played before, picked from the history and started over.
+pub struct Archive;
+
+impl PuzzleSource for Archive {
+    fn name(&self) -> &str {
+        "Archive"
+    }
+
+    fn description(&self) -> String {
+        "Replay a past game with these settings".to_string()
+    }
+
+    fn fetch(
+        &mut self,
+        difficulty: Difficulty,
+        variant: Variant,
+        _: Symmetry,
+    ) -> Result<SudokuGrid, SourceError> {
+        let played: Vec<SudokuGrid> = history::load()
+            .into_iter()
+            .map(|record| record.sudoku)
+            .filter(|s| s.difficulty() == difficulty && s.variant() == variant)
+            .collect();
+        played
+            .choose(&mut rand::rng())
+            .map(SudokuGrid::restarted)
+            .ok_or_else(|| {
+                SourceError::NoPuzzles(format!(
+                    "The archive of {} {} games",
+                    difficulty.name(),
+                    variant.name()
+                ))
+            })
+    }
+}
+
+struct FileSource {
+    name: String,
+    path: PathBuf,
+}
+
+impl PuzzleSource for FileSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> String {
+        format!("Puzzles from {}", self.path.display())
+    }
+
+    fn fetch(&mut self, _: Difficulty, _: Variant, _: Symmetry) -> Result<SudokuGrid, SourceError> {
+        let text =
+            fs::read_to_string(&self.path).map_err(|e| SourceError::Io(self.name.clone(), e))?;
+        pick_puzzle(&self.name, &text)
+    }
+}
+
+struct HttpSource {
+    name: String,
+    url: String,
+    fetch: Fetch,
+}
+
+impl HttpSource {
+    fn curl(&self) -> Command {
+        let mut curl = Command::new("curl");
+        curl.args(["--fail", "--silent", "--show-error", "--location"])
+            .args(["--max-time", "10"])
+            .arg(&self.url);
+        curl
+    }
+}
+
+impl PuzzleSource for HttpSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> String {
+        format!("Puzzles downloaded from {}", self.url)
+    }
+
+    fn fetch(&mut self, _: Difficulty, _: Variant, _: Symmetry) -> Result<SudokuGrid, SourceError> {
+        let text = self.fetch.take(&self.name);
+        self.fetch.start(&self.name, self.curl());
+        pick_puzzle(&self.name, &text?)
+    }
+
+    fn prepare(&mut self, _: Difficulty, _: Variant, _: Symmetry) {
+        self.fetch.start(&self.name, self.curl());
+    }
+
+    fn poll(&mut self) {
+        self.fetch.poll(&self.name);
+    }
+}
+
+struct CommandSource {
+    name: String,
+    command: String,
+    fetch: Fetch,
+}
+
+impl CommandSource {
+    fn shell(&self) -> Command {
+        let mut command = Command::new(if cfg!(windows) { "cmd" } else { "sh" });
+        command
+            .arg(if cfg!(windows) { "/C" } else { "-c" })
+            .arg(&self.command);
+        command
+    }
+}
+
+impl PuzzleSource for CommandSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> String {
+        format!("Puzzles printed by `{}`", self.command)
+    }
+
+    fn fetch(&mut self, _: Difficulty, _: Variant, _: Symmetry) -> Result<SudokuGrid, SourceError> {
+        let text = self.fetch.take(&self.name);
+        self.fetch.start(&self.name, self.shell());
+        pick_puzzle(&self.name, &text?)
+    }
+
+    fn prepare(&mut self, _: Difficulty, _: Variant, _: Symmetry) {
+        self.fetch.start(&self.name, self.shell());
+    }
+
+    fn poll(&mut self) {
+        self.fetch.poll(&self.name);
+    }
+}
+
+/// What a download or command printed, fetched on a background thread so
+/// the menu never waits on the network or a slow program. The source's
+/// program runs once for each puzzle taken, the next run starting as soon
+/// as one is taken.
+#[derive(Default)]
+struct Fetch {
+    running: Option<JoinHandle<Result<String, SourceError>>>,
+    done: Option<Result<String, SourceError>>,
+}
+
+impl Fetch {
+    /// Runs the program, unless it is running already or its output waits.
+    fn start(&mut self, name: &str, command: Command) {
+        if self.running.is_none() && self.done.is_none() {
+            let name = name.to_string();
+            self.running = Some(thread::spawn(move || run(&name, command)));
+        }
+    }
+
+    /// Collects the output once the program is done. Never blocks.
+    fn poll(&mut self, name: &str) {
+        if self.running.as_ref().is_some_and(JoinHandle::is_finished) {
+            self.done = self.running.take().map(|running| {
+                running.join().unwrap_or_else(|_| {
+                    Err(SourceError::Failed(
+                        name.to_string(),
+                        "stopped unexpectedly".to_string(),
+                    ))
+                })
+            });
+        }
+    }
+
+    /// The output, or `Pending` while the program is still running.
+    fn take(&mut self, name: &str) -> Result<String, SourceError> {
+        self.poll(name);
+        self.done
+            .take()
+            .unwrap_or_else(|| Err(SourceError::Pending(name.to_string())))
+    }
+}
+
+/// Runs a program and returns what it printed. One still running after
+/// `COMMAND_TIMEOUT` is stopped.
+fn run(name: &str, mut command: Command) -> Result<String, SourceError> {
+    let io_error = |e: io::Error| SourceError::Io(name.to_string(), e);
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(io_error)?;
+    // Read on threads of their own, so a full pipe can't stall the program
+    let stdout = child.stdout.take().map(read_all);
+    let stderr = child.stderr.take().map(read_all);
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(io_error)? {
+            break status;
+        }
+        if started.elapsed() >= COMMAND_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(SourceError::Failed(
+                name.to_string(),
+                format!("no puzzles within {} seconds", COMMAND_TIMEOUT.as_secs()),
+            ));
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+    let collect = |reader: Option<JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default()
+    };
+    let (stdout, stderr) = (collect(stdout), collect(stderr));
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        let reason = stderr
+            .lines()
+            .next()
+            .map_or_else(|| status.to_string(), str::to_string);
+        return Err(SourceError::Failed(name.to_string(), reason));
+    }
+    Ok(String::from_utf8_lossy(&stdout).into_owned())
+}
+
+/// Reads a pipe to its end on a background thread.
+fn read_all(mut pipe: impl Read + Send + 'static) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = pipe.read_to_end(&mut bytes);
+        bytes
+    })
+}
+
+/// Picks one puzzle at random from text holding one or more. Puzzles are
+/// separated by blank lines, or given one per line as puzzle IDs or 81-cell
+/// strings. Loose grids are read like pasted imports, under classic rules.
+fn pick_puzzle(name: &str, text: &str) -> Result<SudokuGrid, SourceError> {
+    let mut chunks: Vec<String> = Vec::new();
+    for block in text.split("\n\n").map(str::trim).filter(|b| !b.is_empty()) {
+        let lines: Vec<&str> = block.lines().filter(|l| !l.trim().is_empty()).collect();
+        let one_per_line = lines.iter().all(|line| {
+            PuzzleId::find(line).is_some()
+                || import::parse_loose(line).is_some_and(|p| p.warnings.is_empty())
+        });
+        if one_per_line {
+            chunks.extend(lines.into_iter().map(str::to_string));
+        } else {
+            chunks.push(block.to_string());
+        }
+    }
+    let chunk = chunks
+        .choose(&mut rand::rng())
+        .ok_or_else(|| SourceError::NoPuzzles(name.to_string()))?;
+    if let Some(id) = PuzzleId::find(chunk) {
+        return id
+            .load()
+            .ok_or_else(|| SourceError::Unsolvable(name.to_string()));
+    }
+    let parsed =
+        import::parse_loose(chunk).ok_or_else(|| SourceError::NoPuzzles(name.to_string()))?;
+    SudokuGrid::from_clues(parsed.clues, Variant::Classic)
+        .map(|(sudoku, _)| sudoku)
+        .ok_or_else(|| SourceError::Unsolvable(name.to_string()))
+}
@@ -0,0 +1,205 @@
+use crate::daily::DailyProgress;
+use crate::history::{GameRecord, Outcome};
+use crate::sudoku::Difficulty;
+use chrono::{DateTime, Days, Local, NaiveDate};
+
+/// Totals for the games of one difficulty, worked out from the history file
+/// so they always agree with the archive.
+#[derive(Clone, Copy, Debug)]
+pub struct DifficultyStats {
+    pub difficulty: Difficulty,
+    /// Finished and abandoned games
+    pub played: usize,
+    pub solved: usize,
+    /// Mean and fastest time of the solved games
+    pub average_secs: Option<u64>,
+    pub best_secs: Option<u64>,
+    /// Hints asked for over all games, reveals included
+    pub hints_used: u32,
+}
+
+impl DifficultyStats {
+    /// Solved games in percent of those played, if any were.
+    pub fn win_rate(&self) -> Option<usize> {
+        (self.played > 0).then(|| self.solved * 100 / self.played)
+    }
+}
+
+/// Stats for every difficulty, easiest first and minimal after them,
+/// including ones never played, then a row for each clue count of the
+/// custom games played, fewest clues first.
+pub fn per_difficulty(records: &[GameRecord]) -> Vec<DifficultyStats> {
+    let mut custom: Vec<_> = records
+        .iter()
+        .filter_map(|record| match record.sudoku.difficulty() {
+            Difficulty::Custom(clues) => Some(clues),
+            _ => None,
+        })
+        .collect();
+    custom.sort();
+    custom.dedup();
+    Difficulty::ALL
+        .into_iter()
+        .chain([Difficulty::Minimal])
+        .chain(custom.into_iter().map(Difficulty::Custom))
+        .map(|difficulty| for_difficulty(records, difficulty))
+        .collect()
+}
+
+/// Stats for one difficulty; a custom one counts only games of its clue count.
+pub fn for_difficulty(records: &[GameRecord], difficulty: Difficulty) -> DifficultyStats {
+    let games: Vec<&GameRecord> = records
+        .iter()
+        .filter(|record| record.sudoku.difficulty() == difficulty)
+        .collect();
+    let times: Vec<u64> = games
+        .iter()
+        .filter(|record| record.outcome == Outcome::Solved)
+        .map(|record| record.elapsed_secs)
+        .collect();
+    DifficultyStats {
+        difficulty,
+        played: games.len(),
+        solved: times.len(),
+        average_secs: (!times.is_empty()).then(|| times.iter().sum::<u64>() / times.len() as u64),
+        best_secs: times.iter().copied().min(),
+        hints_used: games.iter().map(|record| record.hints_used).sum(),
+    }
+}
+
+/// Days a weekly report covers, ending with the day it is made.
+const REPORT_DAYS: u64 = 7;
+
+/// The last week of play as a Markdown report, for mail or a journal: the
+/// games played, the best times, the streaks and anything notable, such as a
+/// new personal best. Games count toward the local day they ended on.
+pub fn weekly_report(records: &[GameRecord], daily: &DailyProgress, today: NaiveDate) -> String {
+    let first = today - Days::new(REPORT_DAYS - 1);
+    let (week, before): (Vec<GameRecord>, Vec<GameRecord>) = records
+        .iter()
+        .filter(|record| local_date(record.finished_at).is_some_and(|d| d <= today))
+        .cloned()
+        .partition(|record| local_date(record.finished_at).is_some_and(|d| d >= first));
+    let outcomes = |outcome: Outcome| week.iter().filter(|r| r.outcome == outcome).count();
+    let solved = outcomes(Outcome::Solved);
+    let mut text = format!("# Sudoku week {first} to {today}\n\n## Games\n\n");
+    text.push_str(&format!("- Played: {}", week.len()));
+    if !week.is_empty() {
+        text.push_str(&format!(
+            ", solved {solved} ({}%), abandoned {}, lost {}, given up {}, out of time {}",
+            solved * 100 / week.len(),
+            outcomes(Outcome::Abandoned),
+            outcomes(Outcome::Failed),
+            outcomes(Outcome::GaveUp),
+            outcomes(Outcome::TimedOut)
+        ));
+    }
+    text.push_str(&format!(
+        "\n- Time played: {}\n- Hints: {}, mistakes: {}\n",
+        clock(week.iter().map(|r| r.elapsed_secs).sum()),
+        week.iter().map(|r| r.hints_used).sum::<u32>(),
+        week.iter().map(|r| r.mistakes).sum::<u32>()
+    ));
+
+    text.push_str("\n## Best times\n\n");
+    let this_week = per_difficulty(&week);
+    if solved == 0 {
+        text.push_str("No puzzles solved this week.\n");
+    } else {
+        text.push_str("| Difficulty | Solved | Best | Average |\n|---|---|---|---|\n");
+        for stats in this_week.iter().filter(|s| s.solved > 0) {
+            text.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                stats.difficulty.name(),
+                stats.solved,
+                stats.best_secs.map_or_else(String::new, clock),
+                stats.average_secs.map_or_else(String::new, clock)
+            ));
+        }
+    }
+
+    let dailies = daily
+        .completed_days
+        .iter()
+        .filter(|&&d| d >= first && d <= today)
+        .count();
+    let frozen = daily
+        .frozen_days
+        .iter()
+        .filter(|&&d| d >= first && d <= today)
+        .count();
+    let mut days_played: Vec<NaiveDate> = week
+        .iter()
+        .filter_map(|r| local_date(r.finished_at))
+        .collect();
+    days_played.sort();
+    days_played.dedup();
+    text.push_str(&format!(
+        "\n## Streaks\n\n- Daily streak: {} (best {})\n- Dailies solved this week: {dailies} of {REPORT_DAYS}",
+        days(daily.streak),
+        days(daily.best_streak)
+    ));
+    if frozen > 0 {
+        text.push_str(&format!(", {} covered by a freeze", days(frozen as u32)));
+    }
+    text.push_str(&format!(
+        "\n- Days played: {} of {REPORT_DAYS}\n",
+        days_played.len()
+    ));
+
+    let mut notable = Vec::new();
+    for now in &this_week {
+        let then = for_difficulty(&before, now.difficulty);
+        match (now.best_secs, then.best_secs) {
+            (Some(best), Some(old)) if best < old => notable.push(format!(
+                "New personal best on {}: {} (was {})",
+                now.difficulty.name(),
+                clock(best),
+                clock(old)
+            )),
+            (Some(best), None) => notable.push(format!(
+                "First {} puzzle solved, in {}",
+                now.difficulty.name(),
+                clock(best)
+            )),
+            _ => {}
+        }
+    }
+    let flawless = week
+        .iter()
+        .filter(|r| r.outcome == Outcome::Solved && r.hints_used == 0 && r.mistakes == 0)
+        .count();
+    if flawless > 0 {
+        notable.push(format!("{flawless} solved without hints or mistakes"));
+    }
+    if daily.streak > 0 && daily.streak == daily.best_streak && daily.last_active >= Some(first) {
+        notable.push(format!(
+            "Longest daily streak so far: {}",
+            days(daily.streak)
+        ));
+    }
+    if !notable.is_empty() {
+        text.push_str("\n## Notable\n\n");
+        for line in notable {
+            text.push_str(&format!("- {line}\n"));
+        }
+    }
+    text
+}
+
+/// The local day a game ended on.
+fn local_date(ts: i64) -> Option<NaiveDate> {
+    DateTime::from_timestamp(ts, 0).map(|t| t.with_timezone(&Local).date_naive())
+}
+
+pub fn clock(secs: u64) -> String {
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+fn days(n: u32) -> String {
+    if n == 1 {
+        "1 day".to_string()
+    } else {
+        format!("{n} days")
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Serialize, de::DeserializeOwned};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{env, fs, io, io::Write, path::PathBuf};
+
+const APP_DIR: &str = "tui_sudoku";
+
+/// Cleared by `--no-persist`; see `disable_persistence`.
+static PERSIST: AtomicBool = AtomicBool::new(true);
+
+/// Turns the data and config directories off for the rest of the run, for
+/// shared or read-only systems: reads find nothing and writes are dropped.
+pub fn disable_persistence() {
+    PERSIST.store(false, Ordering::Relaxed);
+}
+
+/// Whether saves, history and settings are read from and written to disk.
+pub fn persistent() -> bool {
+    PERSIST.load(Ordering::Relaxed)
+}
+
+/// Directory holding saves and history, e.g. `~/.local/share/tui_sudoku`.
+/// None in guest mode.
+pub fn data_dir() -> Option<PathBuf> {
+    if !persistent() {
+        return None;
+    }
+    if let Some(dir) = env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join(APP_DIR));
+    }
+    if let Some(dir) = env::var_os("APPDATA").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join(APP_DIR));
+    }
+    env::var_os("HOME")
+        .filter(|d| !d.is_empty())
+        .map(|home| PathBuf::from(home).join(".local/share").join(APP_DIR))
+}
+
+/// Directory holding the user's settings, e.g. `~/.config/tui_sudoku`.
+/// None in guest mode, so the default settings apply.
+pub fn config_dir() -> Option<PathBuf> {
+    if !persistent() {
+        return None;
+    }
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join(APP_DIR));
+    }
+    if let Some(dir) = env::var_os("APPDATA").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join(APP_DIR));
+    }
+    env::var_os("HOME")
+        .filter(|d| !d.is_empty())
+        .map(|home| PathBuf::from(home).join(".config").join(APP_DIR))
+}
+
+/// Reads a JSON file from the data directory. Missing or unreadable files yield None.
+pub fn read_json<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let path = data_dir()?.join(name);
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Writes a JSON file to the data directory, creating the directory if needed.
+/// Does nothing in guest mode.
+pub fn write_json<T: Serialize>(name: &str, value: &T) -> io::Result<()> {
+    if !persistent() {
+        return Ok(());
+    }
+    let dir = data_dir().ok_or_else(|| io::Error::other("no data directory available"))?;
+    fs::create_dir_all(&dir)?;
+    let contents = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+    // Write to a temp file first so a crash never leaves a half-written file behind
+    let tmp = dir.join(format!("{name}.tmp"));
+    fs::write(&tmp, contents)?;
+    fs::rename(tmp, dir.join(name))
+}
+
+/// Appends a line to a text file in the data directory, such as a log.
+/// Does nothing in guest mode.
+pub fn append_line(name: &str, line: &str) -> io::Result<()> {
+    if !persistent() {
+        return Ok(());
+    }
+    let dir = data_dir().ok_or_else(|| io::Error::other("no data directory available"))?;
+    fs::create_dir_all(&dir)?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(name))?;
+    writeln!(file, "{line}")
+}
+
+/// Removes a file from the data directory if it exists.
+pub fn remove(name: &str) -> io::Result<()> {
+    let Some(dir) = data_dir() else {
+        return Ok(());
+    };
+    match fs::remove_file(dir.join(name)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,249 @@
+use ratatui::style::{Color, Modifier, Style};
+use std::fmt;
+
+/// Something that can be true of a grid cell, for rule conditions to test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellFlag {
+    Cursor,
+    /// In the row, column or box of the cursor cell
+    Peer,
+    Given,
+    /// A digit the player entered
+    Entry,
+    Revealed,
+    Empty,
+    /// An empty cell with pencil marks
+    Notes,
+    /// An entry that fails the game's validation: repeating a peer's digit
+    /// while the live check is on, or differing from the solution
+    Invalid,
+    /// A cell the invalid entry under the cursor clashes with
+    Conflict,
+    /// An entry the diff view or a progress check marks as wrong
+    Wrong,
+    /// Part of the region, pattern or placement of a shown hint
+    Hint,
+    /// On a region the variant tints
+    Variant,
+    /// At an end of a chain link, or where one is being drawn from
+    Link,
+}
+
+impl CellFlag {
+    pub const ALL: [CellFlag; 13] = [
+        CellFlag::Cursor,
+        CellFlag::Peer,
+        CellFlag::Given,
+        CellFlag::Entry,
+        CellFlag::Revealed,
+        CellFlag::Empty,
+        CellFlag::Notes,
+        CellFlag::Invalid,
+        CellFlag::Conflict,
+        CellFlag::Wrong,
+        CellFlag::Hint,
+        CellFlag::Variant,
+        CellFlag::Link,
+    ];
+
+    /// The word used for the flag in a condition.
+    pub fn name(self) -> &'static str {
+        match self {
+            CellFlag::Cursor => "cursor",
+            CellFlag::Peer => "peer",
+            CellFlag::Given => "given",
+            CellFlag::Entry => "entry",
+            CellFlag::Revealed => "revealed",
+            CellFlag::Empty => "empty",
+            CellFlag::Notes => "notes",
+            CellFlag::Invalid => "invalid",
+            CellFlag::Conflict => "conflict",
+            CellFlag::Wrong => "wrong",
+            CellFlag::Hint => "hint",
+            CellFlag::Variant => "variant",
+            CellFlag::Link => "link",
+        }
+    }
+
+    fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+/// The flags that hold for one cell.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CellFlags(u16);
+
+impl CellFlags {
+    pub fn set(&mut self, flag: CellFlag, on: bool) {
+        if on {
+            self.0 |= flag.bit();
+        }
+    }
+
+    pub fn has(self, flag: CellFlag) -> bool {
+        self.0 & flag.bit() != 0
+    }
+}
+
+/// A condition over cell flags, such as `cursor and not given`.
+#[derive(Clone, Debug, PartialEq)]
+enum Condition {
+    Flag(CellFlag),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl Condition {
+    fn holds(&self, flags: CellFlags) -> bool {
+        match self {
+            Condition::Flag(flag) => flags.has(*flag),
+            Condition::Not(inner) => !inner.holds(flags),
+            Condition::And(a, b) => a.holds(flags) && b.holds(flags),
+            Condition::Or(a, b) => a.holds(flags) || b.holds(flags),
+        }
+    }
+}
+
+/// Why a condition could not be read.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RuleError {
+    UnknownWord(String),
+    /// The condition stops where a flag or `(` should follow
+    Incomplete,
+    Unexpected(String),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::UnknownWord(word) => {
+                let names: Vec<&str> = CellFlag::ALL.iter().map(|flag| flag.name()).collect();
+                write!(
+                    f,
+                    "unknown word `{word}`; conditions combine {} with `and`, `or`, `not` and parentheses",
+                    names.join(", ")
+                )
+            }
+            RuleError::Incomplete => write!(f, "the condition ends too early"),
+            RuleError::Unexpected(token) => write!(f, "unexpected `{token}`"),
+        }
+    }
+}
+
+/// Reads a condition. `not` binds tightest, then `and`, then `or`.
+fn parse(text: &str) -> Result<Condition, RuleError> {
+    let spaced = text.replace('(', " ( ").replace(')', " ) ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+    let mut parser = Parser { tokens, pos: 0 };
+    let condition = parser.or()?;
+    match parser.next() {
+        None => Ok(condition),
+        Some(token) => Err(RuleError::Unexpected(token.to_string())),
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, word: &str) -> bool {
+        let found = self.tokens.get(self.pos) == Some(&word);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Condition, RuleError> {
+        let mut condition = self.and()?;
+        while self.eat("or") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, RuleError> {
+        let mut condition = self.not()?;
+        while self.eat("and") {
+            condition = Condition::And(Box::new(condition), Box::new(self.not()?));
+        }
+        Ok(condition)
+    }
+
+    fn not(&mut self) -> Result<Condition, RuleError> {
+        if self.eat("not") {
+            return Ok(Condition::Not(Box::new(self.not()?)));
+        }
+        match self.next() {
+            None => Err(RuleError::Incomplete),
+            Some("(") => {
+                let condition = self.or()?;
+                if self.eat(")") {
+                    Ok(condition)
+                } else {
+                    Err(RuleError::Incomplete)
+                }
+            }
+            Some(word @ (")" | "and" | "or")) => Err(RuleError::Unexpected(word.to_string())),
+            Some(word) => CellFlag::ALL
+                .into_iter()
+                .find(|flag| flag.name() == word)
+                .map(Condition::Flag)
+                .ok_or_else(|| RuleError::UnknownWord(word.to_string())),
+        }
+    }
+}
+
+/// A `[[rules]]` entry: colors given to every cell its condition holds for,
+/// over what the theme gives it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StyleRule {
+    condition: Condition,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: Option<bool>,
+}
+
+impl StyleRule {
+    pub fn new(
+        when: &str,
+        fg: Option<Color>,
+        bg: Option<Color>,
+        bold: Option<bool>,
+    ) -> Result<Self, RuleError> {
+        Ok(StyleRule {
+            condition: parse(when)?,
+            fg,
+            bg,
+            bold,
+        })
+    }
+
+    /// `style` with this rule applied, if its condition holds.
+    pub fn apply(&self, flags: CellFlags, mut style: Style) -> Style {
+        if !self.condition.holds(flags) {
+            return style;
+        }
+        if let Some(fg) = self.fg {
+            style = style.fg(fg);
+        }
+        if let Some(bg) = self.bg {
+            style = style.bg(bg);
+        }
+        match self.bold {
+            Some(true) => style.add_modifier(Modifier::BOLD),
+            Some(false) => style.remove_modifier(Modifier::BOLD),
+            None => style,
+        }
+    }
+}
@@ -1,28 +1,174 @@
-use rand::seq::SliceRandom;
+use crate::solver::{self, Technique};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+pub use sudoku_engine::grid::{
+    BOX_SIZE, SIZE, Variant, on_anti_diagonal, on_main_diagonal, shares_unit, window_index,
+};
 
-pub const SIZE: usize = 9; // Ensure these are pub
-pub const BOX_SIZE: usize = 3; // Ensure these are pub
+/// Puzzles made at most while looking for one whose clue count and grade
+/// both suit the difficulty
+const GENERATION_ATTEMPTS: u32 = 30;
 
 /// Represents the game difficulty level.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Difficulty {
     Easy,
     Medium,
     Hard,
+    Expert,
+    /// A minimal puzzle, cleared until every clue left is needed, of any grade
+    Minimal,
+    /// A puzzle with the chosen number of clues, of any grade
+    Custom(u8),
 }
 
+/// Clue counts a custom difficulty can ask for. Fewer than 17 never gives a
+/// unique solution, and more than 60 leaves little to solve.
+pub const MIN_CLUES: u8 = 17;
+pub const MAX_CLUES: u8 = 60;
+/// The fewest clues a custom difficulty can ask for: clearing cells at
+/// random reliably gets down to this many, while below it the generator
+/// stops a clue or more short of the count.
+pub const MIN_CUSTOM_CLUES: u8 = 23;
+
 impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
     /// Returns the approximate number of cells to *keep* for this difficulty.
     fn cells_to_keep(&self) -> usize {
         match self {
-            Difficulty::Easy => 45,   // More clues
-            Difficulty::Medium => 35, // Default
-            Difficulty::Hard => 25,   // Fewer clues
+            Difficulty::Easy => 45,                        // More clues
+            Difficulty::Medium => 35,                      // Default
+            Difficulty::Hard => 25,                        // Fewer clues
+            Difficulty::Expert => 22, // Near the fewest a unique puzzle can have
+            Difficulty::Minimal => usize::from(MIN_CLUES), // Tries every clue, stopping short only at the fewest possible
+            Difficulty::Custom(clues) => usize::from(*clues),
+        }
+    }
+
+    /// Name as shown to the player, e.g. `Hard` or `Custom (30 clues)`.
+    pub fn name(&self) -> String {
+        match self {
+            Difficulty::Custom(clues) => format!("Custom ({clues} clues)"),
+            named => format!("{named:?}"),
+        }
+    }
+
+    /// The named difficulty a custom clue count plays like, for the score
+    /// and the time-attack countdown: the easiest whose clues it has at least.
+    /// Minimal puzzles rate as Expert.
+    pub fn rated(&self) -> Difficulty {
+        match *self {
+            Difficulty::Minimal => Difficulty::Expert,
+            Difficulty::Custom(clues) => Difficulty::ALL
+                .into_iter()
+                .find(|named| usize::from(clues) >= named.cells_to_keep())
+                .unwrap_or(Difficulty::Expert),
+            named => named,
+        }
+    }
+
+    /// The custom difficulty with `delta` more clues, kept within
+    /// `MIN_CUSTOM_CLUES..=MAX_CLUES`. Named difficulties are returned as they are.
+    pub fn with_more_clues(&self, delta: i32) -> Difficulty {
+        match *self {
+            Difficulty::Custom(clues) => Difficulty::Custom(
+                (i32::from(clues) + delta).clamp(i32::from(MIN_CUSTOM_CLUES), i32::from(MAX_CLUES))
+                    as u8,
+            ),
+            named => named,
+        }
+    }
+
+    /// Game-clock seconds a time-attack game of this difficulty must be solved in.
+    pub fn time_attack_secs(&self) -> u64 {
+        match self.rated() {
+            Difficulty::Easy => 10 * 60,
+            Difficulty::Medium => 15 * 60,
+            Difficulty::Hard => 25 * 60,
+            Difficulty::Expert | Difficulty::Minimal | Difficulty::Custom(_) => 40 * 60,
+        }
+    }
+
+    /// Whether a puzzle needing `grade` at most suits this difficulty. Easy
+    /// falls to naked singles, Medium needs hidden singles and Hard locked
+    /// candidates. Expert needs a pair, or more than the solver's techniques.
+    /// Minimal and custom puzzles only ask for their clues.
+    fn accepts(&self, grade: Option<Technique>) -> bool {
+        match (self, grade) {
+            (Difficulty::Minimal | Difficulty::Custom(_), _) => true,
+            (Difficulty::Easy, Some(technique)) => technique == Technique::NakedSingle,
+            (Difficulty::Medium, Some(technique)) => technique == Technique::HiddenSingle,
+            (Difficulty::Hard, Some(technique)) => technique == Technique::LockedCandidates,
+            (Difficulty::Expert, Some(technique)) => technique >= Technique::NakedPair,
+            (Difficulty::Expert, None) => true,
+            (_, None) => false,
+        }
+    }
+}
+
+/// Pattern the clues of a generated puzzle follow, as in newspaper puzzles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// The clues look the same with the board turned half a turn
+    Rotational,
+    /// The clues look the same in a mirror held along the middle column
+    Mirror,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 3] = [Symmetry::None, Symmetry::Rotational, Symmetry::Mirror];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::None => "None",
+            Symmetry::Rotational => "Rotational",
+            Symmetry::Mirror => "Mirror",
+        }
+    }
+
+    /// The next option, wrapping around, for cycling through them.
+    pub fn next(&self) -> Symmetry {
+        let index = Symmetry::ALL.iter().position(|s| s == self).unwrap_or(0);
+        Symmetry::ALL[(index + 1) % Symmetry::ALL.len()]
+    }
+
+    /// The cells cleared together with (r, c): itself and its image, once
+    /// when the cell is its own image, as the centre is.
+    fn group(&self, (r, c): (usize, usize)) -> Vec<(usize, usize)> {
+        let image = match self {
+            Symmetry::None => (r, c),
+            Symmetry::Rotational => (SIZE - 1 - r, SIZE - 1 - c),
+            Symmetry::Mirror => (r, SIZE - 1 - c),
+        };
+        if image == (r, c) {
+            vec![(r, c)]
+        } else {
+            vec![(r, c), image]
         }
     }
 }
 
-#[derive(Clone, Debug)]
+/// Row (or column) order with the three bands shuffled as whole blocks.
+fn shuffled_bands<R: Rng + ?Sized>(rng: &mut R) -> Vec<usize> {
+    let mut bands: Vec<usize> = (0..BOX_SIZE).collect();
+    bands.shuffle(rng);
+    bands
+        .iter()
+        .flat_map(|&band| (0..BOX_SIZE).map(move |i| band * BOX_SIZE + i))
+        .collect()
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SudokuGrid {
     /// The complete solved grid
     solution: [[u8; SIZE]; SIZE],
@@ -30,43 +176,381 @@ pub struct SudokuGrid {
     current: [[u8; SIZE]; SIZE],
     /// Mask indicating which cells are fixed (part of the initial puzzle)
     fixed: [[bool; SIZE]; SIZE],
+    /// Extra rules the puzzle was generated under
+    variant: Variant,
+    difficulty: Difficulty,
+    /// Seed the puzzle was generated from; imported and remapped puzzles have none
+    #[serde(default)]
+    seed: Option<u64>,
+    /// Pattern the clues were removed in, needed with the seed to make the puzzle again
+    #[serde(default)]
+    symmetry: Symmetry,
+    /// The player's pencil marks: bit `d` is set when digit `d` is noted in the cell
+    #[serde(default)]
+    notes: [[u16; SIZE]; SIZE],
+    /// Cells filled from the solution by a reveal hint; they can't be changed
+    #[serde(default)]
+    revealed: [[bool; SIZE]; SIZE],
+    /// How generating the puzzle went; only known in the session that generated it
+    #[serde(skip)]
+    report: Option<GenerationReport>,
+    /// Entries repeating a digit that a peer holds, updated around every
+    /// changed cell so drawing the board never has to check the whole grid
+    #[serde(skip)]
+    clashes: [[bool; SIZE]; SIZE],
+}
+
+/// What a cell holds: its entry (0 = empty) and its pencil marks as a bit set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellState {
+    pub value: u8,
+    pub notes: u16,
+}
+
+/// Generator internals for one puzzle, shown in the debug overlay and logged
+/// so slow generation can be reported with numbers.
+#[derive(Clone, Copy, Debug)]
+pub struct GenerationReport {
+    /// Boards filled before one was kept
+    pub attempts: u32,
+    /// Digits the backtracking fill had to take back
+    pub backtracks: u64,
+    /// Solution counts run to check the puzzle has a single answer
+    pub uniqueness_checks: u32,
+    /// Hardest technique the logical solver needs, or None if it gets stuck
+    pub grade: Option<Technique>,
+    pub elapsed_ms: u64,
+}
+
+impl GenerationReport {
+    /// The report as `key=value` pairs for the generation log.
+    pub fn log_fields(&self) -> String {
+        let grade = self.grade.map_or("none".to_string(), |t| format!("{t:?}"));
+        format!(
+            "attempts={} backtracks={} uniqueness_checks={} grade={grade} elapsed_ms={}",
+            self.attempts, self.backtracks, self.uniqueness_checks, self.elapsed_ms
+        )
+    }
 }
 
 impl SudokuGrid {
-    /// Generates a new Sudoku puzzle for the given difficulty.
-    pub fn new(difficulty: Difficulty) -> Self {
+    /// Generates a new Sudoku puzzle for the given difficulty and variant.
+    pub fn new(difficulty: Difficulty, variant: Variant) -> Self {
+        Self::from_seed(difficulty, variant, rand::random())
+    }
+
+    /// Generates the same puzzle every time for a given seed.
+    pub fn from_seed(difficulty: Difficulty, variant: Variant, seed: u64) -> Self {
+        Self::symmetric(difficulty, variant, Symmetry::None, seed)
+    }
+
+    /// Like `from_seed`, with the clues removed in the symmetry's pattern.
+    pub fn symmetric(
+        difficulty: Difficulty,
+        variant: Variant,
+        symmetry: Symmetry,
+        seed: u64,
+    ) -> Self {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        SudokuGrid {
+            seed: Some(seed),
+            ..Self::generate(difficulty, variant, symmetry, rng)
+        }
+    }
+
+    /// Fills boards until one can be cut down to a puzzle that suits the difficulty.
+    fn generate<R: Rng + ?Sized>(
+        difficulty: Difficulty,
+        variant: Variant,
+        symmetry: Symmetry,
+        rng: &mut R,
+    ) -> Self {
+        // Pairs of clues can't promise that every single clue is needed
+        let symmetry = match difficulty {
+            Difficulty::Minimal => Symmetry::None,
+            _ => symmetry,
+        };
+        let started = Instant::now();
+        let mut backtracks = 0;
+        let mut puzzle = Self::closest_fit(difficulty, started, || {
+            let mut grid = [[0u8; SIZE]; SIZE];
+            let mut generator = Generator::new(&mut grid, variant, rng);
+            generator.fill(); // Fill the grid completely
+            backtracks += generator.backtracks;
+            let settings = (difficulty, variant, symmetry);
+            Self::with_clues_removed(grid, settings, rng, started, backtracks)
+        });
+        if let Some(report) = puzzle.report.as_mut() {
+            report.backtracks = backtracks;
+        }
+        puzzle
+    }
+
+    /// Makes puzzles until one has the difficulty's clue count and a grade it
+    /// accepts, keeping the closest one after `GENERATION_ATTEMPTS`: the right
+    /// grade first, then the fewest extra clues.
+    fn closest_fit(
+        difficulty: Difficulty,
+        started: Instant,
+        mut make: impl FnMut() -> Self,
+    ) -> Self {
+        let misfit = |puzzle: &Self| {
+            let grade = puzzle.report.and_then(|report| report.grade);
+            let extra_clues = match difficulty {
+                // Any minimal puzzle will do, however many clues it needed
+                Difficulty::Minimal => 0,
+                _ => puzzle
+                    .clue_count()
+                    .saturating_sub(difficulty.cells_to_keep()),
+            };
+            (!difficulty.accepts(grade), extra_clues)
+        };
+        let mut best: Option<Self> = None;
+        let mut uniqueness_checks = 0;
+        let mut attempts = 0;
+        while attempts < GENERATION_ATTEMPTS {
+            attempts += 1;
+            let puzzle = make();
+            uniqueness_checks += puzzle.report.map_or(0, |report| report.uniqueness_checks);
+            let fit = misfit(&puzzle);
+            if best.as_ref().is_none_or(|best| fit < misfit(best)) {
+                best = Some(puzzle);
+            }
+            if fit == (false, 0) {
+                break;
+            }
+        }
+        let mut best = best.expect("at least one generation attempt");
+        // The kept puzzle's report covers every attempt made
+        if let Some(report) = best.report.as_mut() {
+            report.attempts = attempts;
+            report.uniqueness_checks = uniqueness_checks;
+            report.elapsed_ms = started.elapsed().as_millis() as u64;
+        }
+        best
+    }
+
+    /// The classic, X and Windoku puzzles of one seed. They share a single
+    /// solution grid that obeys the diagonal and window rules at once, so only
+    /// the clues and the rules differ between them.
+    pub fn cross_variant_set(difficulty: Difficulty, seed: u64) -> [Self; 3] {
+        let started = Instant::now();
+        let rng = &mut StdRng::seed_from_u64(seed);
         let mut grid = [[0u8; SIZE]; SIZE];
-        let mut generator = Generator::new(&mut grid);
-        generator.fill(); // Fill the grid completely
+        let mut generator = Generator::new(&mut grid, Variant::Diagonal, rng);
+        generator.also = Variant::Windoku;
+        generator.fill_most_constrained();
+        let backtracks = generator.backtracks;
+        // Only the clues can be retried, as the three share the filled grid
+        [Variant::Classic, Variant::Diagonal, Variant::Windoku].map(|variant| {
+            Self::closest_fit(difficulty, started, || {
+                let settings = (difficulty, variant, Symmetry::None);
+                Self::with_clues_removed(grid, settings, rng, started, backtracks)
+            })
+        })
+    }
 
-        let solution = grid; // Keep the full solution
+    /// Makes a puzzle from a filled grid by clearing cells in random order until
+    /// the difficulty's number of clues is left. A clear that would let the
+    /// puzzle have a second solution is undone, so fewer cells may be cleared.
+    /// With a symmetry each cell is cleared together with its image. Every
+    /// cell is tried once, so a minimal puzzle has no clue left that could go:
+    /// a clue needed when it was tried is still needed with fewer around it.
+    /// Under a symmetry that would hold for the pairs rather than single
+    /// clues, so minimal puzzles are made without one.
+    fn with_clues_removed<R: Rng + ?Sized>(
+        solution: [[u8; SIZE]; SIZE],
+        (difficulty, variant, symmetry): (Difficulty, Variant, Symmetry),
+        rng: &mut R,
+        started: Instant,
+        backtracks: u64,
+    ) -> Self {
         let mut current = solution; // Start current state from solution
         let mut fixed = [[true; SIZE]; SIZE]; // Assume all fixed initially
 
         let mut cells: Vec<(usize, usize)> =
             (0..SIZE * SIZE).map(|i| (i / SIZE, i % SIZE)).collect();
 
-        // Get a thread-local RNG instance
-        let mut rng = rand::rng();
-        cells.shuffle(&mut rng);
+        cells.shuffle(rng);
 
         let numbers_to_keep = difficulty.cells_to_keep();
         let numbers_to_remove = (SIZE * SIZE).saturating_sub(numbers_to_keep).min(70); // Allow removing more for harder levels, cap reasonably
 
-        // Use enumerate instead of a manual counter
-        for (count, &(r, c)) in cells.iter().enumerate() {
-            if count >= numbers_to_remove {
+        let mut removed = 0;
+        let mut uniqueness_checks = 0;
+        for &cell in &cells {
+            if removed >= numbers_to_remove {
                 break;
             }
-            current[r][c] = 0; // Clear the cell in the user's grid
-            fixed[r][c] = false; // Mark the cell as not fixed
+            let group = symmetry.group(cell);
+            // Images come up again later in the shuffle, already cleared; a
+            // pair that would overshoot waits for a single, such as the centre
+            if group.iter().any(|&(r, c)| current[r][c] == 0)
+                || removed + group.len() > numbers_to_remove
+            {
+                continue;
+            }
+            for &(r, c) in &group {
+                current[r][c] = 0; // Clear the cell in the user's grid
+            }
+            uniqueness_checks += 1;
+            if count_solutions(current, variant, 2).0 != 1 {
+                for &(r, c) in &group {
+                    current[r][c] = solution[r][c]; // Needed to keep the answer unique
+                }
+                continue;
+            }
+            for &(r, c) in &group {
+                fixed[r][c] = false; // Mark the cell as not fixed
+            }
+            removed += group.len();
+        }
+
+        let report = GenerationReport {
+            attempts: 1,
+            backtracks,
+            uniqueness_checks,
+            grade: solver::grade(current, variant),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        };
+        SudokuGrid {
+            solution,
+            current,
+            fixed,
+            variant,
+            difficulty,
+            seed: None,
+            symmetry,
+            notes: [[0; SIZE]; SIZE],
+            revealed: [[false; SIZE]; SIZE],
+            report: Some(report),
+            clashes: [[false; SIZE]; SIZE],
+        }
+    }
+
+    /// Returns an unplayed copy of this puzzle disguised by relabelling the digits,
+    /// rotating the board and (for classic rules) shuffling bands and stacks.
+    /// The logical structure, and so the solving path, is unchanged.
+    pub fn remapped(&self) -> Self {
+        let rng = &mut rand::rng();
+        let mut digits: Vec<u8> = (1..=SIZE as u8).collect();
+        digits.shuffle(rng);
+
+        // Band and stack swaps would break diagonals, windows and knight moves
+        let mut rows: Vec<usize> = (0..SIZE).collect();
+        let mut cols: Vec<usize> = (0..SIZE).collect();
+        if self.variant == Variant::Classic {
+            rows = shuffled_bands(rng);
+            cols = shuffled_bands(rng);
+        }
+        let turns = rng.random_range(0..4);
+        // Source cell for every target cell
+        let source = |r: usize, c: usize| {
+            let (mut r, mut c) = (r, c);
+            for _ in 0..turns {
+                (r, c) = (SIZE - 1 - c, r);
+            }
+            (rows[r], cols[c])
+        };
+
+        let mut solution = [[0u8; SIZE]; SIZE];
+        let mut fixed = [[false; SIZE]; SIZE];
+        for r in 0..SIZE {
+            for c in 0..SIZE {
+                let (sr, sc) = source(r, c);
+                solution[r][c] = digits[self.solution[sr][sc] as usize - 1];
+                fixed[r][c] = self.fixed[sr][sc];
+            }
+        }
+        let mut current = solution;
+        for (row, fixed_row) in current.iter_mut().zip(&fixed) {
+            for (cell, &is_fixed) in row.iter_mut().zip(fixed_row) {
+                if !is_fixed {
+                    *cell = 0;
+                }
+            }
         }
 
         SudokuGrid {
             solution,
             current,
             fixed,
+            variant: self.variant,
+            difficulty: self.difficulty,
+            seed: None,
+            // Band swaps and quarter turns don't keep the pattern
+            symmetry: Symmetry::None,
+            notes: [[0; SIZE]; SIZE],
+            revealed: [[false; SIZE]; SIZE],
+            report: None,
+            clashes: [[false; SIZE]; SIZE],
+        }
+    }
+
+    /// Returns the same puzzle with every entry and pencil mark removed.
+    pub fn restarted(&self) -> Self {
+        let mut sudoku = self.clone();
+        for r in 0..SIZE {
+            for c in 0..SIZE {
+                if !sudoku.fixed[r][c] {
+                    sudoku.current[r][c] = 0;
+                }
+            }
         }
+        sudoku.notes = [[0; SIZE]; SIZE];
+        sudoku.revealed = [[false; SIZE]; SIZE];
+        sudoku.clashes = [[false; SIZE]; SIZE];
+        sudoku
+    }
+
+    /// Builds a puzzle from given clues (0 = empty), solving it for the stored solution.
+    /// Returns the puzzle and its number of solutions, capped at 2, or None if it has none.
+    pub fn from_clues(clues: [[u8; SIZE]; SIZE], variant: Variant) -> Option<(Self, usize)> {
+        let (solutions, solution) = count_solutions(clues, variant, 2);
+        let solution = solution?;
+        let fixed = clues.map(|row| row.map(|v| v != 0));
+        let difficulty = match fixed.iter().flatten().filter(|&&f| f).count() {
+            n if n >= Difficulty::Easy.cells_to_keep() => Difficulty::Easy,
+            n if n >= Difficulty::Medium.cells_to_keep() => Difficulty::Medium,
+            n if n >= Difficulty::Hard.cells_to_keep() => Difficulty::Hard,
+            _ => Difficulty::Expert,
+        };
+        Some((
+            SudokuGrid {
+                solution,
+                current: clues,
+                fixed,
+                variant,
+                difficulty,
+                seed: None,
+                symmetry: Symmetry::None,
+                notes: [[0; SIZE]; SIZE],
+                revealed: [[false; SIZE]; SIZE],
+                report: None,
+                clashes: [[false; SIZE]; SIZE],
+            },
+            solutions,
+        ))
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn symmetry(&self) -> Symmetry {
+        self.symmetry
+    }
+
+    pub fn generation_report(&self) -> Option<GenerationReport> {
+        self.report
     }
 
     pub fn get_cell(&self, r: usize, c: usize, show_solution: bool) -> Option<u8> {
@@ -82,13 +566,127 @@ impl SudokuGrid {
         self.fixed[r][c]
     }
 
+    /// The player's current board, 0 for empty cells.
+    pub fn board(&self) -> [[u8; SIZE]; SIZE] {
+        self.current
+    }
+
+    pub fn solution_value(&self, r: usize, c: usize) -> u8 {
+        self.solution[r][c]
+    }
+
+    /// Number of filled cells whose digit differs from the solution.
+    pub fn wrong_entry_count(&self) -> usize {
+        (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .filter(|&(r, c)| self.is_wrong(r, c))
+            .count()
+    }
+
+    /// Whether the player's entry at (r, c) differs from the solution.
+    pub fn is_wrong(&self, r: usize, c: usize) -> bool {
+        self.current[r][c] != 0 && self.current[r][c] != self.solution[r][c]
+    }
+
+    /// The board with wrong entries cleared, keeping clues and correct entries.
+    pub fn correct_entries(&self) -> [[u8; SIZE]; SIZE] {
+        let mut board = self.current;
+        for (r, row) in board.iter_mut().enumerate() {
+            for (c, cell) in row.iter_mut().enumerate() {
+                if self.is_wrong(r, c) {
+                    *cell = 0;
+                }
+            }
+        }
+        board
+    }
+
+    /// Number of cells still empty.
+    pub fn empty_count(&self) -> usize {
+        self.current.iter().flatten().filter(|&&v| v == 0).count()
+    }
+
+    /// Whether the player has entered any digit of their own.
+    pub fn has_progress(&self) -> bool {
+        (0..SIZE).any(|r| (0..SIZE).any(|c| !self.fixed[r][c] && self.current[r][c] != 0))
+    }
+
+    /// Percentage (0-100) of the initially empty cells that have been filled in.
+    pub fn progress_percent(&self) -> usize {
+        let mut open = 0usize;
+        let mut filled = 0usize;
+        for r in 0..SIZE {
+            for c in 0..SIZE {
+                if !self.fixed[r][c] {
+                    open += 1;
+                    if self.current[r][c] != 0 {
+                        filled += 1;
+                    }
+                }
+            }
+        }
+        (filled * 100).checked_div(open).unwrap_or(100)
+    }
+
+    /// Filled cells (givens included) in each 3x3 box, numbered row by row.
+    pub fn box_fill_counts(&self) -> [usize; SIZE] {
+        let mut counts = [0; SIZE];
+        for r in 0..SIZE {
+            for c in 0..SIZE {
+                if self.current[r][c] != 0 {
+                    counts[(r / BOX_SIZE) * BOX_SIZE + c / BOX_SIZE] += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// How often each digit is on the board (givens included), indexed from
+    /// digit 1. Wrong entries count, so a digit can show up more than nine times.
+    pub fn digit_counts(&self) -> [usize; SIZE] {
+        let mut counts = [0; SIZE];
+        for &value in self.current.iter().flatten() {
+            if value != 0 {
+                counts[value as usize - 1] += 1;
+            }
+        }
+        counts
+    }
+
+    /// The unfinished box with the fewest filled cells, if any box is unfinished.
+    pub fn least_complete_box(&self) -> Option<usize> {
+        let counts = self.box_fill_counts();
+        (0..SIZE)
+            .filter(|&b| counts[b] < SIZE)
+            .min_by_key(|&b| counts[b])
+    }
+
+    /// Number of given clues in the puzzle.
+    pub fn clue_count(&self) -> usize {
+        self.fixed.iter().flatten().filter(|&&f| f).count()
+    }
+
+    /// The difficulty's name, with the clues the puzzle really has for a
+    /// custom one: shared puzzle IDs can still ask for fewer clues than
+    /// the generator reaches.
+    pub fn difficulty_name(&self) -> String {
+        match self.difficulty {
+            Difficulty::Custom(_) => format!("Custom ({} clues)", self.clue_count()),
+            named => named.name(),
+        }
+    }
+
     /// Attempts to set a number in the user's grid.
     /// Returns true if the number was set, false otherwise (e.g., fixed cell).
     pub fn set_number(&mut self, r: usize, c: usize, num: u8) -> bool {
-        if r < SIZE && c < SIZE && !self.fixed[r][c] {
+        if r < SIZE && c < SIZE && !self.fixed[r][c] && !self.revealed[r][c] {
             // Allow setting 0 to clear. num >= 0 is always true for u8.
             if num <= 9 {
                 self.current[r][c] = num;
+                if num != 0 {
+                    self.notes[r][c] = 0;
+                }
+                self.recheck_around(r, c);
                 return true;
             }
         }
@@ -99,6 +697,133 @@ impl SudokuGrid {
         self.set_number(r, c, 0)
     }
 
+    /// Fills an open cell with its solution digit and locks it. Returns the
+    /// digit, or None for givens and cells already revealed.
+    pub fn reveal_cell(&mut self, r: usize, c: usize) -> Option<u8> {
+        if self.fixed[r][c] || self.revealed[r][c] {
+            return None;
+        }
+        self.current[r][c] = self.solution[r][c];
+        self.notes[r][c] = 0;
+        self.revealed[r][c] = true;
+        self.recheck_around(r, c);
+        Some(self.solution[r][c])
+    }
+
+    pub fn is_revealed(&self, r: usize, c: usize) -> bool {
+        self.revealed[r][c]
+    }
+
+    /// Number of cells filled by reveal hints.
+    pub fn revealed_count(&self) -> usize {
+        self.revealed.iter().flatten().filter(|&&r| r).count()
+    }
+
+    /// What a cell holds, e.g. to record it for undo.
+    pub fn cell_state(&self, r: usize, c: usize) -> CellState {
+        CellState {
+            value: self.current[r][c],
+            notes: self.notes[r][c],
+        }
+    }
+
+    /// Puts a cell back the way it was, e.g. to undo a change. Fixed cells are left alone.
+    pub fn restore_cell(&mut self, r: usize, c: usize, state: CellState) {
+        if !self.fixed[r][c] && !self.revealed[r][c] {
+            self.current[r][c] = state.value;
+            self.notes[r][c] = state.notes;
+            self.recheck_around(r, c);
+        }
+    }
+
+    /// Whether the cell's entry repeats a digit one of its peers holds. Clues
+    /// never count as clashing.
+    pub fn clashes(&self, r: usize, c: usize) -> bool {
+        self.clashes[r][c]
+    }
+
+    /// Checks every cell for clashes again, for a board that was read from
+    /// disk rather than built up one change at a time.
+    pub fn recheck_all(&mut self) {
+        for (r, c) in (0..SIZE).flat_map(|r| (0..SIZE).map(move |c| (r, c))) {
+            self.clashes[r][c] = self.clash_at(r, c);
+        }
+    }
+
+    /// Checks the changed cell and its peers, the only cells whose clashes a
+    /// change can affect: at most 20 cells for classic rules.
+    fn recheck_around(&mut self, r: usize, c: usize) {
+        self.clashes[r][c] = self.clash_at(r, c);
+        for (pr, pc) in (0..SIZE).flat_map(|r| (0..SIZE).map(move |c| (r, c))) {
+            if self.variant.sees((r, c), (pr, pc)) {
+                self.clashes[pr][pc] = self.clash_at(pr, pc);
+            }
+        }
+    }
+
+    fn clash_at(&self, r: usize, c: usize) -> bool {
+        let value = self.current[r][c];
+        !self.fixed[r][c] && value != 0 && !self.is_valid_move(r, c, value)
+    }
+
+    /// Digits pencilled into a cell, in ascending order.
+    pub fn notes(&self, r: usize, c: usize) -> Vec<u8> {
+        (1..=SIZE as u8)
+            .filter(|&d| self.notes[r][c] & (1 << d) != 0)
+            .collect()
+    }
+
+    /// Digits the current board still allows in an empty cell, as a bit set
+    /// like the pencil marks; 0 for a filled cell.
+    pub fn candidates(&self, r: usize, c: usize) -> u16 {
+        if self.current[r][c] != 0 {
+            return 0;
+        }
+        (1..=SIZE as u8)
+            .filter(|&d| self.is_valid_move(r, c, d))
+            .fold(0, |mask, d| mask | 1 << d)
+    }
+
+    /// Empty cells the current board leaves a single digit for, with that digit.
+    pub fn naked_singles(&self) -> Vec<(usize, usize, u8)> {
+        (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .filter_map(|(r, c)| {
+                let mask = self.candidates(r, c);
+                (mask.count_ones() == 1).then(|| (r, c, mask.trailing_zeros() as u8))
+            })
+            .collect()
+    }
+
+    /// Erases `digit` from the pencil marks of every peer of (r, c), as
+    /// placing it there rules it out for them. Returns each cell that changed
+    /// with how it was before, so the removal can be undone.
+    pub fn clear_peer_notes(
+        &mut self,
+        r: usize,
+        c: usize,
+        digit: u8,
+    ) -> Vec<((usize, usize), CellState)> {
+        let mut cleared = Vec::new();
+        for (pr, pc) in (0..SIZE).flat_map(|r| (0..SIZE).map(move |c| (r, c))) {
+            if self.notes[pr][pc] & 1 << digit != 0 && self.variant.sees((r, c), (pr, pc)) {
+                cleared.push(((pr, pc), self.cell_state(pr, pc)));
+                self.notes[pr][pc] &= !(1 << digit);
+            }
+        }
+        cleared
+    }
+
+    /// Adds or removes a pencil mark in an empty cell. Returns whether the digit
+    /// is now noted, or None if the cell holds a digit and takes no notes.
+    pub fn toggle_note(&mut self, r: usize, c: usize, digit: u8) -> Option<bool> {
+        if self.current[r][c] != 0 || !(1..=SIZE as u8).contains(&digit) {
+            return None;
+        }
+        self.notes[r][c] ^= 1 << digit;
+        Some(self.notes[r][c] & (1 << digit) != 0)
+    }
+
     /// Checks if the number `num` is valid to place at `(r, c)` in the *current* grid.
     /// Ignores the cell (r, c) itself during the check.
     pub fn is_valid_move(&self, r: usize, c: usize, num: u8) -> bool {
@@ -133,29 +858,138 @@ impl SudokuGrid {
             }
         }
 
+        // Check variant rules
+        if self.variant != Variant::Classic {
+            for row in 0..SIZE {
+                for col in 0..SIZE {
+                    if self.current[row][col] == num && self.variant.sees((r, c), (row, col)) {
+                        return false;
+                    }
+                }
+            }
+        }
+
         true
     }
 
+    /// The cells that keep `num` from `(r, c)`: every peer already holding
+    /// it, under the rules of the variant. Empty when the move is valid.
+    pub fn conflicting_peers(&self, r: usize, c: usize, num: u8) -> Vec<(usize, usize)> {
+        if num == 0 {
+            return Vec::new();
+        }
+        (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .filter(|&(pr, pc)| self.current[pr][pc] == num && self.variant.sees((r, c), (pr, pc)))
+            .collect()
+    }
+
     /// Checks if the current grid matches the solution.
     pub fn is_solved(&self) -> bool {
         self.current == self.solution
     }
 }
 
+/// Counts the solutions of a grid (0 = empty) up to `limit`, also returning the
+/// first solution found. Clues that already clash give no solutions.
+pub fn count_solutions(
+    clues: [[u8; SIZE]; SIZE],
+    variant: Variant,
+    limit: usize,
+) -> (usize, Option<[[u8; SIZE]; SIZE]>) {
+    if !conflicting_cells(&clues, variant).is_empty() {
+        return (0, None);
+    }
+    let mut grid = clues;
+    let mut first = None;
+    let count = Generator::new(&mut grid, variant, &mut rand::rng()).count(limit, &mut first);
+    (count, first)
+}
+
+/// Completes a partly filled grid (0 = empty) with the solver, giving up at
+/// `deadline`. None if the grid has no completion or time ran out first.
+pub fn complete_grid(
+    grid: [[u8; SIZE]; SIZE],
+    variant: Variant,
+    deadline: Instant,
+) -> Option<[[u8; SIZE]; SIZE]> {
+    if !conflicting_cells(&grid, variant).is_empty() {
+        return None;
+    }
+    let mut grid = grid;
+    Generator::new(&mut grid, variant, &mut rand::rng())
+        .complete(deadline)
+        .then_some(grid)
+}
+
+/// Reads a puzzle in the usual one-line notation: 81 cells in reading order,
+/// each a digit 1-9 or `0`/`.` for a blank.
+pub fn parse_line(text: &str) -> Result<[[u8; SIZE]; SIZE], String> {
+    let text = text.trim();
+    let length = text.chars().count();
+    if length != SIZE * SIZE {
+        return Err(format!(
+            "A puzzle line has {} cells, not {length}",
+            SIZE * SIZE
+        ));
+    }
+    let mut clues = [[0; SIZE]; SIZE];
+    for (i, ch) in text.chars().enumerate() {
+        clues[i / SIZE][i % SIZE] = match ch {
+            '1'..='9' => ch as u8 - b'0',
+            '0' | '.' => 0,
+            _ => {
+                return Err(format!(
+                    "Cell {} is `{ch}`; use 1-9 for clues and 0 or . for blanks",
+                    i + 1
+                ));
+            }
+        };
+    }
+    Ok(clues)
+}
+
+/// Filled cells that share a digit with another cell they see.
+pub fn conflicting_cells(grid: &[[u8; SIZE]; SIZE], variant: Variant) -> Vec<(usize, usize)> {
+    let cells: Vec<(usize, usize)> = (0..SIZE)
+        .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+        .filter(|&(r, c)| grid[r][c] != 0)
+        .collect();
+    cells
+        .iter()
+        .copied()
+        .filter(|&a| {
+            cells
+                .iter()
+                .any(|&b| grid[a.0][a.1] == grid[b.0][b.1] && variant.sees(a, b))
+        })
+        .collect()
+}
+
 // --- Backtracking Generator ---
-// (Generator struct and impl unchanged)
-struct Generator<'a> {
+struct Generator<'a, R: Rng + ?Sized> {
     grid: &'a mut [[u8; SIZE]; SIZE],
     nums: [u8; SIZE],
+    variant: Variant,
+    rng: &'a mut R,
+    /// A second variant whose rule `fill` also obeys, Classic for none
+    also: Variant,
+    /// Digits `fill` placed and then took back
+    backtracks: u64,
 }
 
-impl<'a> Generator<'a> {
-    fn new(grid: &'a mut [[u8; SIZE]; SIZE]) -> Self {
+impl<'a, R: Rng + ?Sized> Generator<'a, R> {
+    fn new(grid: &'a mut [[u8; SIZE]; SIZE], variant: Variant, rng: &'a mut R) -> Self {
         let mut nums = [1, 2, 3, 4, 5, 6, 7, 8, 9];
-        // Get a thread-local RNG instance
-        let mut rng = rand::rng();
-        nums.shuffle(&mut rng);
-        Generator { grid, nums }
+        nums.shuffle(rng);
+        Generator {
+            grid,
+            nums,
+            variant,
+            rng,
+            also: Variant::Classic,
+            backtracks: 0,
+        }
     }
 
     fn find_empty(&self) -> Option<(usize, usize)> {
@@ -192,15 +1026,26 @@ impl<'a> Generator<'a> {
                 }
             }
         }
+        // Check variant rules
+        for variant in [self.variant, self.also] {
+            if variant == Variant::Classic {
+                continue;
+            }
+            for row in 0..SIZE {
+                for col in 0..SIZE {
+                    if self.grid[row][col] == num && variant.links((r, c), (row, col)) {
+                        return false;
+                    }
+                }
+            }
+        }
         true
     }
 
     fn fill(&mut self) -> bool {
         if let Some((r, c)) = self.find_empty() {
             let mut local_nums = self.nums;
-            // Get a thread-local RNG instance for this scope if needed, or reuse one passed in
-            let mut rng = rand::rng();
-            local_nums.shuffle(&mut rng);
+            local_nums.shuffle(self.rng);
 
             for &num in &local_nums {
                 if self.is_safe(r, c, num) {
@@ -209,6 +1054,7 @@ impl<'a> Generator<'a> {
                         return true; // Success!
                     }
                     self.grid[r][c] = 0; // Backtrack
+                    self.backtracks += 1;
                 }
             }
             false // No number worked for this cell
@@ -216,4 +1062,83 @@ impl<'a> Generator<'a> {
             true // Grid is full
         }
     }
+
+    /// Like `fill`, but branching on the most constrained cell each time,
+    /// which keeps grids under several extra rules quick to fill.
+    fn fill_most_constrained(&mut self) -> bool {
+        let Some(((r, c), mut options)) = self.most_constrained() else {
+            return true;
+        };
+        options.shuffle(self.rng);
+        for num in options {
+            self.grid[r][c] = num;
+            if self.fill_most_constrained() {
+                return true;
+            }
+            self.grid[r][c] = 0;
+            self.backtracks += 1;
+        }
+        false
+    }
+
+    /// The empty cell with the fewest safe digits, and those digits.
+    fn most_constrained(&self) -> Option<((usize, usize), Vec<u8>)> {
+        let mut best: Option<((usize, usize), Vec<u8>)> = None;
+        for r in 0..SIZE {
+            for c in 0..SIZE {
+                if self.grid[r][c] != 0 {
+                    continue;
+                }
+                let options: Vec<u8> = (1..=9).filter(|&n| self.is_safe(r, c, n)).collect();
+                if best.as_ref().is_none_or(|(_, b)| options.len() < b.len()) {
+                    let dead_end = options.is_empty();
+                    best = Some(((r, c), options));
+                    if dead_end {
+                        return best;
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Fills the grid with its first completion found before `deadline`.
+    /// On failure the grid is left as it was.
+    fn complete(&mut self, deadline: Instant) -> bool {
+        let Some(((r, c), options)) = self.most_constrained() else {
+            return true;
+        };
+        for num in options {
+            if Instant::now() >= deadline {
+                break;
+            }
+            self.grid[r][c] = num;
+            if self.complete(deadline) {
+                return true;
+            }
+            self.grid[r][c] = 0;
+        }
+        false
+    }
+
+    /// Counts completions of the grid, stopping at `limit`. The first one found
+    /// is stored in `first`. The grid is restored afterwards.
+    fn count(&mut self, limit: usize, first: &mut Option<[[u8; SIZE]; SIZE]>) -> usize {
+        let Some(((r, c), options)) = self.most_constrained() else {
+            if first.is_none() {
+                *first = Some(*self.grid);
+            }
+            return 1;
+        };
+        let mut found = 0;
+        for num in options {
+            self.grid[r][c] = num;
+            found += self.count(limit - found, first);
+            self.grid[r][c] = 0;
+            if found >= limit {
+                break;
+            }
+        }
+        found
+    }
 }
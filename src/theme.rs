@@ -0,0 +1,323 @@
+use ratatui::style::Color;
+use serde::Deserialize;
+
+/// A built-in set of colors, picked with `theme` under `[display]` or on the
+/// settings screen. `[colors]` overrides apply on top of it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scheme {
+    #[default]
+    Default,
+    Solarized,
+    Gruvbox,
+    /// Plain terminal colors with strong backgrounds, for low vision and
+    /// washed-out screens
+    HighContrast,
+}
+
+impl Scheme {
+    pub const ALL: [Scheme; 4] = [
+        Scheme::Default,
+        Scheme::Solarized,
+        Scheme::Gruvbox,
+        Scheme::HighContrast,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Scheme::Default => "Default",
+            Scheme::Solarized => "Solarized",
+            Scheme::Gruvbox => "Gruvbox",
+            Scheme::HighContrast => "High contrast",
+        }
+    }
+
+    /// The scheme's value for `theme` in the config file.
+    pub fn key(self) -> &'static str {
+        match self {
+            Scheme::Default => "default",
+            Scheme::Solarized => "solarized",
+            Scheme::Gruvbox => "gruvbox",
+            Scheme::HighContrast => "high-contrast",
+        }
+    }
+
+    pub fn theme(self) -> Theme {
+        match self {
+            Scheme::Default => Theme::default(),
+            Scheme::Solarized => Theme {
+                title_fg: rgb(0xb58900),
+                text_fg: rgb(0x93a1a1),
+                instructions_fg: rgb(0x2aa198),
+                highlight_bg: rgb(0x073642),
+                border_fg: rgb(0x586e75),
+                cursor_bg: rgb(0xb58900),
+                user_fg: rgb(0x268bd2),
+                fixed_fg: rgb(0xeee8d5),
+                invalid_fg: rgb(0xdc322f),
+                conflict_bg: rgb(0x5c1f1f),
+                timer_fg: rgb(0x2aa198),
+                pace_ahead_fg: rgb(0x859900),
+                pace_even_fg: rgb(0xb58900),
+                pace_behind_fg: rgb(0xdc322f),
+                success_fg: rgb(0x859900),
+                info_fg: rgb(0x2aa198),
+                variant_bg: rgb(0x073642),
+                peer_bg: rgb(0x04303a),
+                legend_fg: rgb(0x839496),
+                hint_bg: rgb(0x0b4a63),
+                hint_cell_bg: rgb(0x859900),
+                hint_pattern_bg: rgb(0x1d5c57),
+                strong_link_bg: rgb(0x34420a),
+                weak_link_bg: rgb(0x5e4508),
+                theirs_fg: rgb(0x6c71c4),
+                disagree_bg: rgb(0x5c1f1f),
+                flash_bg: rgb(0x859900),
+                diff_wrong_bg: rgb(0x4f1a1a),
+            },
+            Scheme::Gruvbox => Theme {
+                title_fg: rgb(0xfabd2f),
+                text_fg: rgb(0xebdbb2),
+                instructions_fg: rgb(0x8ec07c),
+                highlight_bg: rgb(0x504945),
+                border_fg: rgb(0x665c54),
+                cursor_bg: rgb(0xd79921),
+                user_fg: rgb(0x83a598),
+                fixed_fg: rgb(0xfbf1c7),
+                invalid_fg: rgb(0xfb4934),
+                conflict_bg: rgb(0x712b1e),
+                timer_fg: rgb(0x8ec07c),
+                pace_ahead_fg: rgb(0xb8bb26),
+                pace_even_fg: rgb(0xfabd2f),
+                pace_behind_fg: rgb(0xfb4934),
+                success_fg: rgb(0xb8bb26),
+                info_fg: rgb(0x83a598),
+                variant_bg: rgb(0x3c3836),
+                peer_bg: rgb(0x32302f),
+                legend_fg: rgb(0xa89984),
+                hint_bg: rgb(0x076678),
+                hint_cell_bg: rgb(0x98971a),
+                hint_pattern_bg: rgb(0x427b58),
+                strong_link_bg: rgb(0x4f5b1a),
+                weak_link_bg: rgb(0x7c4a12),
+                theirs_fg: rgb(0xd3869b),
+                disagree_bg: rgb(0x6b2020),
+                flash_bg: rgb(0xb8bb26),
+                diff_wrong_bg: rgb(0x6b1f1f),
+            },
+            Scheme::HighContrast => Theme {
+                title_fg: Color::LightYellow,
+                text_fg: Color::White,
+                instructions_fg: Color::White,
+                highlight_bg: Color::Blue,
+                border_fg: Color::White,
+                cursor_bg: Color::Magenta,
+                user_fg: Color::LightCyan,
+                fixed_fg: Color::White,
+                invalid_fg: Color::LightRed,
+                conflict_bg: Color::Red,
+                timer_fg: Color::White,
+                pace_ahead_fg: Color::LightGreen,
+                pace_even_fg: Color::LightYellow,
+                pace_behind_fg: Color::LightRed,
+                success_fg: Color::LightGreen,
+                info_fg: Color::LightCyan,
+                variant_bg: Color::DarkGray,
+                peer_bg: Color::Indexed(238),
+                legend_fg: Color::White,
+                hint_bg: Color::Blue,
+                hint_cell_bg: Color::Green,
+                hint_pattern_bg: Color::Indexed(90),
+                strong_link_bg: Color::Green,
+                weak_link_bg: Color::Indexed(130),
+                theirs_fg: Color::LightMagenta,
+                disagree_bg: Color::Red,
+                flash_bg: Color::Green,
+                diff_wrong_bg: Color::Red,
+            },
+        }
+    }
+}
+
+/// A 24-bit color written as `0xRRGGBB`.
+const fn rgb(hex: u32) -> Color {
+    Color::Rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+}
+
+/// Colors used throughout the UI.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub title_fg: Color,
+    pub text_fg: Color,
+    pub instructions_fg: Color,
+    pub highlight_bg: Color,
+    pub border_fg: Color,
+    pub cursor_bg: Color,
+    pub user_fg: Color,
+    /// Foreground for the given clues; Reset keeps the terminal's own color
+    pub fixed_fg: Color,
+    pub invalid_fg: Color,
+    /// Background for the cells an invalid entry under the cursor clashes with
+    pub conflict_bg: Color,
+    pub timer_fg: Color,
+    /// Timer colors against the median time for the difficulty: ahead, on pace, behind
+    pub pace_ahead_fg: Color,
+    pub pace_even_fg: Color,
+    pub pace_behind_fg: Color,
+    pub success_fg: Color,
+    pub info_fg: Color,
+    /// Background tint for cells on an active variant region (diagonals, windows)
+    pub variant_bg: Color,
+    /// Background shading the row, column and box of the cursor cell
+    pub peer_bg: Color,
+    pub legend_fg: Color,
+    /// Background for the region a hint points at
+    pub hint_bg: Color,
+    /// Background for the cell a fully revealed hint places
+    pub hint_cell_bg: Color,
+    /// Background for the cells whose candidates make the hint's deduction
+    pub hint_pattern_bg: Color,
+    /// Backgrounds for the cells at the ends of strong and weak chain links
+    pub strong_link_bg: Color,
+    pub weak_link_bg: Color,
+    /// Digits only the other save has, when comparing two saves
+    pub theirs_fg: Color,
+    /// Background for the cells two compared saves fill differently
+    pub disagree_bg: Color,
+    /// Background blinked over a row, column or box when it is completed
+    pub flash_bg: Color,
+    /// Background marking a wrong entry in the diff view
+    pub diff_wrong_bg: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            title_fg: Color::Yellow,
+            text_fg: Color::White,
+            instructions_fg: Color::LightCyan,
+            highlight_bg: Color::DarkGray,
+            border_fg: Color::DarkGray,
+            cursor_bg: Color::LightYellow,
+            user_fg: Color::Blue,
+            fixed_fg: Color::Reset,
+            invalid_fg: Color::Red,
+            conflict_bg: Color::Indexed(88),
+            timer_fg: Color::Cyan,
+            pace_ahead_fg: Color::Green,
+            pace_even_fg: Color::Yellow,
+            pace_behind_fg: Color::Red,
+            success_fg: Color::Green,
+            info_fg: Color::Cyan,
+            variant_bg: Color::Indexed(236),
+            peer_bg: Color::Indexed(235),
+            legend_fg: Color::Gray,
+            hint_bg: Color::Indexed(24),
+            hint_cell_bg: Color::LightGreen,
+            hint_pattern_bg: Color::Indexed(30),
+            strong_link_bg: Color::Indexed(22),
+            weak_link_bg: Color::Indexed(94),
+            theirs_fg: Color::Magenta,
+            disagree_bg: Color::Indexed(124),
+            flash_bg: Color::Green,
+            diff_wrong_bg: Color::Indexed(52),
+        }
+    }
+}
+
+/// The config key of each color with the field it names, listed once for
+/// looking colors up and for setting them.
+macro_rules! color_keys {
+    ($($key:ident),* $(,)?) => {
+        /// The color under a config key, if the key exists.
+        pub fn color(&self, key: &str) -> Option<Color> {
+            match key {
+                $(stringify!($key) => Some(self.$key),)*
+                _ => None,
+            }
+        }
+
+        /// The color stored under a config key, if the key exists.
+        pub fn color_mut(&mut self, key: &str) -> Option<&mut Color> {
+            match key {
+                $(stringify!($key) => Some(&mut self.$key),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+impl Theme {
+    /// Names accepted for color overrides in the config file.
+    pub const KEYS: [&'static str; 28] = [
+        "title_fg",
+        "text_fg",
+        "instructions_fg",
+        "highlight_bg",
+        "border_fg",
+        "cursor_bg",
+        "user_fg",
+        "fixed_fg",
+        "invalid_fg",
+        "conflict_bg",
+        "timer_fg",
+        "pace_ahead_fg",
+        "pace_even_fg",
+        "pace_behind_fg",
+        "success_fg",
+        "info_fg",
+        "variant_bg",
+        "peer_bg",
+        "legend_fg",
+        "hint_bg",
+        "hint_cell_bg",
+        "hint_pattern_bg",
+        "strong_link_bg",
+        "weak_link_bg",
+        "theirs_fg",
+        "disagree_bg",
+        "flash_bg",
+        "diff_wrong_bg",
+    ];
+
+    /// This theme with `[colors]` overrides applied over it.
+    pub fn with_colors(mut self, colors: &[(String, Color)]) -> Self {
+        for (key, color) in colors {
+            if let Some(slot) = self.color_mut(key) {
+                *slot = *color;
+            }
+        }
+        self
+    }
+
+    color_keys!(
+        title_fg,
+        text_fg,
+        instructions_fg,
+        highlight_bg,
+        border_fg,
+        cursor_bg,
+        user_fg,
+        fixed_fg,
+        invalid_fg,
+        conflict_bg,
+        timer_fg,
+        pace_ahead_fg,
+        pace_even_fg,
+        pace_behind_fg,
+        success_fg,
+        info_fg,
+        variant_bg,
+        peer_bg,
+        legend_fg,
+        hint_bg,
+        hint_cell_bg,
+        hint_pattern_bg,
+        strong_link_bg,
+        weak_link_bg,
+        theirs_fg,
+        disagree_bg,
+        flash_bg,
+        diff_wrong_bg,
+    );
+}
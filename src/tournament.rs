@@ -0,0 +1,199 @@
+use crate::puzzle_id;
+use crate::sudoku::{Difficulty, SudokuGrid, Variant};
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, io, path::Path, path::PathBuf};
+
+/// Whether players may ask for hints during a tournament.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HintPolicy {
+    #[default]
+    Off,
+    /// Hints follow the player's usual cooldown and penalties
+    Allowed,
+}
+
+/// Rules shared by every puzzle of a tournament.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Rules {
+    #[serde(default)]
+    pub variant: Variant,
+    /// Per-puzzle time limit; a puzzle still open when it runs out counts as timed out
+    #[serde(default)]
+    pub time_limit_secs: Option<u64>,
+    #[serde(default)]
+    pub hints: HintPolicy,
+}
+
+/// One puzzle of the bundle, regenerated from its seed on every machine.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PuzzleSpec {
+    pub difficulty: Difficulty,
+    pub seed: u64,
+}
+
+/// A tournament bundle as distributed by the organizer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tournament {
+    pub name: String,
+    pub rules: Rules,
+    pub puzzles: Vec<PuzzleSpec>,
+    /// Checksum over the fields above, so edited bundles are refused.
+    /// It detects tampering by players, it is not a cryptographic signature.
+    #[serde(default)]
+    pub signature: String,
+}
+
+#[derive(Debug)]
+pub enum TournamentError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, serde_json::Error),
+    Empty,
+    Unsigned,
+    BadSignature,
+}
+
+impl fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TournamentError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            TournamentError::Parse(path, e) => {
+                write!(f, "{} is not a tournament bundle: {e}", path.display())
+            }
+            TournamentError::Empty => write!(f, "the tournament has no puzzles"),
+            TournamentError::Unsigned => write!(
+                f,
+                "the bundle is not signed; sign it with `tui_sudoku tournament sign <file>`"
+            ),
+            TournamentError::BadSignature => write!(
+                f,
+                "the signature does not match; the bundle was changed after signing"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TournamentError {}
+
+impl Tournament {
+    /// Reads a bundle and checks its signature.
+    pub fn open(path: &Path) -> Result<Self, TournamentError> {
+        let tournament = Self::read(path)?;
+        if tournament.signature.is_empty() {
+            return Err(TournamentError::Unsigned);
+        }
+        if tournament.signature != tournament.compute_signature() {
+            return Err(TournamentError::BadSignature);
+        }
+        Ok(tournament)
+    }
+
+    /// Signs a bundle in place, for the organizer.
+    pub fn sign_file(path: &Path) -> Result<Self, TournamentError> {
+        let mut tournament = Self::read(path)?;
+        tournament.signature = tournament.compute_signature();
+        let contents = serde_json::to_string_pretty(&tournament)
+            .map_err(|e| TournamentError::Parse(path.to_path_buf(), e))?;
+        fs::write(path, contents).map_err(|e| TournamentError::Io(path.to_path_buf(), e))?;
+        Ok(tournament)
+    }
+
+    fn read(path: &Path) -> Result<Self, TournamentError> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| TournamentError::Io(path.to_path_buf(), e))?;
+        let tournament: Tournament = serde_json::from_str(&contents)
+            .map_err(|e| TournamentError::Parse(path.to_path_buf(), e))?;
+        if tournament.puzzles.is_empty() {
+            return Err(TournamentError::Empty);
+        }
+        Ok(tournament)
+    }
+
+    /// FNV-1a over the serialized name, rules and puzzles.
+    fn compute_signature(&self) -> String {
+        let payload =
+            serde_json::to_string(&(&self.name, &self.rules, &self.puzzles)).unwrap_or_default();
+        format!("{:016x}", puzzle_id::fnv1a(payload.as_bytes()))
+    }
+
+    pub fn puzzle(&self, index: usize) -> Option<SudokuGrid> {
+        let spec = self.puzzles.get(index)?;
+        Some(SudokuGrid::from_seed(
+            spec.difficulty,
+            self.rules.variant,
+            spec.seed,
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResultOutcome {
+    Solved,
+    TimedOut,
+    Forfeited,
+}
+
+/// How one tournament puzzle went.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameResult {
+    /// Position of the puzzle in the bundle (0-based)
+    pub puzzle: usize,
+    pub outcome: ResultOutcome,
+    pub elapsed_secs: u64,
+    pub score: u32,
+    pub hints_used: u32,
+}
+
+/// Result file handed back to the organizer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TournamentResults {
+    pub tournament: String,
+    /// Signature of the bundle that was played
+    pub signature: String,
+    /// Wall-clock time of the last update (unix seconds)
+    pub updated_at: i64,
+    pub games: Vec<GameResult>,
+    pub total_score: u32,
+}
+
+/// A tournament in progress: the bundle and the results so far.
+pub struct TournamentRun {
+    pub tournament: Tournament,
+    /// Where the result file is written, next to the bundle
+    pub results_path: PathBuf,
+    pub results: Vec<GameResult>,
+}
+
+impl TournamentRun {
+    pub fn new(tournament: Tournament, bundle_path: &Path) -> Self {
+        TournamentRun {
+            tournament,
+            results_path: bundle_path.with_extension("results.json"),
+            results: Vec::new(),
+        }
+    }
+
+    /// Index of the next puzzle to play, if any are left.
+    pub fn next_index(&self) -> Option<usize> {
+        let index = self.results.len();
+        (index < self.tournament.puzzles.len()).then_some(index)
+    }
+
+    pub fn total_score(&self) -> u32 {
+        self.results.iter().map(|result| result.score).sum()
+    }
+
+    /// Records a finished puzzle and rewrites the result file.
+    pub fn record(&mut self, result: GameResult) -> io::Result<()> {
+        self.results.push(result);
+        let results = TournamentResults {
+            tournament: self.tournament.name.clone(),
+            signature: self.tournament.signature.clone(),
+            updated_at: chrono::Utc::now().timestamp(),
+            games: self.results.clone(),
+            total_score: self.total_score(),
+        };
+        let contents = serde_json::to_string_pretty(&results).map_err(io::Error::other)?;
+        fs::write(&self.results_path, contents)
+    }
+}
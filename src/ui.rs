@@ -1,40 +1,253 @@
-use crate::app::{App, AppState};
-use crate::sudoku::SIZE;
+use crate::app::{App, AppState, Confirm, DigitPicker, Hint, Pace, SoftKey};
+use crate::compare::{self, Side};
+use crate::config::{self, Validation};
+use crate::daily;
+use crate::history::Outcome;
+use crate::hunt::Hunt;
+use crate::import::ImportReview;
+use crate::keymap::Action;
+use crate::ladder;
+use crate::latency::{self, Latency};
+use crate::metrics::{self, Metrics};
+use crate::puzzle_id;
+use crate::quiz::Quiz;
+use crate::save::Elimination;
+use crate::screensaver::Screensaver;
+use crate::settings::Setting;
+use crate::solver;
+use crate::storage;
+use crate::style_rule::{CellFlag, CellFlags};
+use crate::sudoku::{
+    BOX_SIZE, Difficulty, SIZE, SudokuGrid, Symmetry, Variant, on_anti_diagonal, on_main_diagonal,
+    shares_unit, window_index,
+};
+use crate::theme::Theme;
+use crate::tournament::{HintPolicy, ResultOutcome};
+use crate::view::GridView;
+use crate::win::WinSummary;
+use chrono::{Datelike, Weekday};
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
-use std::time::Duration; // Added Duration for default timer value
+use std::cmp::Ordering;
+use std::iter;
+use std::time::Duration;
+use sudoku_widget::{CellContent, CellLook, GridText, NOTE_CELL_ROWS, grid_size};
 
 // Define grid dimensions including borders for centering calculation
-const GRID_WIDTH: u16 = 37; // 9 cells * 3 chars + 8 separators * 1 char + 2 border chars
-const GRID_HEIGHT: u16 = 19; // 9 number rows + 8 separator rows + 2 border chars
+const GRID_WIDTH: u16 = grid_size(1).0 + 2; // 9 cells * 3 chars + 8 separators * 1 char + 2 border chars
+const GRID_HEIGHT: u16 = grid_size(1).1 + 2; // 9 number rows + 8 separator rows + 2 border chars
+const NOTE_GRID_HEIGHT: u16 = grid_size(NOTE_CELL_ROWS).1 + 2;
+const SIDEBAR_WIDTH: u16 = 22;
+const FOCUS_WIDTH: u16 = 22;
+const RADAR_HEIGHT: u16 = 7; // Three technique rows, a blank line, a note and the border
+const HINT_EXPLANATION_WIDTH: u16 = 44;
+const COUNTDOWN_WARNING_SECS: u64 = 60; // A time-attack clock turns to the behind color below this
+const THUMBNAIL_WIDTH: u16 = 25; // 9 cells * 2 chars + 2 box separators * 2 chars + 2 border chars
+const THUMBNAIL_HEIGHT: u16 = 13; // 9 rows + 2 box separator rows + 2 border chars
+
+/// 3x5 bitmaps of the digits 1-9 for the focus panel, drawn two columns per pixel
+const BIG_DIGITS: [[&str; 5]; SIZE] = [
+    [" # ", "## ", " # ", " # ", "###"],
+    ["###", "  #", "###", "#  ", "###"],
+    ["###", "  #", "###", "  #", "###"],
+    ["# #", "# #", "###", "  #", "  #"],
+    ["###", "#  ", "###", "  #", "###"],
+    ["###", "#  ", "###", "# #", "###"],
+    ["###", "  #", "  #", "  #", "  #"],
+    ["###", "# #", "###", "# #", "###"],
+    ["###", "# #", "###", "  #", "###"],
+];
 
 /// Main drawing function: delegates based on AppState
 pub fn draw(frame: &mut Frame, app: &mut App) {
+    if !app.keymap_problems.is_empty() {
+        draw_keymap_report(frame, app);
+        return;
+    }
+    if let Some(screensaver) = &app.screensaver {
+        draw_screensaver(frame, &app.theme, screensaver);
+        return;
+    }
     match app.state {
+        AppState::ResumePrompt => draw_resume_prompt(frame, app),
         AppState::SelectingDifficulty => draw_difficulty_selection(frame, app),
-        AppState::Running | AppState::Solved => draw_game_ui(frame, app),
+        AppState::Previewing => draw_preview(frame, app),
+        AppState::Running | AppState::Solved | AppState::Failed => draw_game_ui(frame, app),
+        AppState::History => draw_history(frame, app),
+        AppState::Stats => draw_stats(frame, app),
+        AppState::Settings => draw_settings(frame, app),
+        AppState::Daily => draw_daily(frame, app),
+        AppState::Ladder => draw_ladder(frame, app),
+        AppState::Import => draw_import(frame, app),
+        AppState::Tournament => draw_tournament(frame, app),
+        AppState::Challenge => draw_challenge(frame, app),
+        AppState::Hunt => draw_hunt(frame, app),
+    }
+    if let Some(input) = &app.seed_input {
+        draw_seed_popup(frame, app, input);
+    }
+}
+
+/// Formats a number of seconds as MM:SS
+fn format_clock(secs: u64) -> String {
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+/// Formats a unix timestamp in local time
+fn format_timestamp(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| "????-??-?? ??:??".to_string())
+}
+
+/// Draws the startup report of problems found in the configured keys
+fn draw_keymap_report(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let config_path = storage::config_dir()
+        .map(|dir| dir.join(config::CONFIG_FILE).display().to_string())
+        .unwrap_or_else(|| config::CONFIG_FILE.to_string());
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("Problems in the [keys] section of {config_path}:"),
+            Style::default()
+                .fg(theme.text_fg)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for problem in &app.keymap_problems {
+        lines.push(Line::from(Span::styled(
+            format!("- {}", problem.description()),
+            Style::default().fg(theme.invalid_fg),
+        )));
+        lines.push(Line::from(Span::styled(
+            format!("  Fix: {}", problem.fix()),
+            Style::default().fg(theme.info_fg),
+        )));
     }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Enter: Continue with these bindings, q: Quit",
+        Style::default().fg(theme.instructions_fg),
+    )));
+    let report = Paragraph::new(Text::from(lines))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Key Bindings")
+                .title_style(Style::default().fg(theme.title_fg)),
+        );
+    frame.render_widget(report, frame.area());
+}
+
+/// Draws the startup prompt offering to resume the autosaved game
+fn draw_resume_prompt(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = calculate_centered_rect(frame.area(), 70, 15);
+    let question = match &app.saved_game {
+        Some(saved) => format!(
+            "Resume {} {} game, {}% complete, {} elapsed?",
+            saved.sudoku.difficulty_name(),
+            saved.sudoku.variant().name(),
+            saved.sudoku.progress_percent(),
+            format_clock(saved.elapsed_secs)
+        ),
+        None => "No saved game".to_string(),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            question,
+            Style::default()
+                .fg(theme.text_fg)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    if let Some(saved) = &app.saved_game {
+        lines.extend(thumbnail_lines(theme, &saved.sudoku));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        "r/Enter: Resume, d: Discard, k/Esc: Keep for later, q: Quit",
+        Style::default().fg(theme.instructions_fg),
+    )));
+    let text = Text::from(lines);
+    let prompt = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Unfinished Game")
+                .title_style(Style::default().fg(theme.title_fg)),
+        );
+    frame.render_widget(prompt, area);
+}
+
+/// A board drawn one character per cell, clues and entries in their colors and
+/// empty cells dimmed, so a game can be recognized at a glance in a list.
+fn thumbnail_lines(theme: &Theme, sudoku: &SudokuGrid) -> Vec<Line<'static>> {
+    (0..SIZE)
+        .map(|r| {
+            let mut spans = Vec::new();
+            for c in 0..SIZE {
+                if c > 0 && c % BOX_SIZE == 0 {
+                    spans.push(Span::raw(" "));
+                }
+                spans.push(match sudoku.get_cell(r, c, false) {
+                    Some(digit) => Span::styled(
+                        digit.to_string(),
+                        Style::default().fg(if sudoku.is_fixed(r, c) {
+                            theme.fixed_fg
+                        } else {
+                            theme.user_fg
+                        }),
+                    ),
+                    None => Span::styled(
+                        "·",
+                        Style::default()
+                            .fg(theme.legend_fg)
+                            .add_modifier(Modifier::DIM),
+                    ),
+                });
+            }
+            Line::from(spans)
+        })
+        .collect()
 }
 
 /// Draws the difficulty selection menu
 fn draw_difficulty_selection(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(2)
         .constraints([
             Constraint::Length(3), // Title
             Constraint::Min(0),    // List
-            Constraint::Length(3), // Instructions
+            Constraint::Length(3), // Variant
+            Constraint::Length(3), // Source
+            Constraint::Length(4), // Instructions
         ])
         .split(frame.area());
 
     // Title
-    let title = Paragraph::new("Sudoku TUI")
+    let title = if storage::persistent() {
+        "Sudoku TUI"
+    } else {
+        "Sudoku TUI - Guest, nothing is saved"
+    };
+    let title = Paragraph::new(title)
         .style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.title_fg)
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center)
@@ -46,20 +259,35 @@ fn draw_difficulty_selection(frame: &mut Frame, app: &App) {
         .difficulties
         .iter()
         .map(|d| {
-            let label = format!("{:?}", d);
-            ListItem::new(label).style(Style::default().fg(Color::White))
+            let label = match d {
+                Difficulty::Custom(_) => format!("{} (+/-)", d.name()),
+                named => named.name(),
+            };
+            ListItem::new(label).style(Style::default().fg(theme.text_fg))
         })
         .collect();
 
+    let mut list_title = "Select Difficulty".to_string();
+    if app.limited_mistakes {
+        list_title.push_str(&format!(
+            " - game over at {} mistakes",
+            app.gameplay.mistake_limit
+        ));
+    }
+    if app.memory_mode {
+        list_title.push_str(" - memory mode");
+    }
+    if app.zen_mode {
+        list_title.push_str(" - zen");
+    }
+    if app.time_attack_mode {
+        list_title.push_str(" - time attack");
+    }
     let list = List::new(difficulties)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Select Difficulty"),
-        )
+        .block(Block::default().borders(Borders::ALL).title(list_title))
         .highlight_style(
             Style::default()
-                .bg(Color::DarkGray)
+                .bg(theme.highlight_bg)
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol("> ");
@@ -70,97 +298,760 @@ fn draw_difficulty_selection(frame: &mut Frame, app: &App) {
 
     frame.render_stateful_widget(list, chunks[1], &mut list_state);
 
+    // Variant
+    let variant = app.selected_variant();
+    let variant_text = Text::from(vec![
+        Line::from(Span::styled(
+            format!("< {} >", variant.name()),
+            Style::default()
+                .fg(theme.text_fg)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            variant.rule_text(),
+            Style::default().fg(theme.legend_fg),
+        )),
+    ]);
+    let variant_paragraph = Paragraph::new(variant_text)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::TOP).title("Variant"));
+    frame.render_widget(variant_paragraph, chunks[2]);
+
+    // Source
+    let source = app.selected_source();
+    let source_text = Text::from(vec![
+        Line::from(Span::styled(
+            format!("< {} >", source.name()),
+            Style::default()
+                .fg(theme.text_fg)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            source.description(),
+            Style::default().fg(theme.legend_fg),
+        )),
+    ]);
+    let source_paragraph = Paragraph::new(source_text)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::TOP).title("Source"));
+    frame.render_widget(source_paragraph, chunks[3]);
+
     // Instructions
-    let instructions = Paragraph::new("Use Up/Down (k/j) to select, Enter to start, q to quit.")
-        .style(Style::default().fg(Color::LightCyan))
+    let mut instruction_lines = vec![Line::from(Span::styled(
+        "Use Up/Down (k/j) to select, +/- for custom clues, Left/Right (h/l) for variant, Tab for source, Enter to start, D for daily, P for the ladder, C for a challenge, F to find the mistake, S for a seed, L to limit mistakes, M for memory mode, Z for zen mode, A for time attack, I to import, H for history, T for stats, O for settings, U to check for updates, q to quit.",
+        Style::default().fg(theme.instructions_fg),
+    ))];
+    if let Some(message) = &app.status_message {
+        instruction_lines.push(Line::from(Span::styled(
+            message.clone(),
+            Style::default().fg(theme.info_fg),
+        )));
+    }
+    let instructions = Paragraph::new(instruction_lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    frame.render_widget(instructions, chunks[4]);
+}
+
+/// Draws the pre-game preview: the clue layout of the board about to be played
+fn draw_preview(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Thumbnail
+            Constraint::Length(3), // Options
+            Constraint::Length(3), // Instructions
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new(format!(
+        "{} {} Puzzle Preview",
+        app.selected_difficulty().name(),
+        app.selected_variant().name()
+    ))
+    .style(
+        Style::default()
+            .fg(theme.title_fg)
+            .add_modifier(Modifier::BOLD),
+    )
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let thumbnail_rect = calculate_centered_rect(chunks[1], THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT);
+    if let Some(preview) = &app.preview {
+        let thumbnail = Paragraph::new(build_clue_thumbnail(preview, theme, app.view))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("{} clues", preview.clue_count())),
+            )
+            .alignment(Alignment::Center);
+        frame.render_widget(thumbnail, thumbnail_rect);
+    } else {
+        let placeholder = Paragraph::new("Generating...").alignment(Alignment::Center);
+        frame.render_widget(placeholder, thumbnail_rect);
+    }
+    if app.show_debug
+        && let Some(preview) = &app.preview
+    {
+        draw_debug_overlay(frame, theme, preview, &app.latency);
+    }
+
+    let validation = if app.tournament.is_some() {
+        "conflicts only, as tournaments require".to_string()
+    } else {
+        app.validation.name().to_string()
+    };
+    let options = Paragraph::new(Line::from(vec![
+        Span::styled("Check entries: ", Style::default().fg(theme.legend_fg)),
+        Span::styled(validation, Style::default().fg(theme.text_fg)),
+        Span::styled("   Symmetry: ", Style::default().fg(theme.legend_fg)),
+        Span::styled(
+            match app.selected_difficulty() {
+                Difficulty::Minimal if app.symmetry != Symmetry::None => "none for minimal puzzles",
+                _ => app.symmetry.name(),
+            },
+            Style::default().fg(theme.text_fg),
+        ),
+    ]))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL).title("Options"));
+    frame.render_widget(options, chunks[2]);
+
+    let instructions = Paragraph::new(
+        "r: Reroll, v: Validation, s: Symmetry, Enter: Start, Esc/b: Back, q: Quit.",
+    )
+    .style(Style::default().fg(theme.instructions_fg))
+    .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[3]);
+}
+
+/// Builds a compact map of the clue layout: a dot for each given, blank otherwise.
+fn build_clue_thumbnail(sudoku: &SudokuGrid, theme: &Theme, view: GridView) -> Text<'static> {
+    let mut lines = Vec::new();
+    let border_style = Style::default().fg(theme.border_fg);
+    let clue_style = Style::default()
+        .fg(theme.text_fg)
+        .add_modifier(Modifier::BOLD);
+
+    for screen_r in 0..SIZE {
+        if screen_r > 0 && screen_r % BOX_SIZE == 0 {
+            lines.push(Line::from("──────┼───────┼───────").style(border_style));
+        }
+        let mut spans = Vec::new();
+        for screen_c in 0..SIZE {
+            if screen_c > 0 && screen_c % BOX_SIZE == 0 {
+                spans.push(Span::styled("│ ", border_style));
+            }
+            let (r, c) = view.to_board((screen_r, screen_c), SIZE);
+            if sudoku.is_fixed(r, c) {
+                spans.push(Span::styled("● ", clue_style));
+            } else {
+                spans.push(Span::styled("· ", border_style));
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+    Text::from(lines)
+}
+
+/// Draws the idle demo: the solver filling in a board, with the step it just took
+fn draw_screensaver(frame: &mut Frame, theme: &Theme, screensaver: &Screensaver) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Min(0),    // Board
+            Constraint::Length(1), // Step
+            Constraint::Length(1), // Instructions
+        ])
+        .split(frame.area());
+
+    let puzzle = &screensaver.puzzle;
+    let board = screensaver.board();
+    let latest = screensaver.latest();
+    let border_style = Style::default().fg(theme.border_fg);
+    let mut lines = Vec::new();
+    for (r, row) in board.iter().enumerate() {
+        if r > 0 && r % BOX_SIZE == 0 {
+            lines.push(Line::from("──────┼───────┼───────").style(border_style));
+        }
+        let mut spans = Vec::new();
+        for (c, &digit) in row.iter().enumerate() {
+            if c > 0 && c % BOX_SIZE == 0 {
+                spans.push(Span::styled("│ ", border_style));
+            }
+            let mut style = if puzzle.is_fixed(r, c) {
+                Style::default()
+                    .fg(theme.text_fg)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.info_fg)
+            };
+            if latest.is_some_and(|((lr, lc, _), _)| (lr, lc) == (r, c)) {
+                style = style.bg(theme.hint_cell_bg);
+            }
+            if let Some(bg) = screensaver
+                .celebration
+                .as_ref()
+                .and_then(|celebration| celebration.cell_bg((r, c), theme))
+            {
+                style = style.bg(bg);
+            }
+            let text = match digit {
+                0 => "· ".to_string(),
+                digit => format!("{digit} "),
+            };
+            spans.push(Span::styled(text, style));
+        }
+        lines.push(Line::from(spans));
+    }
+    let board_rect = calculate_centered_rect(chunks[0], THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT);
+    let board_widget = Paragraph::new(Text::from(lines))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "{} {}",
+                    puzzle.difficulty_name(),
+                    puzzle.variant().name()
+                ))
+                .title_style(Style::default().fg(theme.title_fg)),
+        );
+    frame.render_widget(board_widget, board_rect);
+
+    let step = match latest {
+        _ if screensaver.celebration.is_some() => "Solved!".to_string(),
+        Some(((r, c, digit), Some(technique))) => format!(
+            "{}: {digit} at {}",
+            technique.name(),
+            solver::cell_name((r, c))
+        ),
+        Some(((r, c, digit), None)) => {
+            format!(
+                "From the solution: {digit} at {}",
+                solver::cell_name((r, c))
+            )
+        }
+        None => "Solving...".to_string(),
+    };
+    let step = Paragraph::new(step)
+        .style(Style::default().fg(theme.text_fg))
+        .alignment(Alignment::Center);
+    frame.render_widget(step, chunks[1]);
+
+    let instructions = Paragraph::new("Press any key to return to the menu")
+        .style(Style::default().fg(theme.instructions_fg))
         .alignment(Alignment::Center);
     frame.render_widget(instructions, chunks[2]);
 }
 
+/// How much of the game screen the terminal has room for. Each tier gives
+/// up one more thing than the one before: the sidebar, then the full help
+/// line, then the tall grid, until only a notice that the terminal is too
+/// small is left.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LayoutTier {
+    #[default]
+    Full,
+    NoSidebar,
+    ShortHelp,
+    CompactGrid,
+    TooSmall,
+}
+
+impl LayoutTier {
+    fn shows_sidebar(self) -> bool {
+        self == LayoutTier::Full
+    }
+
+    fn short_help(self) -> bool {
+        self >= LayoutTier::ShortHelp
+    }
+
+    fn compact_grid(self) -> bool {
+        self >= LayoutTier::CompactGrid
+    }
+}
+
+/// Heights of the game screen's rows other than the grid, in the order they
+/// are laid out: help, timer, digits left, variant legend, color key,
+/// status and soft keys.
+fn game_rows(app: &App) -> [u16; 7] {
+    let variant = app
+        .sudoku
+        .as_ref()
+        .map_or(Variant::Classic, |s| s.variant());
+    let legend_height = if variant == Variant::Classic { 0 } else { 1 };
+    let key_height = if app.show_legend { 2 } else { 0 };
+    let soft_key_height = if app.soft_keys { 1 } else { 0 };
+    let digits_height = if app.show_digits && !app.is_paused() {
+        1
+    } else {
+        0
+    };
+    [
+        1,
+        1,
+        digits_height,
+        legend_height,
+        key_height,
+        3,
+        soft_key_height,
+    ]
+}
+
+/// The line of keys above the game, or its abbreviated form.
+fn game_help(app: &App, short: bool) -> String {
+    let key = |action| app.keymap.label(action);
+    if short {
+        format!(
+            "{}:Help {}:Quit {}:Menu",
+            key(Action::Help),
+            key(Action::Quit),
+            key(Action::Menu),
+        )
+    } else {
+        format!(
+            "{}: Help, {}: Quit, {}: New Game Menu",
+            key(Action::Help),
+            key(Action::Quit),
+            key(Action::Menu),
+        )
+    }
+}
+
+/// The smallest terminal the game screen can be drawn in, in the compact tier.
+fn minimum_game_size(app: &App) -> (u16, u16) {
+    let width = GRID_WIDTH.max(game_help(app, true).chars().count() as u16);
+    let height = game_rows(app).iter().sum::<u16>() + GRID_HEIGHT;
+    (width, height)
+}
+
+/// Picks the richest tier of the game screen that fits `area`.
+pub fn negotiate_layout(app: &App, area: Rect) -> LayoutTier {
+    let chrome = game_rows(app).iter().sum::<u16>();
+    let grid_height = if app.note_grid {
+        NOTE_GRID_HEIGHT
+    } else {
+        GRID_HEIGHT
+    };
+    let fits =
+        |width: u16, grid_height: u16| area.width >= width && area.height >= chrome + grid_height;
+    let help = GRID_WIDTH.max(game_help(app, false).chars().count() as u16);
+    let short_help = GRID_WIDTH.max(game_help(app, true).chars().count() as u16);
+    // The sidebar takes as much room right of the centered grid as the
+    // focus panel does left of it
+    let with_sidebar = help.max(GRID_WIDTH + 2 * (SIDEBAR_WIDTH + 2));
+    if fits(with_sidebar, grid_height) {
+        LayoutTier::Full
+    } else if fits(help, grid_height) {
+        LayoutTier::NoSidebar
+    } else if fits(short_help, grid_height) {
+        LayoutTier::ShortHelp
+    } else if fits(short_help, GRID_HEIGHT) {
+        LayoutTier::CompactGrid
+    } else {
+        LayoutTier::TooSmall
+    }
+}
+
+/// Stands in for the game screen in a terminal too small for any tier of it.
+fn draw_too_small(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let (width, height) = minimum_game_size(app);
+    let area = frame.area();
+    let notice = Paragraph::new(vec![
+        Line::from(Span::styled(
+            "Terminal too small",
+            Style::default()
+                .fg(theme.title_fg)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!(
+                "{}x{}, the game needs {width}x{height}",
+                area.width, area.height
+            ),
+            Style::default().fg(theme.text_fg),
+        )),
+        Line::from(Span::styled(
+            "Enlarge the window to keep playing",
+            Style::default().fg(theme.instructions_fg),
+        )),
+    ])
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+    // Room for the lines to wrap on a narrow terminal
+    let rect = Rect {
+        y: area.y + area.height.saturating_sub(5) / 2,
+        height: area.height.min(5),
+        ..area
+    };
+    frame.render_widget(notice, rect);
+}
+
 /// Draws the main game UI (grid, timer, status)
 fn draw_game_ui(frame: &mut Frame, app: &mut App) {
+    // Toggled panels change what fits as much as a resize does, so each
+    // frame settles the tier again before anything is placed
+    app.layout = negotiate_layout(app, frame.area());
+    if app.layout == LayoutTier::TooSmall {
+        app.forget_layout();
+        draw_too_small(frame, app);
+        return;
+    }
+    let variant = app
+        .sudoku
+        .as_ref()
+        .map_or(Variant::Classic, |s| s.variant());
+    let [
+        help_height,
+        timer_height,
+        digits_height,
+        legend_height,
+        key_height,
+        status_height,
+        soft_key_height,
+    ] = game_rows(app);
     let main_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(2), // Help Text
-            Constraint::Length(1), // Timer
-            Constraint::Min(0),    // Grid Area
-            Constraint::Length(3), // Status
+            Constraint::Length(help_height),     // Help Text
+            Constraint::Length(timer_height),    // Timer
+            Constraint::Min(0),                  // Grid Area
+            Constraint::Length(digits_height),   // Digits Left
+            Constraint::Length(legend_height),   // Variant Legend
+            Constraint::Length(key_height),      // Color Key
+            Constraint::Length(status_height),   // Status
+            Constraint::Length(soft_key_height), // Soft Keys
         ])
         .split(frame.area());
 
+    let grid_area = main_layout[2];
+    // The tall grid gives way to the compact one in the tier before last
+    let cell_rows = if app.note_grid && !app.layout.compact_grid() {
+        NOTE_CELL_ROWS
+    } else {
+        1
+    };
+    let grid_height = if cell_rows == 1 {
+        GRID_HEIGHT
+    } else {
+        NOTE_GRID_HEIGHT
+    };
+    let centered_grid_rect = calculate_centered_rect(grid_area, GRID_WIDTH, grid_height);
+    app.set_grid_rect(centered_grid_rect);
+    if let Some(sudoku) = &app.sudoku {
+        // Taken out while filling it in, since that reads the rest of the app
+        let mut grid = std::mem::take(&mut app.grid);
+        update_grid(&mut grid, app, sudoku, cell_rows);
+        app.grid = grid;
+    }
+    // The sidebar sits right of the grid, the first thing given up for room
+    let sidebar_rect = Rect {
+        x: centered_grid_rect.right() + 2,
+        y: centered_grid_rect.y,
+        width: SIDEBAR_WIDTH,
+        height: centered_grid_rect.height,
+    };
+    let show_sidebar = app.layout.shows_sidebar();
+    // The focus panel sits left of the grid, mirroring the sidebar
+    let focus_rect = Rect {
+        x: centered_grid_rect.x.saturating_sub(FOCUS_WIDTH + 2),
+        y: centered_grid_rect.y,
+        width: FOCUS_WIDTH,
+        height: centered_grid_rect.height,
+    };
+    let left_fits = centered_grid_rect.x >= grid_area.x + FOCUS_WIDTH + 2;
+    let show_focus = app.show_focus && !app.is_paused() && left_fits;
+    // The radar shares the left column, below the focus panel when both are open
+    let show_radar = app.show_radar
+        && app.tournament.is_none()
+        && app.state == AppState::Running
+        && !app.is_paused()
+        && left_fits;
+    let radar_rect = Rect {
+        y: if show_focus {
+            focus_rect.bottom().saturating_sub(RADAR_HEIGHT)
+        } else {
+            focus_rect.y
+        },
+        height: RADAR_HEIGHT.min(focus_rect.height),
+        ..focus_rect
+    };
+    let focus_rect = Rect {
+        height: if show_radar {
+            focus_rect.height.saturating_sub(RADAR_HEIGHT)
+        } else {
+            focus_rect.height
+        },
+        ..focus_rect
+    };
+    let soft_keys = if app.soft_keys {
+        layout_soft_keys(app, main_layout[7])
+    } else {
+        Vec::new()
+    };
+    app.set_soft_key_rects(
+        soft_keys
+            .iter()
+            .map(|&(rect, key, _)| (rect, key))
+            .collect(),
+    );
+    if let Some(picker) = app.picker.as_mut().filter(|picker| picker.open) {
+        picker.place(frame.area());
+    }
+
+    // Everything below only reads app state
+    let app = &*app;
+    let theme = &app.theme;
+
     // --- Help Text ---
-    let help_line1 = Line::from(vec![Span::styled(
-        "Controls: q: Quit, n: New Game Menu, s: Toggle Solution",
+    let help_line = Line::from(vec![Span::styled(
+        game_help(app, app.layout.short_help()),
         Style::default()
-            .fg(Color::Yellow)
+            .fg(theme.title_fg)
             .add_modifier(Modifier::BOLD),
     )]);
-    let help_line2 = Line::from(vec![Span::styled(
-        "          Arrows/hjkl: Move, 1-9: Enter, 0/Del/Backspace: Clear",
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD),
-    )]);
-    let title_text = Text::from(vec![help_line1, help_line2]);
-    let title = Paragraph::new(title_text).alignment(Alignment::Center);
+    let title = Paragraph::new(help_line).alignment(Alignment::Center);
     frame.render_widget(title, main_layout[0]);
 
     // --- Timer ---
     let elapsed = app.elapsed_time.unwrap_or(Duration::ZERO);
     let elapsed_secs = elapsed.as_secs();
-    let timer_str = format!("{:02}:{:02}", elapsed_secs / 60, elapsed_secs % 60);
-    let timer_paragraph = Paragraph::new(timer_str)
-        .style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+    // Zen games keep the clock out of sight until the board is solved
+    let zen = app.zen && app.state == AppState::Running;
+    // With the clock turned off in the settings only the score is shown
+    let hidden = !app.show_timer && app.state == AppState::Running;
+    // A countdown stays in view even with the clock turned off, as it decides the game
+    let time_left = app.time_left().filter(|_| app.state == AppState::Running);
+    let mut timer_str = if zen {
+        "Zen".to_string()
+    } else if let Some(left) = time_left {
+        format!(
+            "{} left   Score: {}",
+            format_clock(left),
+            app.current_score()
+        )
+    } else if hidden {
+        format!("Score: {}", app.current_score())
+    } else {
+        format!(
+            "{}   Score: {}",
+            format_clock(elapsed_secs),
+            app.current_score()
         )
+    };
+    if let Some(left) = app.tournament_time_left() {
+        timer_str.push_str(&format!("   Time left: {}", format_clock(left)));
+    }
+    if let Some(challenge) = &app.challenge {
+        // A solved puzzle's time is already part of the challenge
+        let running = app.state == AppState::Running;
+        let current = if running { elapsed } else { Duration::ZERO };
+        timer_str.push_str(&format!(
+            "   Challenge {}/3, total {}",
+            challenge.times.len() + usize::from(running),
+            format_clock((challenge.total_time() + current).as_secs())
+        ));
+    }
+    if let Some(median) = app.median_secs.filter(|_| !zen && !hidden) {
+        timer_str.push_str(&format!("   Median {}", format_clock(median)));
+    }
+    if let Some(seed) = app.sudoku.as_ref().and_then(|s| s.seed()) {
+        timer_str.push_str(&format!("   Seed {seed}"));
+    }
+    if app.wrong_submissions > 0 {
+        timer_str.push_str(&format!("   Wrong submits: {}", app.wrong_submissions));
+    }
+    if let Some(limit) = app.mistake_limit {
+        timer_str.push_str(&format!("   Mistakes: {}/{limit}", app.mistakes));
+    }
+    if let Some(memory) = &app.memory {
+        timer_str.push_str(&format!("   Recalls: {}", memory.recalls_left));
+        if memory.recalling() {
+            timer_str.push_str(" (showing)");
+        }
+    }
+    if app.note_mode && app.state == AppState::Running {
+        timer_str.push_str("   [Notes]");
+    }
+    if app.is_paused() || app.popup_since.is_some() {
+        timer_str.push_str("   [Paused]");
+    }
+    let timer_fg = match app.pace().filter(|_| !zen && !hidden) {
+        _ if time_left.is_some_and(|left| left < COUNTDOWN_WARNING_SECS) => theme.pace_behind_fg,
+        Some(Pace::Ahead) => theme.pace_ahead_fg,
+        Some(Pace::Even) => theme.pace_even_fg,
+        Some(Pace::Behind) => theme.pace_behind_fg,
+        None => theme.timer_fg,
+    };
+    let timer_paragraph = Paragraph::new(timer_str)
+        .style(Style::default().fg(timer_fg).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center);
     frame.render_widget(timer_paragraph, main_layout[1]);
 
     // --- Grid Area ---
-    let grid_area = main_layout[2];
-    let centered_grid_rect = calculate_centered_rect(grid_area, GRID_WIDTH, GRID_HEIGHT);
-    app.set_grid_rect(centered_grid_rect);
-
-    if let Some(sudoku) = &app.sudoku {
-        let grid_text = build_grid_text(app, sudoku);
-        let grid_paragraph = Paragraph::new(grid_text)
-            .block(Block::default().borders(Borders::ALL).title("Sudoku Grid"))
-            .alignment(Alignment::Center);
-        frame.render_widget(grid_paragraph, centered_grid_rect);
+    if app.is_paused() {
+        // The board stays hidden so the pause can't be used to think
+        let paused = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "Paused",
+                Style::default()
+                    .fg(theme.title_fg)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{} to resume", app.keymap.label(Action::Pause)),
+                Style::default().fg(theme.instructions_fg),
+            )),
+        ])
+        .block(Block::default().borders(Borders::ALL).title("Sudoku Grid"))
+        .alignment(Alignment::Center);
+        frame.render_widget(paused, centered_grid_rect);
+    } else if app.popup_since.is_some() && app.blur_behind_popups {
+        // Hidden too, so a popup can't stand in for the pause key
+        let hidden = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "Clock stopped",
+                Style::default()
+                    .fg(theme.title_fg)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                "while the popup is open",
+                Style::default().fg(theme.legend_fg),
+            )),
+        ])
+        .block(Block::default().borders(Borders::ALL).title("Sudoku Grid"))
+        .alignment(Alignment::Center);
+        frame.render_widget(hidden, centered_grid_rect);
+    } else if app.sudoku.is_some() {
+        let mut grid_title = if let Some(date) = app.daily_date {
+            format!("Daily Puzzle - {date}")
+        } else if variant == Variant::Classic {
+            "Sudoku Grid".to_string()
+        } else {
+            format!("Sudoku Grid - {}", variant.name())
+        };
+        if let Some(view) = app.view.label() {
+            grid_title.push_str(&format!(" ({view})"));
+        }
+        let grid_block = Block::default().borders(Borders::ALL).title(grid_title);
+        let grid_inner = grid_block.inner(centered_grid_rect);
+        frame.render_widget(grid_block, centered_grid_rect);
+        frame.render_widget(&app.grid, grid_inner);
     } else {
         let placeholder = Paragraph::new("Loading...").alignment(Alignment::Center);
         frame.render_widget(placeholder, centered_grid_rect);
     }
 
+    // --- Sidebar ---
+    if show_sidebar && let Some(sudoku) = &app.sudoku {
+        draw_sidebar(frame, app, sudoku, sidebar_rect);
+    }
+
+    // --- Focus Panel ---
+    if show_focus && let Some(sudoku) = &app.sudoku {
+        draw_focus_panel(frame, app, sudoku, focus_rect);
+    }
+
+    // --- Technique Radar ---
+    if show_radar && let Some(sudoku) = &app.sudoku {
+        draw_radar(frame, app, sudoku, radar_rect);
+    }
+
+    // --- Digits Left ---
+    if digits_height > 0
+        && let Some(sudoku) = &app.sudoku
+    {
+        frame.render_widget(
+            Paragraph::new(build_digit_tracker(theme, sudoku)).alignment(Alignment::Center),
+            main_layout[3],
+        );
+    }
+
+    // --- Variant Legend ---
+    if variant != Variant::Classic {
+        let marker = match variant {
+            Variant::AntiKnight => Span::styled("♞ ", Style::default().fg(theme.legend_fg)),
+            _ => Span::styled("   ", Style::default().bg(theme.variant_bg)),
+        };
+        let legend = Line::from(vec![
+            marker,
+            Span::styled(
+                format!(" {}", variant.rule_text()),
+                Style::default().fg(theme.legend_fg),
+            ),
+        ]);
+        frame.render_widget(
+            Paragraph::new(legend).alignment(Alignment::Center),
+            main_layout[4],
+        );
+    }
+
+    // --- Color Key ---
+    if app.show_legend {
+        frame.render_widget(
+            Paragraph::new(build_color_key(app, variant))
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            main_layout[5],
+        );
+    }
+
     // --- Status / Win Message ---
-    let status_area = main_layout[3];
+    let status_area = main_layout[6];
     let status_text = if app.state == AppState::Solved {
-        let final_time_str = format!("{:02}:{:02}", elapsed_secs / 60, elapsed_secs % 60);
+        let final_time_str = format_clock(elapsed_secs);
         Line::from(vec![
             Span::styled(
                 "Congratulations! You solved it in ",
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(theme.success_fg)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
                 final_time_str,
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(theme.success_fg)
                     .add_modifier(Modifier::BOLD | Modifier::ITALIC),
             ),
             Span::styled(
                 "! (q: Quit, n: New Menu)",
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(theme.success_fg)
                     .add_modifier(Modifier::BOLD),
             ),
         ])
+    } else if app.state == AppState::Failed {
+        let reason = match app.failure {
+            Some(Outcome::GaveUp) => "Gave up",
+            Some(Outcome::TimedOut) => "Time's up",
+            _ => "Out of mistakes",
+        };
+        Line::from(Span::styled(
+            format!(
+                "{reason} after {}! (r: Retry, n: New Menu, q: Quit)",
+                format_clock(elapsed_secs)
+            ),
+            Style::default()
+                .fg(theme.invalid_fg)
+                .add_modifier(Modifier::BOLD),
+        ))
     // Use is_some_and for cleaner check
     } else if !app.last_input_valid
+        && !app.zen
         && app
             .sudoku
             .as_ref()
@@ -169,80 +1060,2732 @@ fn draw_game_ui(frame: &mut Frame, app: &mut App) {
     {
         Line::from(Span::styled(
             "Invalid move!",
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            Style::default()
+                .fg(theme.invalid_fg)
+                .add_modifier(Modifier::BOLD),
+        ))
+    } else if let Some(left) = app.peek_seconds_left() {
+        Line::from(Span::styled(
+            format!(
+                "Showing Solution - hiding in {left}s (peek {} of {})",
+                app.peeks_used, app.gameplay.max_peeks
+            ),
+            Style::default().fg(theme.info_fg),
         ))
     } else if app.show_solution {
         Line::from(Span::styled(
             "Showing Solution",
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme.info_fg),
+        ))
+    } else if app.show_diff
+        && let Some(sudoku) = &app.sudoku
+    {
+        Line::from(Span::styled(
+            format!(
+                "Diff: {} wrong, {} empty (d to hide)",
+                sudoku.wrong_entry_count(),
+                sudoku.empty_count()
+            ),
+            Style::default().fg(theme.info_fg),
         ))
+    } else if let Some(hint) = &app.hint {
+        let region = hint.step.region.name();
+        let text = match (hint.tier, hint.step.placement) {
+            (1, _) => format!("Hint: look at {region} (H for more)"),
+            (2, _) => format!("Hint: {} in {region} (H for more)", hint.technique.name()),
+            (_, Some((r, c, d))) => format!(
+                "Hint: {} places {d} at r{}c{} (H: why)",
+                hint.technique.name(),
+                r + 1,
+                c + 1
+            ),
+            _ => format!("Hint: {} in {region}", hint.technique.name()),
+        };
+        Line::from(Span::styled(text, Style::default().fg(theme.info_fg)))
     } else {
         Line::from(Span::raw(""))
     };
-    let status_paragraph = Paragraph::new(status_text)
+    let mut status_lines = vec![status_text];
+    if let Some(message) = &app.status_message {
+        status_lines.push(Line::from(Span::styled(
+            message.clone(),
+            Style::default().fg(theme.info_fg),
+        )));
+    }
+    if let Some((r, c)) = app.link_from {
+        status_lines.push(Line::from(Span::styled(
+            format!(
+                "Linking from r{}c{}: at the other cell, digit for strong, Shift+digit for weak, Esc to stop",
+                r + 1,
+                c + 1
+            ),
+            Style::default().fg(theme.info_fg),
+        )));
+    } else if let Some(line) = cursor_links_line(app) {
+        status_lines.push(line);
+    } else if let Some(line) = cursor_compare_line(app) {
+        status_lines.push(line);
+    }
+    if app.state == AppState::Solved && !app.eliminations.is_empty() {
+        let (justified, total) = metrics::elimination_accuracy(&app.eliminations);
+        status_lines.push(Line::from(Span::styled(
+            format!(
+                "Eliminations: {justified} of {total} justified ({}%), details in the history",
+                justified * 100 / total
+            ),
+            Style::default().fg(theme.info_fg),
+        )));
+    }
+    let status_paragraph = Paragraph::new(status_lines)
         .alignment(Alignment::Center)
         .wrap(Wrap { trim: true });
     frame.render_widget(status_paragraph, status_area);
-}
 
-/// Builds the Text widget for the Sudoku grid.
-fn build_grid_text(app: &App, sudoku: &crate::sudoku::SudokuGrid) -> Text<'static> {
-    let mut lines = Vec::new();
-    const H_BORDER: &str = "───┼───┼───┼───┼───┼───┼───┼───┼───";
-    const V_SEP: char = '│';
+    // --- Soft Keys ---
+    for (rect, _, label) in soft_keys {
+        let style = Style::default()
+            .fg(theme.text_fg)
+            .bg(theme.highlight_bg)
+            .add_modifier(Modifier::BOLD);
+        frame.render_widget(Paragraph::new(Span::styled(label, style)), rect);
+    }
 
-    // Use a single style for all borders
-    let border_style = Style::default().fg(Color::DarkGray);
+    if let Some(picker) = app.picker.filter(|picker| picker.open) {
+        draw_digit_picker(frame, theme, &picker);
+    }
 
+    if let Some(hint) = app.hint.as_ref().filter(|h| h.explained)
+        && !app.is_paused()
+    {
+        draw_hint_explanation(frame, theme, hint, sidebar_rect);
+    }
+    if app.show_debug
+        && let Some(sudoku) = &app.sudoku
+    {
+        draw_debug_overlay(frame, theme, sudoku, &app.latency);
+    }
+    if app.quit_prompt {
+        draw_quit_prompt(frame, app);
+    }
+    if let Some(confirm) = app.confirm {
+        draw_confirm_popup(frame, app, confirm);
+    }
+    if let Some(win) = &app.win {
+        match win.quiz.as_ref().filter(|_| win.quizzing) {
+            Some(quiz) => draw_quiz(frame, app, quiz),
+            None => draw_win_screen(frame, app, win),
+        }
+    }
+    if let Some(input) = &app.comment_input {
+        draw_comment_popup(frame, app, input);
+    }
+    if let Some(input) = &app.command_input {
+        draw_command_popup(frame, app, input);
+    }
+    if app.note_color_popup {
+        draw_note_color_popup(frame, app);
+    }
+    if app.show_help {
+        draw_help_popup(frame, app, variant);
+    }
+}
+
+/// Draws `lines` in a bordered box centered over whatever is on screen,
+/// clearing what lies beneath it first.
+fn draw_modal(frame: &mut Frame, size: (u16, u16), title: &str, footer: &str, lines: Vec<Line>) {
+    let area = calculate_centered_rect(frame.area(), size.0, size.1);
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string())
+            .title_bottom(footer.to_string()),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+/// Draws the popup listing every key of the game screen, two to a line,
+/// followed by the rules of the game being played
+fn draw_help_popup(frame: &mut Frame, app: &App, variant: Variant) {
+    const KEY_WIDTH: usize = 11;
+    const TEXT_WIDTH: usize = 24;
+    let theme = &app.theme;
+    let key_style = Style::default()
+        .fg(theme.title_fg)
+        .add_modifier(Modifier::BOLD);
+    let text_style = Style::default().fg(theme.text_fg);
+    let moves = [Action::Up, Action::Down, Action::Left, Action::Right];
+    let mut entries = vec![
+        (app.keymap.movement_label(), "Move"),
+        ("1-9".to_string(), "Enter a digit"),
+        ("Shift+1-9".to_string(), "Toggle a pencil mark"),
+    ];
+    entries.extend(
+        Action::ALL
+            .into_iter()
+            .filter(|action| !moves.contains(action))
+            .map(|action| (app.keymap.label(action), action.description())),
+    );
+    let mut lines: Vec<Line> = entries
+        .chunks(2)
+        .map(|pair| {
+            let mut spans = Vec::new();
+            for (key, text) in pair {
+                spans.push(Span::styled(format!("{key:>KEY_WIDTH$} "), key_style));
+                spans.push(Span::styled(format!("{text:<TEXT_WIDTH$}"), text_style));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    let rule = |text: String| Line::from(Span::styled(text, Style::default().fg(theme.legend_fg)));
+    lines.push(Line::from(""));
+    let pause = app.keymap.label(Action::Pause);
+    lines.push(rule(format!(
+        " While paused only {pause}, {}, {}, {} and the Pause and Menu soft keys work.",
+        app.keymap.label(Action::Quit),
+        app.keymap.label(Action::Menu),
+        app.keymap.label(Action::Help)
+    )));
+    lines.push(rule(format!(" {}.", Variant::Classic.rule_text())));
+    if variant != Variant::Classic {
+        lines.push(rule(format!(
+            " {}: {}.",
+            variant.name(),
+            variant.rule_text()
+        )));
+    }
+    lines.push(rule(match app.game_validation {
+        _ if app.zen => " Zen game: no clock, no marked entries, no mistakes counted.".to_string(),
+        Validation::Conflicts => {
+            " With the live check on, entries repeating a peer's digit are marked.".to_string()
+        }
+        Validation::Solution => " Entries that differ from the solution are marked.".to_string(),
+    }));
+    if let Some(limit) = app.mistake_limit {
+        lines.push(rule(format!(
+            " The game ends after {limit} digits against the solution."
+        )));
+    }
+    if app.memory.is_some() {
+        lines.push(rule(format!(
+            " Memory mode: entries hide after {} seconds; recalls show them briefly.",
+            app.gameplay.memory_fade_seconds
+        )));
+    }
+    if let Some(countdown) = app.countdown {
+        lines.push(rule(format!(
+            " Time attack: the game is lost if not solved within {}.",
+            format_clock(countdown)
+        )));
+    }
+    let width = 2 * (KEY_WIDTH + 1 + TEXT_WIDTH) as u16 + 2;
+    let height = lines.len() as u16 + 2;
+    draw_modal(frame, (width, height), "Help", " Any key: Close ", lines);
+}
+
+/// Draws the popup giving each digit's pencil marks a color, every digit
+/// shown in the color it has now
+fn draw_note_color_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let digits: Vec<Span> = (1..=SIZE as u8)
+        .map(|digit| {
+            let color = app.note_colors[digit as usize - 1].unwrap_or(theme.legend_fg);
+            Span::styled(
+                format!(" {digit} "),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            )
+        })
+        .collect();
+    let lines = vec![
+        Line::from(Span::styled(
+            "Press a digit to step its notes through the colors",
+            Style::default().fg(theme.text_fg),
+        )),
+        Line::from(Span::styled(
+            "0 puts every digit back to the plain color",
+            Style::default().fg(theme.text_fg),
+        )),
+        Line::from(""),
+        Line::from(digits).alignment(Alignment::Center),
+    ];
+    draw_modal(frame, (56, 6), "Note Colors", " Enter/Esc: Done ", lines);
+}
+
+/// Draws the digit pad of a held click, marking the digit under the pointer
+fn draw_digit_picker(frame: &mut Frame, theme: &Theme, picker: &DigitPicker) {
+    let Some(area) = picker.rect else {
+        return;
+    };
+    let lines: Vec<Line> = (0..3u8)
+        .map(|row| {
+            Line::from(
+                (1..=3u8)
+                    .map(|col| {
+                        let digit = row * 3 + col;
+                        let style = if picker.hover == Some(digit) {
+                            Style::default()
+                                .fg(theme.text_fg)
+                                .bg(theme.highlight_bg)
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(theme.text_fg)
+                        };
+                        Span::styled(format!(" {digit} "), style)
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect();
+    let pad = Paragraph::new(lines).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(Clear, area);
+    frame.render_widget(pad, area);
+}
+
+/// Draws the comment text-input popup over the game, listing earlier comments
+fn draw_comment_popup(frame: &mut Frame, app: &App, input: &str) {
+    let theme = &app.theme;
+    let area = calculate_centered_rect(frame.area(), 60, 10);
+    let mut lines: Vec<Line> = app
+        .comments
+        .iter()
+        .map(|comment| {
+            Line::from(vec![
+                Span::styled(
+                    format!("[{}] ", format_clock(comment.elapsed_secs)),
+                    Style::default().fg(theme.timer_fg),
+                ),
+                Span::styled(comment.text.clone(), Style::default().fg(theme.text_fg)),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("> ", Style::default().fg(theme.title_fg)),
+        Span::styled(input.to_string(), Style::default().fg(theme.text_fg)),
+        Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+    ]));
+    // Keep the input line visible when there are many comments
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let scroll = lines.len().saturating_sub(inner_height) as u16;
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Add Comment")
+                .title_bottom(" Enter: Save, Esc: Cancel "),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+/// Draws the line for entering cells by coordinates
+fn draw_command_popup(frame: &mut Frame, app: &App, input: &str) {
+    let theme = &app.theme;
+    let lines = vec![
+        Line::from(Span::styled(
+            "r5c3=7 enters a digit, r5c3 clear empties the cell",
+            Style::default().fg(theme.text_fg),
+        )),
+        Line::from(Span::styled(
+            "Separate several with spaces, commas or semicolons",
+            Style::default().fg(theme.text_fg),
+        )),
+        Line::from(Span::styled(
+            "singles fills cells with one candidate, unlink drops links",
+            Style::default().fg(theme.text_fg),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(":", Style::default().fg(theme.title_fg)),
+            Span::styled(input.to_string(), Style::default().fg(theme.text_fg)),
+            Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+        ]),
+    ];
+    draw_modal(
+        frame,
+        (60, 8),
+        "Enter Cells",
+        " Enter: Run, Esc: Cancel ",
+        lines,
+    );
+}
+
+/// Draws the menu's prompt for a seed to play
+fn draw_seed_popup(frame: &mut Frame, app: &App, input: &str) {
+    let theme = &app.theme;
+    let area = calculate_centered_rect(frame.area(), 50, 7);
+    let lines = vec![
+        Line::from(Span::styled(
+            format!(
+                "{} {}",
+                app.selected_difficulty().name(),
+                app.selected_variant().name()
+            ),
+            Style::default().fg(theme.text_fg),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.title_fg)),
+            Span::styled(input.to_string(), Style::default().fg(theme.text_fg)),
+            Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+        ]),
+    ];
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Play Seed")
+            .title_bottom(" Enter: Preview, Esc: Cancel "),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+/// Places the soft key buttons centered in `area`, with the label each shows.
+fn layout_soft_keys(app: &App, area: Rect) -> Vec<(Rect, SoftKey, String)> {
+    let labels: Vec<(SoftKey, String)> = SoftKey::ALL
+        .into_iter()
+        .map(|key| {
+            let label = match key {
+                SoftKey::Pause if app.is_paused() => "Resume",
+                SoftKey::Notes if app.note_mode => "Notes on",
+                _ => key.label(),
+            };
+            (key, format!("[{label}]"))
+        })
+        .collect();
+    let gap = 2;
+    let total: u16 = labels
+        .iter()
+        .map(|(_, l)| l.len() as u16 + gap)
+        .sum::<u16>()
+        - gap;
+    let mut x = area.x + area.width.saturating_sub(total) / 2;
+    let mut placed = Vec::new();
+    for (key, label) in labels {
+        let width = label.len() as u16;
+        if x + width > area.right() {
+            break;
+        }
+        placed.push((Rect::new(x, area.y, width, area.height.min(1)), key, label));
+        x += width + gap;
+    }
+    placed
+}
+
+/// Draws the generator report for a board in the top right corner, with how
+/// quickly input has been showing on screen
+fn draw_debug_overlay(frame: &mut Frame, theme: &Theme, sudoku: &SudokuGrid, latency: &Latency) {
+    let screen = frame.area();
+    let width = 42.min(screen.width);
+    let area = Rect::new(
+        screen.right() - width,
+        screen.y,
+        width,
+        12.min(screen.height),
+    );
+    let mut lines: Vec<Line> = match sudoku.generation_report() {
+        Some(report) => [
+            ("Attempts", report.attempts.to_string()),
+            ("Backtracks", report.backtracks.to_string()),
+            ("Uniqueness checks", report.uniqueness_checks.to_string()),
+            (
+                "Grade",
+                report
+                    .grade
+                    .map_or("Beyond the solver", |t| t.name())
+                    .to_string(),
+            ),
+            ("Elapsed", format!("{} ms", report.elapsed_ms)),
+        ]
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{label:<18}"), Style::default().fg(theme.legend_fg)),
+                Span::styled(value, Style::default().fg(theme.text_fg)),
+            ])
+        })
+        .collect(),
+        None => vec![Line::from(Span::styled(
+            "Not generated in this session",
+            Style::default().fg(theme.legend_fg),
+        ))],
+    };
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("{:<18}", "Board hash"),
+            Style::default().fg(theme.legend_fg),
+        ),
+        Span::styled(
+            puzzle_id::board_hash(sudoku),
+            Style::default().fg(theme.text_fg),
+        ),
+    ]));
+    lines.push(Line::from(""));
+    let ms = |took: Duration| format!("{} ms", took.as_millis());
+    let slow = Style::default().fg(theme.invalid_fg);
+    let fine = Style::default().fg(theme.text_fg);
+    let over = |took: Duration| if took > latency::BUDGET { slow } else { fine };
+    let rows = [
+        (
+            "Last input",
+            latency.last.map_or("-".to_string(), ms),
+            latency.last.map_or(fine, over),
+        ),
+        (
+            "Slowest",
+            latency
+                .worst
+                .as_ref()
+                .map_or("-".to_string(), |(event, took)| {
+                    format!("{event}, {}", ms(*took))
+                }),
+            latency.worst.as_ref().map_or(fine, |&(_, took)| over(took)),
+        ),
+        (
+            "Over budget",
+            format!(
+                "{} of {} ({})",
+                latency.over_budget,
+                latency.events,
+                ms(latency::BUDGET)
+            ),
+            if latency.over_budget > 0 { slow } else { fine },
+        ),
+    ];
+    lines.extend(rows.into_iter().map(|(label, value, style)| {
+        Line::from(vec![
+            Span::styled(format!("{label:<18}"), Style::default().fg(theme.legend_fg)),
+            Span::styled(value, style),
+        ])
+    }));
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Debug")
+            .title_bottom(" F12: Hide "),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+/// Draws each step of a fully revealed hint with why it holds, over the
+/// sidebar so the grid and the highlighted cells stay visible. On narrow
+/// screens it takes the bottom of the screen instead
+fn draw_hint_explanation(frame: &mut Frame, theme: &Theme, hint: &Hint, sidebar: Rect) {
+    let screen = frame.area();
+    let right_room = screen.right().saturating_sub(sidebar.x);
+    let area = if right_room >= SIDEBAR_WIDTH {
+        Rect {
+            width: right_room.min(HINT_EXPLANATION_WIDTH),
+            ..sidebar
+        }
+    } else {
+        let height = (screen.height / 2).max(3);
+        Rect::new(screen.x, screen.bottom() - height, screen.width, height)
+    };
+    let text_width = area.width.saturating_sub(2) as usize;
+
+    let room = area.height.saturating_sub(2) as usize;
+
+    let blocks: Vec<Vec<Line>> =
+        hint.lead_up
+            .iter()
+            .chain([&hint.step])
+            .map(|step| {
+                let mut text = step.explanation();
+                if step.eliminations.is_empty() {
+                    text.push('.');
+                } else {
+                    text.push_str(&format!(". Removes {}.", removals(&step.eliminations)));
+                }
+                let mut block = vec![Line::from(Span::styled(
+                    step.technique.name(),
+                    Style::default().fg(theme.title_fg),
+                ))];
+                block.extend(wrap_words(&text, text_width).into_iter().map(|line| {
+                    Line::from(Span::styled(line, Style::default().fg(theme.text_fg)))
+                }));
+                block
+            })
+            .collect();
+    // Long chains keep their last steps, which lead straight to the placement,
+    // leaving a line to say how many were left out
+    let mut shown = 0;
+    let mut used = 0;
+    for block in blocks.iter().rev() {
+        let reserve = usize::from(shown + 1 < blocks.len());
+        if shown > 0 && used + block.len() + reserve > room {
+            break;
+        }
+        used += block.len();
+        shown += 1;
+    }
+    let skipped = blocks.len() - shown;
+    let mut lines = Vec::new();
+    if skipped > 0 {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "... {skipped} earlier step{}",
+                if skipped == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(theme.legend_fg),
+        )));
+    }
+    lines.extend(blocks.into_iter().skip(skipped).flatten());
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Why")
+            .title_bottom(" H: Hide "),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+/// Candidate removals in few words: `4 from r4c7 and r4c9` when they share a
+/// digit, otherwise per cell like `3 and 4 from r7c1, and 3 from r9c1`
+fn removals(eliminations: &[(usize, usize, u8)]) -> String {
+    let join = |items: Vec<String>| match items.as_slice() {
+        [rest @ .., last] if !rest.is_empty() => format!("{} and {last}", rest.join(", ")),
+        _ => items.concat(),
+    };
+    let digit = eliminations[0].2;
+    if eliminations.iter().all(|&(_, _, d)| d == digit) {
+        let cells = eliminations
+            .iter()
+            .map(|&(r, c, _)| solver::cell_name((r, c)))
+            .collect();
+        return format!("{digit} from {}", join(cells));
+    }
+    let mut per_cell: Vec<((usize, usize), Vec<String>)> = Vec::new();
+    for &(r, c, d) in eliminations {
+        match per_cell.iter_mut().find(|(cell, _)| *cell == (r, c)) {
+            Some((_, digits)) => digits.push(d.to_string()),
+            None => per_cell.push(((r, c), vec![d.to_string()])),
+        }
+    }
+    let parts: Vec<String> = per_cell
+        .into_iter()
+        .map(|(cell, digits)| format!("{} from {}", join(digits), solver::cell_name(cell)))
+        .collect();
+    match parts.as_slice() {
+        [rest @ .., last] if !rest.is_empty() => format!("{}, and {last}", rest.join(", ")),
+        _ => parts.concat(),
+    }
+}
+
+/// Breaks text into lines of at most `width` characters at spaces.
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Draws the popup asking how to leave an unfinished game
+fn draw_quit_prompt(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = calculate_centered_rect(frame.area(), 56, 5);
+    let save = if app.save_replaces_other {
+        "Save and quit, replacing the earlier save"
+    } else {
+        "Save and quit, resume next time"
+    };
+    let lines: Vec<Line> = [
+        ("s / Enter", save),
+        ("a", "Abandon and quit"),
+        ("f", "Abandon, the solver finishes the board"),
+    ]
+    .into_iter()
+    .map(|(key, action)| {
+        Line::from(vec![
+            Span::styled(format!("{key:>10}  "), Style::default().fg(theme.title_fg)),
+            Span::styled(action, Style::default().fg(theme.text_fg)),
+        ])
+    })
+    .collect();
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Quit")
+            .title_bottom(" Esc: Keep playing "),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+/// Draws the popup asking yes or no before an action that loses the game
+fn draw_confirm_popup(frame: &mut Frame, app: &App, confirm: Confirm) {
+    let theme = &app.theme;
+    let lines = vec![
+        Line::from(Span::styled(
+            confirm.question(),
+            Style::default()
+                .fg(theme.text_fg)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .alignment(Alignment::Center),
+        Line::from(Span::styled(
+            confirm.detail(),
+            Style::default().fg(theme.legend_fg),
+        ))
+        .alignment(Alignment::Center),
+    ];
+    let footer = format!(
+        " {}y / Enter: {}, n / Esc: {} ",
+        if confirm.offers_save() {
+            "s: Save first, "
+        } else {
+            ""
+        },
+        confirm.accept(),
+        confirm.decline()
+    );
+    let width = (footer.chars().count() as u16 + 4).max(44);
+    draw_modal(frame, (width, 4), "Confirm", &footer, lines);
+}
+
+/// Draws the win screen over the solved board: the game's figures, how the
+/// time compares with the earlier solves, and what to do next
+fn draw_win_screen(frame: &mut Frame, app: &App, win: &WinSummary) {
+    let theme = &app.theme;
+    let label = Style::default().fg(theme.legend_fg);
+    let value = Style::default()
+        .fg(theme.text_fg)
+        .add_modifier(Modifier::BOLD);
+    let row = |name: &str, text: String| {
+        Line::from(vec![
+            Span::styled(format!(" {name:<12}"), label),
+            Span::styled(text, value),
+        ])
+    };
+    let (comparison, color) = match win.previous_best {
+        None => (
+            "First solve at this difficulty!".to_string(),
+            theme.success_fg,
+        ),
+        Some(best) if win.is_best() => (
+            format!(
+                "New personal best, {} faster!",
+                format_clock(best - win.elapsed_secs)
+            ),
+            theme.success_fg,
+        ),
+        Some(best) => (
+            format!(
+                "{} off your best of {}",
+                format_clock(win.elapsed_secs - best),
+                format_clock(best)
+            ),
+            theme.info_fg,
+        ),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Congratulations, solved!",
+            Style::default()
+                .fg(theme.success_fg)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .alignment(Alignment::Center),
+        Line::from(""),
+        row(
+            "Puzzle",
+            format!("{} {}", win.difficulty.name(), win.variant.name()),
+        ),
+        row("Time", format_clock(win.elapsed_secs)),
+        row("Score", win.score.to_string()),
+        row("Mistakes", win.mistakes.to_string()),
+        row("Hints used", win.hints_used.to_string()),
+        row(
+            "Average",
+            win.previous_average
+                .map_or_else(|| "-".to_string(), format_clock),
+        ),
+        row("Solves", win.solves.to_string()),
+        Line::from(""),
+        Line::from(Span::styled(comparison, Style::default().fg(color)))
+            .alignment(Alignment::Center),
+        Line::from(""),
+    ];
+    for (i, choice) in win.choices().iter().enumerate() {
+        let style = if i == win.selected {
+            Style::default()
+                .fg(theme.text_fg)
+                .bg(theme.highlight_bg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text_fg)
+        };
+        let marker = if i == win.selected { ">" } else { " " };
+        lines.push(Line::from(Span::styled(
+            format!(" {marker} {:<36}", choice.label()),
+            style,
+        )));
+    }
+    let height = lines.len() as u16 + 2;
+    draw_modal(
+        frame,
+        (48, height),
+        "Solved",
+        " Up/Down: Choose, Enter: Select, Esc: Board ",
+        lines,
+    );
+}
+
+/// Draws the technique quiz over the win screen: the position with the
+/// cell in question marked, the answers, and once one is picked whether it
+/// was right.
+fn draw_quiz(frame: &mut Frame, app: &App, quiz: &Quiz) {
+    let theme = &app.theme;
+    let border = Style::default().fg(theme.border_fg);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!(
+                "What technique places the {} at {}?",
+                quiz.digit,
+                solver::cell_name(quiz.cell)
+            ),
+            Style::default()
+                .fg(theme.title_fg)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .alignment(Alignment::Center),
+        Line::from(""),
+    ];
+    for (r, row) in quiz.board.iter().enumerate() {
+        if r > 0 && r % 3 == 0 {
+            lines.push(
+                Line::from(Span::styled("──────┼───────┼──────", border))
+                    .alignment(Alignment::Center),
+            );
+        }
+        let mut spans = Vec::new();
+        for (c, &digit) in row.iter().enumerate() {
+            if c > 0 && c % 3 == 0 {
+                spans.push(Span::styled(" │", border));
+            }
+            if c > 0 {
+                spans.push(Span::raw(" "));
+            }
+            let span = if (r, c) == quiz.cell {
+                Span::styled(
+                    quiz.digit.to_string(),
+                    Style::default()
+                        .fg(theme.text_fg)
+                        .bg(theme.highlight_bg)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else if digit == 0 {
+                Span::styled("·", Style::default().fg(theme.legend_fg))
+            } else {
+                Span::styled(digit.to_string(), Style::default().fg(theme.fixed_fg))
+            };
+            spans.push(span);
+        }
+        lines.push(Line::from(spans).alignment(Alignment::Center));
+    }
+    lines.push(Line::from(""));
+    for (i, technique) in quiz.choices.iter().enumerate() {
+        let style = match quiz.picked {
+            Some(_) if *technique == quiz.answer => Style::default()
+                .fg(theme.success_fg)
+                .add_modifier(Modifier::BOLD),
+            Some(picked) if picked == i => Style::default().fg(theme.invalid_fg),
+            None if i == quiz.selected => Style::default()
+                .fg(theme.text_fg)
+                .bg(theme.highlight_bg)
+                .add_modifier(Modifier::BOLD),
+            _ => Style::default().fg(theme.text_fg),
+        };
+        let marker = if quiz.picked.is_none() && i == quiz.selected {
+            ">"
+        } else {
+            " "
+        };
+        lines.push(Line::from(Span::styled(
+            format!(" {marker} {}. {:<36}", i + 1, technique.name()),
+            style,
+        )));
+    }
+    lines.push(Line::from(""));
+    let (verdict, color) = match quiz.correct() {
+        None => ("Pick the technique.".to_string(), theme.legend_fg),
+        Some(true) => ("Right!".to_string(), theme.success_fg),
+        Some(false) => (
+            format!("Not quite, it takes a {}.", quiz.answer.name()),
+            theme.invalid_fg,
+        ),
+    };
+    lines.push(
+        Line::from(Span::styled(verdict, Style::default().fg(color))).alignment(Alignment::Center),
+    );
+    let footer = if quiz.picked.is_none() {
+        " Up/Down, 1-4: Choose, Enter: Answer "
+    } else {
+        " Enter/Esc: Back "
+    };
+    let height = lines.len() as u16 + 2;
+    draw_modal(frame, (48, height), "Technique Quiz", footer, lines);
+}
+
+/// Draws the daily puzzle screen with the streak and streak freezes
+fn draw_daily(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let progress = &app.daily_progress;
+    let today = daily::today();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Details
+            Constraint::Length(2), // Instructions
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new(format!("Daily Puzzle - {}", today.format("%A, %Y-%m-%d")))
+        .style(
+            Style::default()
+                .fg(theme.title_fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let label = Style::default().fg(theme.legend_fg);
+    let value = Style::default()
+        .fg(theme.text_fg)
+        .add_modifier(Modifier::BOLD);
+    let status = if progress.completed(today) {
+        Span::styled("Solved today ✓", Style::default().fg(theme.success_fg))
+    } else {
+        Span::styled("Not solved yet", value)
+    };
+    let freezes = if progress.freezes == 0 {
+        "none".to_string()
+    } else {
+        "❄ ".repeat(progress.freezes as usize)
+    };
+    // The week's schedule, with today's day picked out
+    let mut schedule = vec![Span::styled("Schedule:        ", label)];
+    for weekday in iter::successors(Some(Weekday::Mon), |day| Some(day.succ())).take(7) {
+        let style = if weekday == today.weekday() {
+            value.fg(theme.title_fg)
+        } else {
+            label
+        };
+        if weekday != Weekday::Mon {
+            schedule.push(Span::styled(", ", label));
+        }
+        schedule.push(Span::styled(
+            format!("{weekday} {:?}", daily::scheduled(weekday)),
+            style,
+        ));
+    }
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Difficulty:      ", label),
+            Span::styled(format!("{:?}", daily::difficulty_for(today)), value),
+            Span::styled(format!(" ({}s)", today.format("%A")), label),
+        ]),
+        Line::from(schedule),
+        Line::from(vec![Span::styled("Today:           ", label), status]),
+        Line::from(vec![
+            Span::styled("Streak:          ", label),
+            Span::styled(
+                format!("{} days (best {})", progress.streak, progress.best_streak),
+                value,
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Streak freezes:  ", label),
+            Span::styled(freezes, Style::default().fg(theme.info_fg)),
+            Span::styled(
+                format!(" ({}/{})", progress.freezes, daily::MAX_FREEZES),
+                label,
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "Every {}-day streak earns a freeze. A freeze is spent automatically on a missed day so the streak survives.",
+                daily::DAYS_PER_FREEZE
+            ),
+            label,
+        )),
+    ];
+    if !progress.frozen_days.is_empty() {
+        let recent: Vec<String> = progress
+            .frozen_days
+            .iter()
+            .rev()
+            .take(5)
+            .map(|d| d.to_string())
+            .collect();
+        lines.push(Line::from(Span::styled(
+            format!("Protected days: {}", recent.join(", ")),
+            label,
+        )));
+    }
+    let details = Paragraph::new(lines)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(details, chunks[1]);
+
+    let mut instruction_lines = vec![Line::from(Span::styled(
+        "Enter: Play today's puzzle, Esc/b: Back, q: Quit.",
+        Style::default().fg(theme.instructions_fg),
+    ))];
+    if let Some(message) = &app.status_message {
+        instruction_lines.push(Line::from(Span::styled(
+            message.clone(),
+            Style::default().fg(theme.info_fg),
+        )));
+    }
+    frame.render_widget(
+        Paragraph::new(instruction_lines).alignment(Alignment::Center),
+        chunks[2],
+    );
+}
+
+/// Draws the practice ladder: every rung with its difficulty and best time,
+/// the next one to climb and those still locked.
+fn draw_ladder(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let progress = &app.ladder_progress;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Rungs
+            Constraint::Length(2), // Instructions
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new(format!(
+        "Practice Ladder - {}/{} rungs cleared",
+        progress.cleared(),
+        ladder::RUNGS
+    ))
+    .style(
+        Style::default()
+            .fg(theme.title_fg)
+            .add_modifier(Modifier::BOLD),
+    )
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let rungs: Vec<ListItem> = (0..ladder::RUNGS)
+        .map(|rung| {
+            let (status, style) = match progress.best(rung) {
+                Some(best) => (
+                    format!("✓ best {}", format_clock(best)),
+                    Style::default().fg(theme.success_fg),
+                ),
+                None if progress.unlocked(rung) => (
+                    "next".to_string(),
+                    Style::default()
+                        .fg(theme.text_fg)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                None => ("locked".to_string(), Style::default().fg(theme.legend_fg)),
+            };
+            ListItem::new(format!(
+                "Rung {:>2}  {:<8} {status}",
+                rung + 1,
+                ladder::difficulty_for(rung).name()
+            ))
+            .style(style)
+        })
+        .collect();
+    let list = List::new(rungs)
+        .block(Block::default().borders(Borders::ALL).title("Rungs"))
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight_bg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected_rung));
+    frame.render_stateful_widget(list, chunks[1], &mut list_state);
+
+    let mut instruction_lines = vec![Line::from(Span::styled(
+        "Up/Down (k/j): Choose a rung, Enter: Play, Esc/b: Back, q: Quit.",
+        Style::default().fg(theme.instructions_fg),
+    ))];
+    if let Some(message) = &app.status_message {
+        instruction_lines.push(Line::from(Span::styled(
+            message.clone(),
+            Style::default().fg(theme.info_fg),
+        )));
+    }
+    frame.render_widget(
+        Paragraph::new(instruction_lines).alignment(Alignment::Center),
+        chunks[2],
+    );
+}
+
+/// Draws the tournament standings between games
+fn draw_tournament(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let Some(run) = &app.tournament else {
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Length(1), // Rules
+            Constraint::Min(0),    // Puzzles
+            Constraint::Length(1), // Status
+            Constraint::Length(1), // Instructions
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new(format!("Tournament: {}", run.tournament.name))
+        .style(
+            Style::default()
+                .fg(theme.title_fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let rules = &run.tournament.rules;
+    let time_limit = rules
+        .time_limit_secs
+        .map_or("no time limit".to_string(), |secs| {
+            format!("{} per puzzle", format_clock(secs))
+        });
+    let hints = match rules.hints {
+        HintPolicy::Off => "no hints",
+        HintPolicy::Allowed => "hints allowed",
+    };
+    frame.render_widget(
+        Paragraph::new(format!("{}, {time_limit}, {hints}", rules.variant.name()))
+            .style(Style::default().fg(theme.legend_fg))
+            .alignment(Alignment::Center),
+        chunks[1],
+    );
+
+    let next = run.next_index();
+    let items: Vec<ListItem> = run
+        .tournament
+        .puzzles
+        .iter()
+        .enumerate()
+        .map(|(i, spec)| {
+            let (result, style) = match run.results.get(i) {
+                Some(result) => {
+                    let (outcome, color) = match result.outcome {
+                        ResultOutcome::Solved => ("Solved", theme.success_fg),
+                        ResultOutcome::TimedOut => ("Timed out", theme.invalid_fg),
+                        ResultOutcome::Forfeited => ("Forfeited", theme.invalid_fg),
+                    };
+                    (
+                        format!(
+                            "{outcome:<10} {}  {} pts",
+                            format_clock(result.elapsed_secs),
+                            result.score
+                        ),
+                        Style::default().fg(color),
+                    )
+                }
+                None if next == Some(i) => ("Next".to_string(), Style::default().fg(theme.info_fg)),
+                None => (String::new(), Style::default().fg(theme.text_fg)),
+            };
+            ListItem::new(format!(
+                "{:>2}. {:<8} {result}",
+                i + 1,
+                format!("{:?}", spec.difficulty)
+            ))
+            .style(style)
+        })
+        .collect();
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Puzzles - total {} pts", run.total_score())),
+    );
+    frame.render_widget(list, chunks[2]);
+
+    if let Some(message) = &app.status_message {
+        frame.render_widget(
+            Paragraph::new(message.clone())
+                .style(Style::default().fg(theme.info_fg))
+                .alignment(Alignment::Center),
+            chunks[3],
+        );
+    }
+    let instructions = match next {
+        Some(i) => format!("Enter: Play puzzle {}, q: Quit.", i + 1),
+        None => format!(
+            "All puzzles played. Results are in {}. q: Quit.",
+            run.results_path.display()
+        ),
+    };
+    frame.render_widget(
+        Paragraph::new(instructions)
+            .style(Style::default().fg(theme.instructions_fg))
+            .alignment(Alignment::Center),
+        chunks[4],
+    );
+}
+
+/// Draws the challenge screen: the three puzzles of the seed and their times
+fn draw_challenge(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let Some(challenge) = &app.challenge else {
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Length(1), // Code
+            Constraint::Min(0),    // Puzzles
+            Constraint::Length(1), // Status
+            Constraint::Length(1), // Instructions
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new(format!(
+        "{} Cross-Variant Challenge",
+        challenge.difficulty.name()
+    ))
+    .style(
+        Style::default()
+            .fg(theme.title_fg)
+            .add_modifier(Modifier::BOLD),
+    )
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    frame.render_widget(
+        Paragraph::new(format!(
+            "One solution grid, three rule sets. Replay with: tui_sudoku challenge {}",
+            challenge.code()
+        ))
+        .style(Style::default().fg(theme.legend_fg))
+        .alignment(Alignment::Center),
+        chunks[1],
+    );
+
+    let next = challenge.next_index();
+    let items: Vec<ListItem> = challenge
+        .puzzles
+        .iter()
+        .enumerate()
+        .map(|(i, sudoku)| {
+            let (result, style) = match challenge.times.get(i) {
+                Some(time) => (
+                    format!("Solved     {}", format_clock(time.as_secs())),
+                    Style::default().fg(theme.success_fg),
+                ),
+                None if next == Some(i) => ("Next".to_string(), Style::default().fg(theme.info_fg)),
+                None => (String::new(), Style::default().fg(theme.text_fg)),
+            };
+            ListItem::new(format!(
+                "{:>2}. {:<14} {result}",
+                i + 1,
+                sudoku.variant().name()
+            ))
+            .style(style)
+        })
+        .collect();
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(format!(
+        "Puzzles - total {}",
+        format_clock(challenge.total_time().as_secs())
+    )));
+    frame.render_widget(list, chunks[2]);
+
+    if let Some(message) = &app.status_message {
+        frame.render_widget(
+            Paragraph::new(message.clone())
+                .style(Style::default().fg(theme.info_fg))
+                .alignment(Alignment::Center),
+            chunks[3],
+        );
+    }
+    let instructions = match next {
+        Some(i) => format!(
+            "Enter: Play puzzle {}, Esc/b: Give up and go back, q: Quit.",
+            i + 1
+        ),
+        None => "Challenge complete. Esc/b: Back, q: Quit.".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(instructions)
+            .style(Style::default().fg(theme.instructions_fg))
+            .alignment(Alignment::Center),
+        chunks[4],
+    );
+}
+
+/// Draws the import screen: a paste area, then the parsed board for fixing misreads
+fn draw_import(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Paste area or review
+            Constraint::Length(1), // Status
+            Constraint::Length(1), // Instructions
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new("Import Puzzle")
+        .style(
+            Style::default()
+                .fg(theme.title_fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let instructions = match &app.import_review {
+        None => {
+            // Keep the end of long pastes in view
+            let lines: Vec<&str> = app.import_text.lines().collect();
+            let visible = chunks[1].height.saturating_sub(2) as usize;
+            let text = if lines.is_empty() {
+                Text::from(Span::styled(
+                    "Paste a puzzle: one row per line, blanks as 0 . * or _",
+                    Style::default().fg(theme.legend_fg),
+                ))
+            } else {
+                Text::from(
+                    lines[lines.len().saturating_sub(visible)..]
+                        .iter()
+                        .map(|line| Line::from(line.to_string()))
+                        .collect::<Vec<_>>(),
+                )
+            };
+            frame.render_widget(
+                Paragraph::new(text)
+                    .style(Style::default().fg(theme.text_fg))
+                    .block(Block::default().borders(Borders::ALL).title("Pasted text")),
+                chunks[1],
+            );
+            "Paste or type the puzzle, Tab: Review, Esc: Back."
+        }
+        Some(review) => {
+            let grid_rect =
+                calculate_centered_rect(chunks[1], GRID_WIDTH + SIDEBAR_WIDTH + 2, GRID_HEIGHT);
+            let grid_rect = Rect {
+                width: GRID_WIDTH,
+                ..grid_rect
+            };
+            frame.render_widget(
+                Paragraph::new(build_import_grid(theme, review))
+                    .block(Block::default().borders(Borders::ALL).title("Parsed board"))
+                    .alignment(Alignment::Center),
+                grid_rect,
+            );
+            let info_rect = Rect {
+                x: grid_rect.right() + 2,
+                width: SIDEBAR_WIDTH,
+                ..grid_rect
+            };
+            if info_rect.right() <= chunks[1].right() {
+                draw_import_checks(frame, theme, review, info_rect);
+            }
+            "Arrows/hjkl: Move, 1-9: Set, 0/Del: Clear, Enter: Play, Esc: Edit text."
+        }
+    };
+
+    if let Some(message) = &app.status_message {
+        frame.render_widget(
+            Paragraph::new(message.clone())
+                .style(Style::default().fg(theme.info_fg))
+                .alignment(Alignment::Center),
+            chunks[2],
+        );
+    }
+    frame.render_widget(
+        Paragraph::new(instructions)
+            .style(Style::default().fg(theme.instructions_fg))
+            .alignment(Alignment::Center),
+        chunks[3],
+    );
+}
+
+/// Summary of what is wrong with the board under review
+fn draw_import_checks(frame: &mut Frame, theme: &Theme, review: &ImportReview, area: Rect) {
+    let (verdict, style) = match (review.conflicts.is_empty(), review.solutions) {
+        (false, _) => ("Clues clash", Style::default().fg(theme.invalid_fg)),
+        (true, 0) => ("No solution", Style::default().fg(theme.invalid_fg)),
+        (true, 1) => ("Unique solution", Style::default().fg(theme.success_fg)),
+        _ => ("Several solutions", Style::default().fg(theme.title_fg)),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{} clues", review.clue_count()),
+            Style::default().fg(theme.text_fg),
+        )),
+        Line::from(Span::styled(verdict, style.add_modifier(Modifier::BOLD))),
+    ];
+    if !review.conflicts.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("{} cells in conflict", review.conflicts.len()),
+            Style::default().fg(theme.invalid_fg),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.extend(review.warnings.iter().map(|warning| {
+        Line::from(Span::styled(
+            warning.clone(),
+            Style::default().fg(theme.legend_fg),
+        ))
+    }));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL).title("Checks")),
+        area,
+    );
+}
+
+/// Builds the grid for the board under review, marking clashing clues.
+fn build_import_grid(theme: &Theme, review: &ImportReview) -> Text<'static> {
+    const H_BORDER: &str = "───┼───┼───┼───┼───┼───┼───┼───┼───";
+    let border_style = Style::default().fg(theme.border_fg);
+    let mut lines = Vec::new();
+    for r in 0..SIZE {
+        if r > 0 {
+            lines.push(Line::from(H_BORDER).style(border_style));
+        }
+        let mut spans = Vec::new();
+        for c in 0..SIZE {
+            if c > 0 {
+                spans.push(Span::styled("│", border_style));
+            }
+            let mut style = Style::default()
+                .fg(theme.fixed_fg)
+                .add_modifier(Modifier::BOLD);
+            if review.conflicts.contains(&(r, c)) {
+                style = style.fg(theme.invalid_fg);
+            }
+            if review.cursor == (r, c) {
+                style = style.bg(theme.cursor_bg);
+            }
+            let text = match review.clues[r][c] {
+                0 => "   ".to_string(),
+                d => format!(" {d} "),
+            };
+            spans.push(Span::styled(text, style));
+        }
+        lines.push(Line::from(spans));
+    }
+    Text::from(lines)
+}
+
+/// Draws a "find the mistake" round: the position, the clock and the score
+fn draw_hunt(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let Some(hunt) = &app.hunt else {
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Board and clock
+            Constraint::Length(1), // Status
+            Constraint::Length(1), // Instructions
+        ])
+        .split(frame.area());
+
+    let title = match hunt.difficulty {
+        Some(difficulty) => format!("Find the Mistake - {}", difficulty.name()),
+        None => "Find the Mistake - Your Board".to_string(),
+    };
+    let title = Paragraph::new(title)
+        .style(
+            Style::default()
+                .fg(theme.title_fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let to_find = hunt.hidden.len() + hunt.found.len();
+    let grid_rect = calculate_centered_rect(chunks[1], GRID_WIDTH + SIDEBAR_WIDTH + 2, GRID_HEIGHT);
+    let grid_rect = Rect {
+        width: GRID_WIDTH,
+        ..grid_rect
+    };
+    frame.render_widget(
+        Paragraph::new(build_hunt_grid(theme, hunt, app.colorblind))
+            .block(Block::default().borders(Borders::ALL).title("Position"))
+            .alignment(Alignment::Center),
+        grid_rect,
+    );
+    let info_rect = Rect {
+        x: grid_rect.right() + 2,
+        width: SIDEBAR_WIDTH,
+        ..grid_rect
+    };
+    if info_rect.right() <= chunks[1].right() {
+        let left = hunt.time_left();
+        let clock_fg = if left.as_secs() < 15 {
+            theme.pace_behind_fg
+        } else {
+            theme.timer_fg
+        };
+        let lines = vec![
+            Line::from(Span::styled(
+                format!("Time left {}", format_clock(left.as_secs())),
+                Style::default().fg(clock_fg).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("Found {}/{to_find}", hunt.found.len()),
+                Style::default().fg(theme.text_fg),
+            )),
+            Line::from(Span::styled(
+                format!("Wrong picks {}", hunt.misses.len()),
+                Style::default().fg(theme.text_fg),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("Round {}", hunt.rounds),
+                Style::default().fg(theme.legend_fg),
+            )),
+            Line::from(Span::styled(
+                format!("Score {}", hunt.score),
+                Style::default()
+                    .fg(theme.text_fg)
+                    .add_modifier(Modifier::BOLD),
+            )),
+        ];
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Hunt")),
+            info_rect,
+        );
+    }
+
+    let status = match &app.status_message {
+        _ if hunt.is_over() && !hunt.hidden.is_empty() => {
+            Some("Time's up, the mistakes are marked".to_string())
+        }
+        Some(message) => Some(message.clone()),
+        None if hunt.is_over() => None,
+        None if hunt.difficulty.is_some() => {
+            Some("One entry is wrong, though nothing clashes. Which?".to_string())
+        }
+        None => Some(format!("Your board has {to_find} wrong. Where?")),
+    };
+    if let Some(status) = status {
+        frame.render_widget(
+            Paragraph::new(status)
+                .style(Style::default().fg(theme.info_fg))
+                .alignment(Alignment::Center),
+            chunks[2],
+        );
+    }
+    let instructions = match (hunt.is_over(), hunt.difficulty) {
+        (false, _) => "Arrows/hjkl: Move, Enter/Space: Pick the mistake, Esc/b: Back, q: Quit.",
+        (true, Some(_)) => "Enter: Next round, Esc/b: Back, q: Quit.",
+        (true, None) => "Esc/b: Back to the game, q: Quit.",
+    };
+    frame.render_widget(
+        Paragraph::new(instructions)
+            .style(Style::default().fg(theme.instructions_fg))
+            .alignment(Alignment::Center),
+        chunks[3],
+    );
+}
+
+/// Builds the grid of a hunt: found mistakes and, once the round is over,
+/// the ones missed in the invalid color, and wrong picks struck through.
+fn build_hunt_grid(theme: &Theme, hunt: &Hunt, colorblind: bool) -> Text<'static> {
+    const H_BORDER: &str = "───┼───┼───┼───┼───┼───┼───┼───┼───";
+    let border_style = Style::default().fg(theme.border_fg);
+    let mut lines = Vec::new();
     for r in 0..SIZE {
         if r > 0 {
-            // Apply the single border style to horizontal lines
             lines.push(Line::from(H_BORDER).style(border_style));
         }
-
-        let mut line_spans = Vec::new();
+        let mut spans = Vec::new();
         for c in 0..SIZE {
             if c > 0 {
-                // Apply the single border style to vertical lines
-                line_spans.push(Span::styled(V_SEP.to_string(), border_style));
+                spans.push(Span::styled("│", border_style));
+            }
+            let cell = (r, c);
+            let (mut style, mark) = if hunt.board.is_fixed(r, c) {
+                (
+                    Style::default()
+                        .fg(theme.fixed_fg)
+                        .add_modifier(Modifier::BOLD),
+                    ' ',
+                )
+            } else if hunt.found.contains(&cell) {
+                (
+                    Style::default()
+                        .fg(theme.invalid_fg)
+                        .add_modifier(Modifier::BOLD),
+                    '!',
+                )
+            } else if hunt.is_over() && hunt.hidden.contains(&cell) {
+                (Style::default().fg(theme.invalid_fg).bg(theme.hint_bg), '?')
+            } else if hunt.misses.contains(&cell) {
+                (
+                    Style::default()
+                        .fg(theme.user_fg)
+                        .add_modifier(Modifier::CROSSED_OUT),
+                    ' ',
+                )
+            } else {
+                (Style::default().fg(theme.user_fg), ' ')
+            };
+            if hunt.cursor == cell && !hunt.is_over() {
+                style = style.bg(theme.cursor_bg);
             }
+            let mark = if colorblind { mark } else { ' ' };
+            let text = match hunt.board.get_cell(r, c, false) {
+                Some(d) => format!(" {d}{mark}"),
+                None => "   ".to_string(),
+            };
+            spans.push(Span::styled(text, style));
+        }
+        lines.push(Line::from(spans));
+    }
+    Text::from(lines)
+}
 
-            let cell_value = sudoku.get_cell(r, c, app.show_solution);
-            let is_cursor = (r, c) == app.cursor_pos;
-            let is_fixed = sudoku.is_fixed(r, c) && !app.show_solution;
+/// Draws the per-difficulty statistics of all recorded games
+fn draw_stats(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Table
+            Constraint::Length(1), // Instructions
+        ])
+        .split(frame.area());
 
-            // Use if let for cleaner validity check
-            let is_valid = if !is_fixed && !app.show_solution {
-                if let Some(value) = cell_value {
-                    sudoku.is_valid_move(r, c, value)
-                } else {
-                    true // Empty cells are considered valid
-                }
+    let title = Paragraph::new("Statistics")
+        .style(
+            Style::default()
+                .fg(theme.title_fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let row = |cells: [String; 7]| {
+        format!(
+            "{:<10}{:>8}{:>8}{:>10}{:>10}{:>8}{:>8}",
+            cells[0], cells[1], cells[2], cells[3], cells[4], cells[5], cells[6]
+        )
+    };
+    let dash = || "-".to_string();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            row([
+                "Level", "Played", "Solved", "Win rate", "Average", "Best", "Hints",
+            ]
+            .map(str::to_string)),
+            Style::default().fg(theme.legend_fg),
+        )),
+        Line::from(""),
+    ];
+    lines.extend(app.stats.iter().map(|stats| {
+        Line::from(Span::styled(
+            row([
+                match stats.difficulty {
+                    Difficulty::Custom(clues) => format!("Custom {clues}"),
+                    named => named.name(),
+                },
+                stats.played.to_string(),
+                stats.solved.to_string(),
+                stats
+                    .win_rate()
+                    .map_or_else(dash, |rate| format!("{rate}%")),
+                stats.average_secs.map_or_else(dash, format_clock),
+                stats.best_secs.map_or_else(dash, format_clock),
+                stats.hints_used.to_string(),
+            ]),
+            Style::default().fg(if stats.played > 0 {
+                theme.text_fg
+            } else {
+                theme.legend_fg
+            }),
+        ))
+    }));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Win rate counts abandoned games; times are of solved games only.",
+        Style::default().fg(theme.legend_fg),
+    )));
+    let table = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("By Difficulty"),
+    );
+    frame.render_widget(table, chunks[1]);
+
+    let instructions = Paragraph::new("Esc/b: Back, q: Quit.")
+        .style(Style::default().fg(theme.instructions_fg))
+        .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+}
+
+/// Draws the settings screen: each option with its current value, the
+/// selected one highlighted, and a sample of the grid drawn as the color
+/// scheme and the colorblind mode make it look
+fn draw_settings(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Options
+            Constraint::Length(1), // Instructions
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new("Settings")
+        .style(
+            Style::default()
+                .fg(theme.title_fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let mut lines: Vec<Line> = Setting::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, &setting)| {
+            let picked = i == app.settings_index;
+            let style = if picked {
+                Style::default()
+                    .fg(theme.text_fg)
+                    .bg(theme.highlight_bg)
+                    .add_modifier(Modifier::BOLD)
             } else {
-                true // Fixed cells or when showing solution are considered valid
+                Style::default().fg(theme.text_fg)
+            };
+            Line::from(Span::styled(
+                format!(
+                    " {} {:<30} < {:^16} > ",
+                    if picked { ">" } else { " " },
+                    setting.label(),
+                    app.setting_value(setting)
+                ),
+                style,
+            ))
+        })
+        .collect();
+    let (given, invalid, mark) = if app.colorblind {
+        (
+            Modifier::BOLD | Modifier::UNDERLINED,
+            Modifier::REVERSED,
+            " 3!",
+        )
+    } else {
+        (Modifier::BOLD, Modifier::empty(), " 3 ")
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(
+            " 5 ",
+            Style::default().fg(theme.fixed_fg).add_modifier(given),
+        ),
+        Span::styled("│", Style::default().fg(theme.border_fg)),
+        Span::styled(" 3 ", Style::default().fg(theme.user_fg)),
+        Span::styled("│", Style::default().fg(theme.border_fg)),
+        Span::styled(
+            " 7 ",
+            Style::default().fg(theme.user_fg).bg(theme.cursor_bg),
+        ),
+        Span::styled("│", Style::default().fg(theme.border_fg)),
+        Span::styled(
+            mark,
+            Style::default().fg(theme.invalid_fg).add_modifier(invalid),
+        ),
+        Span::styled("│", Style::default().fg(theme.border_fg)),
+        Span::styled(
+            " ₁₂",
+            Style::default().fg(theme.legend_fg).bg(theme.peer_bg),
+        ),
+        Span::styled("│", Style::default().fg(theme.border_fg)),
+        Span::styled("   ", Style::default().bg(theme.hint_bg)),
+    ]));
+    if let Some(message) = &app.status_message {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            message.clone(),
+            Style::default().fg(theme.legend_fg),
+        )));
+    }
+    let options = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title("Options"));
+    frame.render_widget(options, chunks[1]);
+
+    let instructions = Paragraph::new(
+        "Up/Down (k/j): Select, Left/Right (h/l) or Enter: Change, Esc/b: Back, q: Quit.",
+    )
+    .style(Style::default().fg(theme.instructions_fg))
+    .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+}
+
+/// Draws the history browser: finished games on the left, details and comments on the right
+fn draw_history(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Browser
+            Constraint::Length(1), // Instructions
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new("Game History")
+        .style(
+            Style::default()
+                .fg(theme.title_fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[1]);
+
+    let items: Vec<ListItem> = app
+        .history
+        .iter()
+        .map(|record| {
+            let outcome = match record.outcome {
+                Outcome::Solved => "Solved",
+                Outcome::Abandoned if record.solver_finish.is_some() => {
+                    "Abandoned, solver finished"
+                }
+                outcome => outcome.name(),
             };
+            // The game's details run down beside its thumbnail
+            let details = [
+                format_timestamp(record.finished_at),
+                format!(
+                    "{} {}",
+                    record.sudoku.difficulty_name(),
+                    record.sudoku.variant().name()
+                ),
+                format_clock(record.elapsed_secs),
+                outcome.to_string(),
+            ];
+            let mut lines: Vec<Line> = thumbnail_lines(theme, &record.sudoku)
+                .into_iter()
+                .enumerate()
+                .map(|(i, mut line)| {
+                    if let Some(detail) = details.get(i) {
+                        line.spans.push(Span::raw(format!("   {detail}")));
+                    }
+                    line
+                })
+                .collect();
+            lines.push(Line::from(""));
+            ListItem::new(lines).style(Style::default().fg(theme.text_fg))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Games"))
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight_bg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+    let mut list_state = ListState::default();
+    if !app.history.is_empty() {
+        list_state.select(Some(app.history_index));
+    }
+    frame.render_stateful_widget(list, columns[0], &mut list_state);
 
-            let mut style = Style::default();
-            if is_cursor {
-                style = style.bg(Color::LightYellow);
+    let details = match app.history.get(app.history_index) {
+        Some(record) => {
+            let mut lines = Vec::new();
+            // Games recorded before move logging have no pace data
+            if !record.moves.is_empty() {
+                let metrics = Metrics::from_moves(&record.moves, record.elapsed_secs);
+                lines.extend(metrics_lines(theme, &metrics, true));
+                lines.push(Line::from(""));
+            }
+            if !record.eliminations.is_empty() {
+                lines.extend(elimination_lines(
+                    theme,
+                    &record.eliminations,
+                    &record.sudoku,
+                ));
+                lines.push(Line::from(""));
             }
-            if !is_valid {
-                style = style.fg(Color::Red);
-            } else if is_fixed {
-                style = style.add_modifier(Modifier::BOLD);
-            } else if cell_value.is_some() && !app.show_solution {
-                style = style.fg(Color::Blue);
+            if let Some(finish) = &record.solver_finish {
+                lines.extend(solver_finish_lines(theme, &record.sudoku, finish));
+                lines.push(Line::from(""));
             }
+            let revealed = record.sudoku.revealed_count();
+            if revealed > 0 {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "{revealed} cell{} revealed by hints",
+                        if revealed == 1 { "" } else { "s" }
+                    ),
+                    Style::default().fg(theme.info_fg),
+                )));
+                lines.push(Line::from(""));
+            }
+            let assisted = record.moves.iter().filter(|m| m.assisted).count();
+            if assisted > 0 {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "{assisted} cell{} filled as naked singles",
+                        if assisted == 1 { "" } else { "s" }
+                    ),
+                    Style::default().fg(theme.info_fg),
+                )));
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled(
+                "Comments",
+                Style::default().fg(theme.legend_fg),
+            )));
+            if record.comments.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "No comments",
+                    Style::default().fg(theme.legend_fg),
+                )));
+            }
+            lines.extend(record.comments.iter().map(|comment| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("[{}] ", format_clock(comment.elapsed_secs)),
+                        Style::default().fg(theme.timer_fg),
+                    ),
+                    Span::styled(comment.text.clone(), Style::default().fg(theme.text_fg)),
+                ])
+            }));
+            Text::from(lines)
+        }
+        None => Text::from(Span::styled(
+            "No games played yet",
+            Style::default().fg(theme.legend_fg),
+        )),
+    };
+    let details_paragraph = Paragraph::new(details)
+        .block(Block::default().borders(Borders::ALL).title("Details"))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(details_paragraph, columns[1]);
 
-            let cell_content_str = match cell_value {
-                Some(n) => format!(" {} ", n),
-                None => "   ".to_string(),
+    let instructions =
+        Paragraph::new("Up/Down (k/j): Select, p: Practice remapped copy, Esc/b: Back, q: Quit.")
+            .style(Style::default().fg(theme.instructions_fg))
+            .alignment(Alignment::Center);
+    frame.render_widget(instructions, chunks[2]);
+}
+
+/// The solver's completion of an abandoned board: clues, the player's kept entries,
+/// the cells the solver filled, and in the invalid color the ones it corrected
+fn solver_finish_lines(
+    theme: &Theme,
+    sudoku: &SudokuGrid,
+    finish: &[[u8; SIZE]; SIZE],
+) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(Span::styled(
+        "Solver's finish",
+        Style::default().fg(theme.legend_fg),
+    ))];
+    for (r, row) in finish.iter().enumerate() {
+        if r > 0 && r % BOX_SIZE == 0 {
+            lines.push(Line::from(Span::styled(
+                "------+-------+------",
+                Style::default().fg(theme.border_fg),
+            )));
+        }
+        let mut spans = Vec::new();
+        for (c, &value) in row.iter().enumerate() {
+            if c > 0 && c % BOX_SIZE == 0 {
+                spans.push(Span::styled("| ", Style::default().fg(theme.border_fg)));
+            }
+            let fg = if sudoku.is_fixed(r, c) {
+                theme.fixed_fg
+            } else if sudoku.is_wrong(r, c) {
+                theme.invalid_fg
+            } else if sudoku.get_cell(r, c, false).is_some() {
+                theme.user_fg
+            } else {
+                theme.legend_fg
             };
-            line_spans.push(Span::styled(cell_content_str, style));
+            spans.push(Span::styled(format!("{value} "), Style::default().fg(fg)));
         }
-        lines.push(Line::from(line_spans));
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// Draws the sidebar next to the grid: how complete each 3x3 box is
+fn draw_sidebar(frame: &mut Frame, app: &App, sudoku: &SudokuGrid, area: Rect) {
+    let theme = &app.theme;
+    let counts = sudoku.box_fill_counts();
+    let suggestion = sudoku.least_complete_box();
+
+    let mut lines = vec![Line::from(Span::styled(
+        "Boxes filled",
+        Style::default().fg(theme.legend_fg),
+    ))];
+    for screen_band in 0..BOX_SIZE {
+        let mut spans = Vec::new();
+        for screen_stack in 0..BOX_SIZE {
+            let (band, stack) = app.view.to_board((screen_band, screen_stack), BOX_SIZE);
+            let b = band * BOX_SIZE + stack;
+            let (label, mut style) = if counts[b] == SIZE {
+                (" ✓ ".to_string(), Style::default().fg(theme.success_fg))
+            } else {
+                (
+                    format!(" {} ", counts[b]),
+                    Style::default().fg(theme.text_fg),
+                )
+            };
+            if suggestion == Some(b) {
+                style = style.bg(theme.hint_bg).add_modifier(Modifier::BOLD);
+            }
+            spans.push(Span::styled(label, style));
+        }
+        lines.push(Line::from(spans));
+    }
+    if let Some(b) = suggestion {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("Least filled: box {}", b + 1),
+            Style::default().fg(theme.info_fg),
+        )));
+    }
+    if app.state == AppState::Running {
+        lines.push(Line::from(""));
+        lines.extend(hint_lines(app));
+    }
+    lines.push(Line::from(""));
+    // The think-time breakdown only fits once the box suggestion is gone
+    lines.extend(metrics_lines(
+        theme,
+        &app.metrics(),
+        app.state == AppState::Solved,
+    ));
+
+    let sidebar = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title("Progress"));
+    frame.render_widget(sidebar, area);
+}
+
+/// Draws the selected cell enlarged for low-vision play: its digit in big
+/// glyphs, or for an empty cell the digits its row, column and box still allow
+fn draw_focus_panel(frame: &mut Frame, app: &App, sudoku: &SudokuGrid, area: Rect) {
+    let theme = &app.theme;
+    let (r, c) = app.cursor_pos;
+    let hidden = app.digit_hidden(sudoku, r, c);
+    let value = sudoku.get_cell(r, c, app.show_solution).filter(|_| !hidden);
+    let bold = Modifier::BOLD;
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("Row {}  Column {}", r + 1, c + 1),
+            Style::default().fg(theme.legend_fg),
+        )),
+        Line::from(""),
+    ];
+    let (fg, caption) = match value {
+        None if hidden => (theme.legend_fg, "Hidden entry"),
+        Some(_) if sudoku.is_fixed(r, c) || app.show_solution => (theme.fixed_fg, "Clue"),
+        Some(_) if app.entry_flagged(sudoku, r, c) => match app.game_validation {
+            Validation::Conflicts => (theme.invalid_fg, "Clashes"),
+            Validation::Solution => (theme.invalid_fg, "Wrong"),
+        },
+        Some(_) => (theme.user_fg, "Your entry"),
+        None if !sudoku.notes(r, c).is_empty() => (theme.text_fg, "Your notes"),
+        None => (theme.text_fg, "Empty"),
+    };
+    let notes = sudoku.notes(r, c);
+    match value {
+        Some(v) => lines.extend(BIG_DIGITS[v as usize - 1].iter().map(|row| {
+            let pixels: String = row
+                .chars()
+                .map(|px| if px == '#' { "██" } else { "  " })
+                .collect();
+            Line::from(Span::styled(pixels, Style::default().fg(fg)))
+        })),
+        // Candidates would be an assist, so tournaments leave the cell blank,
+        // and they would give a hidden entry away
+        None if app.tournament.is_some() || hidden => lines.extend((0..5).map(|_| Line::from(""))),
+        None => {
+            for band in 0..BOX_SIZE {
+                if band > 0 {
+                    lines.push(Line::from(""));
+                }
+                let spans: Vec<Span> = (1..=BOX_SIZE as u8)
+                    .map(|i| {
+                        let digit = band as u8 * BOX_SIZE as u8 + i;
+                        let shown = if notes.is_empty() {
+                            sudoku.candidates(r, c) & 1 << digit != 0
+                        } else {
+                            notes.contains(&digit)
+                        };
+                        if shown {
+                            Span::styled(
+                                format!(" {digit} "),
+                                Style::default().fg(theme.user_fg).add_modifier(bold),
+                            )
+                        } else {
+                            Span::styled(" · ", Style::default().fg(theme.legend_fg))
+                        }
+                    })
+                    .collect();
+                lines.push(Line::from(spans));
+            }
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        caption,
+        Style::default().fg(fg).add_modifier(bold),
+    )));
+
+    let panel = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title("Focus"));
+    frame.render_widget(panel, area);
+}
+
+/// Draws how many moves each family of techniques has on the board right
+/// now, without saying where they are. Counts for an earlier board are
+/// dimmed until the recount after the latest change.
+fn draw_radar(frame: &mut Frame, app: &App, sudoku: &SudokuGrid, area: Rect) {
+    let theme = &app.theme;
+    let board = sudoku.board();
+    let current = app.radar.is_some_and(|(counted, _)| counted == board);
+    let counts = app.radar.map(|(_, counts)| counts).unwrap_or_default();
+    let fg = if current {
+        theme.text_fg
+    } else {
+        theme.legend_fg
+    };
+    let mut lines: Vec<Line> = [
+        ("Singles", counts.singles),
+        ("Locked cand.", counts.locked_candidates),
+        ("Pairs", counts.pairs),
+    ]
+    .into_iter()
+    .map(|(name, count)| {
+        let style = if count > 0 && current {
+            Style::default()
+                .fg(theme.success_fg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(fg)
+        };
+        Line::from(vec![
+            Span::styled(format!("{name:<13}"), Style::default().fg(fg)),
+            Span::styled(format!("{count:>3}"), style),
+        ])
+    })
+    .collect();
+    lines.push(Line::from(""));
+    let note = if app.radar.is_none() || !current {
+        "Counting..."
+    } else if counts == solver::MoveCounts::default() {
+        "Harder moves needed"
+    } else {
+        "Moves available now"
+    };
+    lines.push(Line::from(Span::styled(
+        note,
+        Style::default().fg(theme.instructions_fg),
+    )));
+
+    let panel = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title("Radar"));
+    frame.render_widget(panel, area);
+}
+
+/// Hint usage, cooldown and the cost of the next hint.
+fn hint_lines(app: &App) -> Vec<Line<'static>> {
+    let theme = &app.theme;
+    let value = Style::default().fg(theme.text_fg);
+    let next = match app.hint_cooldown_left() {
+        Some(left) => Span::styled(
+            format!("Next hint in {left}s"),
+            Style::default().fg(theme.invalid_fg),
+        ),
+        None => Span::styled(
+            match app.next_hint_cost() {
+                (low, high) if low == high => format!("Next hint -{low} pts"),
+                (low, high) => format!("Next -{low}..{high} pts"),
+            },
+            Style::default().fg(theme.success_fg),
+        ),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled("Hints", Style::default().fg(theme.legend_fg))),
+        Line::from(Span::styled(
+            format!("{} used, -{} pts", app.hints_used, app.penalty),
+            value,
+        )),
+    ];
+    let revealed = app.sudoku.as_ref().map_or(0, SudokuGrid::revealed_count);
+    if revealed > 0 {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{revealed} cell{} revealed",
+                if revealed == 1 { "" } else { "s" }
+            ),
+            value,
+        )));
+    }
+    lines.push(Line::from(next));
+    lines
+}
+
+/// Share of removed pencil marks the solver agreed with, followed by the
+/// removals it didn't, which are the ones worth studying: either the answer
+/// itself, or a digit no technique could rule out yet
+fn elimination_lines(
+    theme: &Theme,
+    log: &[Elimination],
+    sudoku: &SudokuGrid,
+) -> Vec<Line<'static>> {
+    let (justified, total) = metrics::elimination_accuracy(log);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Eliminations",
+            Style::default().fg(theme.legend_fg),
+        )),
+        Line::from(Span::styled(
+            format!(
+                "{justified}/{total} justified ({}%)",
+                justified * 100 / total.max(1)
+            ),
+            Style::default().fg(theme.text_fg),
+        )),
+    ];
+    lines.extend(log.iter().filter(|e| !e.justified()).map(|e| {
+        let reason = if sudoku.solution_value(e.row, e.col) == e.digit {
+            "the answer"
+        } else {
+            "not provable yet"
+        };
+        Line::from(vec![
+            Span::styled(
+                format!("[{}] ", format_clock(e.elapsed_ms / 1000)),
+                Style::default().fg(theme.timer_fg),
+            ),
+            Span::styled(
+                format!("R{}C{} -{}: {reason}", e.row + 1, e.col + 1, e.digit),
+                Style::default().fg(theme.invalid_fg),
+            ),
+        ])
+    }));
+    lines
+}
+
+/// Pace statistics, optionally followed by a think-time histogram.
+fn metrics_lines(theme: &Theme, metrics: &Metrics, histogram: bool) -> Vec<Line<'static>> {
+    let label = Style::default().fg(theme.legend_fg);
+    let value = Style::default().fg(theme.text_fg);
+    let mut lines = vec![
+        Line::from(Span::styled("Pace", label)),
+        Line::from(Span::styled(
+            format!("{:.1} cells/min", metrics.cells_per_minute),
+            value,
+        )),
+        Line::from(Span::styled(
+            format!("{:.1}s per entry", metrics.avg_secs_per_entry),
+            value,
+        )),
+        Line::from(Span::styled(
+            format!("Longest think {}", format_clock(metrics.longest_think_secs)),
+            value,
+        )),
+    ];
+    if histogram {
+        const BAR_WIDTH: usize = 8;
+        let max = metrics.histogram.iter().copied().max().unwrap_or(0).max(1);
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Think time", label)));
+        for (i, &count) in metrics.histogram.iter().enumerate() {
+            let bar = "█".repeat((count * BAR_WIDTH).div_ceil(max));
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<6} ", Metrics::bucket_label(i)), label),
+                Span::styled(
+                    format!("{bar:<BAR_WIDTH$}"),
+                    Style::default().fg(theme.info_fg),
+                ),
+                Span::styled(format!(" {count:>3}"), value),
+            ]));
+        }
+    }
+    lines
+}
+
+/// Builds the row telling how many of each digit are still to be placed.
+/// Finished digits are greyed out, and a digit placed too often is marked as
+/// a conflict.
+fn build_digit_tracker(theme: &Theme, sudoku: &SudokuGrid) -> Line<'static> {
+    let label = Style::default().fg(theme.legend_fg);
+    let done = label.add_modifier(Modifier::DIM);
+    let digit = Style::default()
+        .fg(theme.fixed_fg)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = vec![Span::styled("Left", label)];
+    for (i, &count) in sudoku.digit_counts().iter().enumerate() {
+        let (digit_style, text, style) = match count.cmp(&SIZE) {
+            Ordering::Less => (
+                digit,
+                (SIZE - count).to_string(),
+                Style::default().fg(theme.text_fg),
+            ),
+            Ordering::Equal => (done, "✓".to_string(), done),
+            Ordering::Greater => (
+                digit,
+                format!("+{}", count - SIZE),
+                Style::default().fg(theme.invalid_fg),
+            ),
+        };
+        spans.push(Span::styled(format!("  {}:", i + 1), digit_style));
+        spans.push(Span::styled(text, style));
+    }
+    Line::from(spans)
+}
+
+/// Builds the legend explaining the grid's colors and markers, drawn with
+/// the active theme and showing only what the current game can display.
+fn build_color_key(app: &App, variant: Variant) -> Line<'static> {
+    let theme = &app.theme;
+    let label = Style::default().fg(theme.legend_fg);
+    // The colorblind mode's modifiers and marks, shown the way the grid has them
+    let (given, invalid) = if app.colorblind {
+        (Modifier::BOLD | Modifier::UNDERLINED, Modifier::REVERSED)
+    } else {
+        (Modifier::BOLD, Modifier::empty())
+    };
+    let mark = |marked: &'static str, plain: &'static str| {
+        if app.colorblind { marked } else { plain }
+    };
+    let mut entries = vec![
+        (" 5 ", Style::default().fg(theme.user_fg), "your digits"),
+        (
+            " 5 ",
+            Style::default().fg(theme.fixed_fg).add_modifier(given),
+            "givens",
+        ),
+        (
+            mark(" 5!", " 5 "),
+            Style::default().fg(theme.invalid_fg).add_modifier(invalid),
+            match app.game_validation {
+                Validation::Conflicts => "conflict",
+                Validation::Solution => "wrong digit",
+            },
+        ),
+        ("   ", Style::default().bg(theme.cursor_bg), "cursor"),
+    ];
+    if app.live_check && app.game_validation == Validation::Conflicts {
+        entries.push((
+            mark(" 5*", "   "),
+            Style::default().bg(theme.conflict_bg),
+            "clashes with cursor",
+        ));
+    }
+    if app.show_candidates && app.tournament.is_none() {
+        entries.push((" ⠅ ", label, "candidates"));
+    }
+    if app.peer_highlight {
+        entries.push((
+            "   ",
+            Style::default().bg(theme.peer_bg),
+            "same row/col/box",
+        ));
+    }
+    if app.sudoku.as_ref().is_some_and(|s| s.revealed_count() > 0) {
+        entries.insert(2, (" 5 ", Style::default().fg(theme.info_fg), "revealed"));
+    }
+    match variant {
+        Variant::Diagonal => {
+            entries.push(("   ", Style::default().bg(theme.variant_bg), "diagonal"))
+        }
+        Variant::Windoku => entries.push(("   ", Style::default().bg(theme.variant_bg), "window")),
+        Variant::Classic | Variant::AntiKnight => {}
+    }
+    if let Some(hint) = &app.hint {
+        entries.push(("   ", Style::default().bg(theme.hint_bg), "hint region"));
+        if hint.tier >= 3 {
+            entries.push(("   ", Style::default().bg(theme.hint_pattern_bg), "pattern"));
+        }
+    }
+    if app.links.iter().any(|link| link.strong) || app.link_from.is_some() {
+        entries.push((
+            "   ",
+            Style::default().bg(theme.strong_link_bg),
+            "strong link",
+        ));
+    }
+    if app.links.iter().any(|link| !link.strong) {
+        entries.push(("   ", Style::default().bg(theme.weak_link_bg), "weak link"));
+    }
+    if app.show_diff || app.checked.is_some() {
+        entries.push((
+            mark(" 5?", "   "),
+            Style::default().bg(theme.diff_wrong_bg),
+            "wrong",
+        ));
+    }
+    if app.show_diff {
+        entries.push((" · ", label, "empty"));
+    }
+    if app.comparison.is_some() {
+        entries.push((" 5 ", Style::default().fg(theme.success_fg), "same in both"));
+        entries.push((" 5 ", Style::default().fg(theme.theirs_fg), "only theirs"));
+        entries.push(("   ", Style::default().bg(theme.disagree_bg), "disagree"));
+    }
+
+    let mut spans = Vec::new();
+    for (i, (marker, style, text)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw("  "));
+        }
+        spans.push(Span::styled(marker, style));
+        spans.push(Span::styled(format!(" {text}"), label));
+    }
+    Line::from(spans)
+}
+
+/// Brings the grid's text up to date with the board, cell by cell.
+fn update_grid(grid: &mut GridText, app: &App, sudoku: &SudokuGrid, cell_rows: u16) {
+    let border_style = Style::default().fg(app.theme.border_fg);
+    grid.reshape(cell_rows, border_style, app.note_colors);
+    let marks = GridMarks::new(app, sudoku);
+    for screen_r in 0..SIZE {
+        for screen_c in 0..SIZE {
+            let (r, c) = app.view.to_board((screen_r, screen_c), SIZE);
+            grid.set((screen_r, screen_c), cell_look(app, sudoku, &marks, r, c));
+        }
+    }
+}
+
+/// Overlays worked out once per frame rather than once per cell.
+struct GridMarks {
+    show_diff: bool,
+    hint_region: Option<Vec<(usize, usize)>>,
+    hint_cell: Option<(usize, usize)>,
+    /// Cells the fully revealed deduction rests on
+    hint_pattern: Vec<(usize, usize)>,
+    /// Cells the entry under the cursor clashes with
+    conflicts: Vec<(usize, usize)>,
+    /// Wrong entries found by a progress check that is still on screen
+    checked: Vec<(usize, usize)>,
+    /// Ends of the chain links, with the start of one being drawn as strong
+    strong_links: Vec<(usize, usize)>,
+    weak_links: Vec<(usize, usize)>,
+    /// Empty cells show their candidates rather than the pencil marks
+    candidates: bool,
+}
+
+impl GridMarks {
+    fn new(app: &App, sudoku: &SudokuGrid) -> Self {
+        let (r, c) = app.cursor_pos;
+        let checked = app.live_check
+            && !app.zen
+            && app.game_validation == Validation::Conflicts
+            && !app.show_solution
+            && sudoku.clashes(r, c);
+        GridMarks {
+            // A given-up game marks the wrong entries over its solution
+            show_diff: (app.show_diff && !app.show_solution)
+                || app.failure == Some(Outcome::GaveUp),
+            hint_region: app.hint.as_ref().map(|h| h.step.region.cells()),
+            hint_cell: app
+                .hint
+                .as_ref()
+                .filter(|h| h.tier >= 3)
+                .and_then(|h| h.step.placement)
+                .map(|(r, c, _)| (r, c)),
+            hint_pattern: app
+                .hint
+                .iter()
+                .filter(|h| h.tier >= 3)
+                .flat_map(|h| h.lead_up.iter().chain([&h.step]))
+                .flat_map(|step| step.pattern.iter().copied())
+                .collect(),
+            conflicts: match sudoku.get_cell(r, c, false) {
+                Some(value) if checked => sudoku.conflicting_peers(r, c, value),
+                _ => Vec::new(),
+            },
+            checked: app
+                .checked
+                .as_ref()
+                .filter(|_| !app.show_solution)
+                .map_or_else(Vec::new, |(_, cells)| cells.clone()),
+            strong_links: link_ends(app, true).chain(app.link_from).collect(),
+            weak_links: link_ends(app, false).collect(),
+            candidates: app.show_candidates && app.tournament.is_none(),
+        }
+    }
+}
+
+/// Both digits of the cursor cell when it is one a compared save fills
+/// differently, such as `r3c5: mine 4, theirs 7 in friend.json`.
+fn cursor_compare_line(app: &App) -> Option<Line<'static>> {
+    let comparison = app.comparison.as_ref()?;
+    let sudoku = app.sudoku.as_ref()?;
+    let (r, c) = app.cursor_pos;
+    if compare::side(sudoku, &comparison.theirs, r, c) != Side::Disagree {
+        return None;
+    }
+    let digit = |grid: &SudokuGrid| grid.get_cell(r, c, false).unwrap_or(0);
+    Some(Line::from(Span::styled(
+        format!(
+            "r{}c{}: mine {}, theirs {} in {}",
+            r + 1,
+            c + 1,
+            digit(sudoku),
+            digit(&comparison.theirs),
+            comparison.label
+        ),
+        Style::default().fg(app.theme.info_fg),
+    )))
+}
+
+/// The links of the cursor cell, such as `Links: 5 strong to r1c7`, the
+/// other end of each standing in for a connector the grid can't draw.
+fn cursor_links_line(app: &App) -> Option<Line<'static>> {
+    let ends: Vec<String> = app
+        .links
+        .iter()
+        .filter_map(|link| {
+            let (r, c) = link.other_end(app.cursor_pos)?;
+            let kind = if link.strong { "strong" } else { "weak" };
+            Some(format!("{} {kind} to r{}c{}", link.digit, r + 1, c + 1))
+        })
+        .collect();
+    (!ends.is_empty()).then(|| {
+        Line::from(Span::styled(
+            format!("Links: {}", ends.join(", ")),
+            Style::default().fg(app.theme.info_fg),
+        ))
+    })
+}
+
+/// Both ends of every strong or every weak chain link.
+fn link_ends(app: &App, strong: bool) -> impl Iterator<Item = (usize, usize)> + '_ {
+    app.links
+        .iter()
+        .filter(move |link| link.strong == strong)
+        .flat_map(|link| [link.from, link.to])
+}
+
+/// How the cell at board position (r, c) is drawn this frame.
+fn cell_look(app: &App, sudoku: &SudokuGrid, marks: &GridMarks, r: usize, c: usize) -> CellLook {
+    let theme = &app.theme;
+    let animation_bg = app
+        .animation
+        .as_ref()
+        .and_then(|animation| animation.cell_bg((r, c), theme));
+
+    let cell_value = sudoku.get_cell(r, c, app.show_solution);
+    let is_cursor = (r, c) == app.cursor_pos;
+    let is_fixed = sudoku.is_fixed(r, c) && !app.show_solution;
+
+    // Clashes are worked out as the board changes, not on every frame
+    let is_valid = app.show_solution || !app.entry_flagged(sudoku, r, c);
+    let is_conflict = marks.conflicts.contains(&(r, c));
+    // A checked entry stops being marked once it is corrected
+    let is_wrong = (marks.show_diff || marks.checked.contains(&(r, c))) && sudoku.is_wrong(r, c);
+    let is_hint_cell = marks.hint_cell == Some((r, c));
+    let in_hint_pattern = marks.hint_pattern.contains(&(r, c));
+    let in_hint_region = marks
+        .hint_region
+        .as_ref()
+        .is_some_and(|cells| cells.contains(&(r, c)));
+    let is_peer = !is_cursor && shares_unit((r, c), app.cursor_pos);
+    let in_variant = in_variant_region(sudoku.variant(), r, c);
+    let on_strong_link = marks.strong_links.contains(&(r, c));
+    let on_weak_link = marks.weak_links.contains(&(r, c));
+    let compared = app
+        .comparison
+        .as_ref()
+        .filter(|_| !app.show_solution)
+        .map(|comparison| {
+            let side = compare::side(sudoku, &comparison.theirs, r, c);
+            (side, comparison.theirs.get_cell(r, c, false))
+        });
+    let disagrees = compared.is_some_and(|(side, _)| side == Side::Disagree);
+
+    let mut style = Style::default();
+    if let Some(bg) = animation_bg {
+        style = style.bg(bg);
+    } else if is_cursor {
+        style = style.bg(theme.cursor_bg);
+    } else if is_conflict {
+        style = style.bg(theme.conflict_bg);
+    } else if is_wrong {
+        style = style.bg(theme.diff_wrong_bg);
+    } else if disagrees {
+        style = style.bg(theme.disagree_bg);
+    } else if is_hint_cell {
+        style = style.bg(theme.hint_cell_bg);
+    } else if in_hint_pattern {
+        style = style.bg(theme.hint_pattern_bg);
+    } else if in_hint_region {
+        style = style.bg(theme.hint_bg);
+    } else if on_strong_link {
+        style = style.bg(theme.strong_link_bg);
+    } else if on_weak_link {
+        style = style.bg(theme.weak_link_bg);
+    } else if app.peer_highlight && is_peer {
+        style = style.bg(theme.peer_bg);
+    } else if in_variant {
+        style = style.bg(theme.variant_bg);
+    }
+    if !is_valid {
+        style = style.fg(theme.invalid_fg);
+    } else if is_fixed {
+        style = style.fg(theme.fixed_fg).add_modifier(Modifier::BOLD);
+    } else if sudoku.is_revealed(r, c) && !app.show_solution {
+        style = style.fg(theme.info_fg);
+    } else if cell_value.is_some() && !app.show_solution {
+        style = style.fg(theme.user_fg);
+    }
+
+    // Without telling colors apart, givens are underlined, invalid entries
+    // reversed, and flagged cells carry a mark after the digit
+    let mut mark = ' ';
+    if app.colorblind {
+        if !is_valid {
+            style = style.add_modifier(Modifier::REVERSED);
+            mark = '!';
+        } else if is_wrong {
+            mark = '?';
+        } else if is_conflict {
+            mark = '*';
+        }
+        if is_fixed {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+    }
+
+    let notes = sudoku.cell_state(r, c).notes;
+    let mut content = match cell_value {
+        Some(_) if app.digit_hidden(sudoku, r, c) => {
+            style = style.fg(theme.legend_fg);
+            CellContent::Hidden
+        }
+        Some(n) => CellContent::Digit(n, mark),
+        None if marks.show_diff => {
+            style = style.fg(theme.legend_fg);
+            CellContent::Dot
+        }
+        None if marks.candidates => {
+            style = style.fg(theme.legend_fg);
+            CellContent::Candidates(sudoku.candidates(r, c))
+        }
+        None if notes != 0 => {
+            style = style.fg(theme.legend_fg);
+            CellContent::Notes(notes)
+        }
+        None => CellContent::Blank,
+    };
+    // Entries both saves agree on, and the digits only the other one has
+    match compared {
+        Some((Side::Both, _)) if is_valid => style = style.fg(theme.success_fg),
+        Some((Side::Theirs, Some(n))) => {
+            style = style.fg(theme.theirs_fg);
+            content = CellContent::Digit(n, mark);
+        }
+        _ => {}
+    }
+
+    if !app.style_rules.is_empty() {
+        let mut flags = CellFlags::default();
+        flags.set(CellFlag::Cursor, is_cursor);
+        flags.set(CellFlag::Peer, is_peer);
+        flags.set(CellFlag::Given, is_fixed);
+        flags.set(
+            CellFlag::Entry,
+            cell_value.is_some() && !is_fixed && !sudoku.is_revealed(r, c),
+        );
+        flags.set(
+            CellFlag::Revealed,
+            sudoku.is_revealed(r, c) && !app.show_solution,
+        );
+        flags.set(CellFlag::Empty, cell_value.is_none());
+        flags.set(CellFlag::Notes, matches!(content, CellContent::Notes(_)));
+        flags.set(CellFlag::Invalid, !is_valid);
+        flags.set(CellFlag::Conflict, is_conflict);
+        flags.set(CellFlag::Wrong, is_wrong);
+        flags.set(
+            CellFlag::Hint,
+            is_hint_cell || in_hint_pattern || in_hint_region,
+        );
+        flags.set(CellFlag::Variant, in_variant);
+        flags.set(CellFlag::Link, on_strong_link || on_weak_link);
+        for rule in &app.style_rules {
+            style = rule.apply(flags, style);
+        }
+        // Animations stay visible over the rules
+        if let Some(bg) = animation_bg {
+            style = style.bg(bg);
+        }
+    }
+    CellLook { style, content }
+}
+
+/// Whether the cell lies on a region the variant tints (diagonals, windows).
+fn in_variant_region(variant: Variant, r: usize, c: usize) -> bool {
+    match variant {
+        Variant::Diagonal => on_main_diagonal(r, c) || on_anti_diagonal(r, c),
+        Variant::Windoku => window_index(r, c).is_some(),
+        Variant::Classic | Variant::AntiKnight => false,
     }
-    Text::from(lines)
 }
 
 /// Helper function to calculate a centered Rect
@@ -0,0 +1,138 @@
+use crate::config::UndoSteps;
+use crate::sudoku::CellState;
+
+/// One undoable change to a cell: an entry, a clear or a pencil mark change.
+#[derive(Clone, Copy, Debug)]
+pub struct Edit {
+    pub row: usize,
+    pub col: usize,
+    pub before: CellState,
+    pub after: CellState,
+    /// Undone and redone together with the edit before it
+    joined: bool,
+}
+
+impl Edit {
+    pub fn new(row: usize, col: usize, before: CellState, after: CellState) -> Self {
+        Edit {
+            row,
+            col,
+            before,
+            after,
+            joined: false,
+        }
+    }
+}
+
+/// The undo history of a game, grouped into the steps undo and redo take.
+/// How edits are grouped follows the configured `UndoSteps`: features that
+/// change several cells at once wrap their edits in `begin` and `commit`, and
+/// the history decides whether the group is one step or several.
+#[derive(Debug)]
+pub struct UndoHistory {
+    pub steps: UndoSteps,
+    /// Oldest first
+    edits: Vec<Edit>,
+    /// Edits before this point are applied; the rest can be redone
+    applied: usize,
+    /// Open transactions, counted so a group action can run inside another
+    depth: usize,
+    /// Whether the open transaction has recorded an edit yet
+    group_started: bool,
+    /// Whether the next edit may still join the latest step by cell; not
+    /// after an undo or redo
+    cell_open: bool,
+}
+
+impl UndoHistory {
+    pub fn new(steps: UndoSteps) -> Self {
+        UndoHistory {
+            steps,
+            edits: Vec::new(),
+            applied: 0,
+            depth: 0,
+            group_started: false,
+            cell_open: false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = UndoHistory::new(self.steps);
+    }
+
+    /// Opens a transaction: the edits recorded until the matching `commit`
+    /// come from one action, such as filling every naked single.
+    pub fn begin(&mut self) {
+        if self.depth == 0 {
+            self.group_started = false;
+        }
+        self.depth += 1;
+    }
+
+    pub fn commit(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Adds an edit, dropping anything that could have been redone. `follows`
+    /// marks an edit made by the same key press as the one before it, such as
+    /// a pencil mark erased by placing a digit, which always shares its step.
+    pub fn record(&mut self, mut edit: Edit, follows: bool) {
+        self.edits.truncate(self.applied);
+        let grouped = self.depth > 0 && self.group_started;
+        edit.joined = follows
+            || match self.steps {
+                UndoSteps::Keystroke => false,
+                UndoSteps::Cell => self.cell_open && self.lead_cell() == Some((edit.row, edit.col)),
+                UndoSteps::Action => grouped,
+            };
+        if self.depth > 0 {
+            self.group_started = true;
+        }
+        self.cell_open = true;
+        self.edits.push(edit);
+        self.applied = self.edits.len();
+    }
+
+    /// The cell of the edit that began the latest step.
+    fn lead_cell(&self) -> Option<(usize, usize)> {
+        self.edits
+            .iter()
+            .rev()
+            .find(|edit| !edit.joined)
+            .map(|edit| (edit.row, edit.col))
+    }
+
+    /// Takes back the latest step, returning its edits latest first to be
+    /// reversed, or None if there is nothing to undo.
+    pub fn undo(&mut self) -> Option<Vec<Edit>> {
+        if self.applied == 0 {
+            return None;
+        }
+        self.cell_open = false;
+        let mut step = Vec::new();
+        // Joined edits come after the one they belong to, which is undone last
+        while let Some(index) = self.applied.checked_sub(1) {
+            let edit = self.edits[index];
+            self.applied = index;
+            step.push(edit);
+            if !edit.joined {
+                break;
+            }
+        }
+        Some(step)
+    }
+
+    /// Applies again the latest step taken back, returning its edits oldest
+    /// first, or None if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<Vec<Edit>> {
+        let &first = self.edits.get(self.applied)?;
+        self.cell_open = false;
+        self.applied += 1;
+        let mut step = vec![first];
+        while let Some(&joined) = self.edits.get(self.applied).filter(|e| e.joined) {
+            self.applied += 1;
+            step.push(joined);
+        }
+        Some(step)
+    }
+}
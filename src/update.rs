@@ -0,0 +1,109 @@
+use std::fmt;
+#[cfg(feature = "update-check")]
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+
+/// Latest published release of the app, as listed by GitHub.
+#[cfg(feature = "update-check")]
+const RELEASES_URL: &str = "https://api.github.com/repos/chunghha/tui_sudoku/releases/latest";
+
+/// The part of the releases API reply that is used.
+#[cfg(feature = "update-check")]
+#[derive(serde::Deserialize)]
+struct LatestRelease {
+    tag_name: String,
+    html_url: String,
+}
+
+/// A release newer than the running version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Release {
+    pub version: String,
+    pub url: String,
+}
+
+#[derive(Debug)]
+pub enum UpdateError {
+    /// Built without the `update-check` feature
+    #[cfg(not(feature = "update-check"))]
+    Disabled,
+    #[cfg(feature = "update-check")]
+    Failed(String),
+    #[cfg(feature = "update-check")]
+    BadResponse,
+}
+
+impl fmt::Display for UpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(not(feature = "update-check"))]
+            UpdateError::Disabled => write!(f, "this build leaves them out"),
+            #[cfg(feature = "update-check")]
+            UpdateError::Failed(reason) => write!(f, "{reason}"),
+            #[cfg(feature = "update-check")]
+            UpdateError::BadResponse => write!(f, "unexpected reply from GitHub"),
+        }
+    }
+}
+
+/// Looks for a newer release on a background thread, so a slow network never
+/// holds up the app. Nothing is downloaded or installed; the thread only
+/// tells whether there is something newer than this build.
+pub fn check_in_background() -> JoinHandle<Result<Option<Release>, UpdateError>> {
+    thread::spawn(|| {
+        let (tag, url) = latest_release()?;
+        Ok(is_newer(&tag, env!("CARGO_PKG_VERSION")).then(|| Release {
+            version: tag.trim_start_matches('v').to_string(),
+            url,
+        }))
+    })
+}
+
+/// Tag and page of the latest release, fetched with `curl` under a strict timeout.
+#[cfg(feature = "update-check")]
+fn latest_release() -> Result<(String, String), UpdateError> {
+    let output = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location"])
+        .args(["--connect-timeout", "2", "--max-time", "4"])
+        .args(["--header", "Accept: application/vnd.github+json"])
+        .args([
+            "--user-agent",
+            concat!("tui_sudoku/", env!("CARGO_PKG_VERSION")),
+        ])
+        .arg(RELEASES_URL)
+        .output()
+        .map_err(|e| UpdateError::Failed(e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .next()
+            .map_or_else(|| output.status.to_string(), str::to_string);
+        return Err(UpdateError::Failed(reason));
+    }
+    let release: LatestRelease =
+        serde_json::from_slice(&output.stdout).map_err(|_| UpdateError::BadResponse)?;
+    Ok((release.tag_name, release.html_url))
+}
+
+#[cfg(not(feature = "update-check"))]
+fn latest_release() -> Result<(String, String), UpdateError> {
+    Err(UpdateError::Disabled)
+}
+
+/// Whether version `tag` (e.g. `v0.2.0`) is newer than `current`. Only the
+/// numbers count, so `1.0.0-rc1` is not newer than `1.0.0`.
+pub fn is_newer(tag: &str, current: &str) -> bool {
+    let numbers = |version: &str| -> Vec<u64> {
+        version
+            .trim()
+            .trim_start_matches('v')
+            .split('.')
+            .map(|part| {
+                let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+                digits.parse().unwrap_or(0)
+            })
+            .collect()
+    };
+    numbers(tag) > numbers(current)
+}
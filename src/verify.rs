@@ -0,0 +1,307 @@
+use crate::daily;
+use crate::history::{GameRecord, Outcome};
+use crate::puzzle_id::PuzzleId;
+use crate::stats::clock;
+use crate::sudoku::{SIZE, count_solutions};
+use chrono::{DateTime, Local};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// Leeway between the clock's whole seconds and the moves' milliseconds.
+const CLOCK_SLACK_MS: u64 = 1000;
+
+/// Leeway for a player's clock running ahead of the checker's.
+const FUTURE_SLACK_SECS: i64 = 300;
+
+#[derive(Debug)]
+pub enum VerifyError {
+    Io(PathBuf, io::Error),
+    NotAResult(PathBuf),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            VerifyError::NotAResult(path) => {
+                write!(f, "{} holds no game from the history", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// One of the checks made on a result, with what it found.
+#[derive(Clone, Debug)]
+pub struct Check {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: String) -> Self {
+        Check {
+            name,
+            passed: true,
+            detail,
+        }
+    }
+
+    fn fail(name: &'static str, detail: String) -> Self {
+        Check {
+            name,
+            passed: false,
+            detail,
+        }
+    }
+}
+
+/// Reads a shared result: one game as kept in the history, or a whole
+/// history of them, such as `history.json` itself.
+pub fn load(path: &Path) -> Result<Vec<GameRecord>, VerifyError> {
+    let contents = fs::read_to_string(path).map_err(|e| VerifyError::Io(path.to_path_buf(), e))?;
+    let not_a_result = || VerifyError::NotAResult(path.to_path_buf());
+    let records = match serde_json::from_str::<GameRecord>(&contents) {
+        Ok(record) => vec![record],
+        Err(_) => serde_json::from_str(&contents).map_err(|_| not_a_result())?,
+    };
+    if records.is_empty() {
+        return Err(not_a_result());
+    }
+    Ok(records)
+}
+
+/// Checks a game against its puzzle: the clues, the moves played on them,
+/// the board they leave and the clock. `now` is the checker's unix time.
+pub fn verify(record: &GameRecord, now: i64) -> Vec<Check> {
+    vec![
+        check_puzzle(record),
+        check_moves(record),
+        check_board(record),
+        check_time(record, now),
+    ]
+}
+
+/// The clues must have one solution, the one stored with them, and a seeded
+/// or daily puzzle must be the one its seed or date makes.
+fn check_puzzle(record: &GameRecord) -> Check {
+    let sudoku = &record.sudoku;
+    let clues = sudoku.restarted().board();
+    let solution = match count_solutions(clues, sudoku.variant(), 2) {
+        (0, _) | (_, None) => return Check::fail("puzzle", "the clues have no solution".into()),
+        (1, Some(solution)) => solution,
+        _ => {
+            return Check::fail("puzzle", "the clues have more than one solution".into());
+        }
+    };
+    if (0..SIZE).any(|r| (0..SIZE).any(|c| sudoku.solution_value(r, c) != solution[r][c])) {
+        return Check::fail(
+            "puzzle",
+            "the solution stored with it is not the clues' solution".into(),
+        );
+    }
+    let mut detail = "the clues have one solution".to_string();
+    if sudoku.seed().is_some() {
+        let id = PuzzleId::of(sudoku);
+        if id.load().map(|built| built.board()) != Some(clues) {
+            return Check::fail(
+                "puzzle",
+                format!("the clues differ from those {id} makes with this version"),
+            );
+        }
+        detail.push_str(&format!(", as made by {id}"));
+    }
+    if let Some(date) = record.daily {
+        if daily::puzzle_for(date).board() != clues {
+            return Check::fail("puzzle", format!("not the daily puzzle of {date}"));
+        }
+        detail.push_str(&format!(", the daily puzzle of {date}"));
+    }
+    Check::pass("puzzle", detail)
+}
+
+/// Every move must be a digit or a clear on a cell that is not a clue, and
+/// the moves must be in the order they were played.
+fn check_moves(record: &GameRecord) -> Check {
+    let sudoku = &record.sudoku;
+    let mut last_ms = 0;
+    for (i, entry) in record.moves.iter().enumerate() {
+        let at = format!("move {} at {}", i + 1, clock(entry.elapsed_ms / 1000));
+        let problem = if entry.row >= SIZE || entry.col >= SIZE {
+            Some(format!(
+                "is off the board at r{}c{}",
+                entry.row + 1,
+                entry.col + 1
+            ))
+        } else if sudoku.is_fixed(entry.row, entry.col) {
+            Some(format!(
+                "changes the clue at r{}c{}",
+                entry.row + 1,
+                entry.col + 1
+            ))
+        } else if entry.digit > SIZE as u8 {
+            Some(format!("enters {}, which is no digit", entry.digit))
+        } else if entry.elapsed_ms < last_ms {
+            Some("is timed before the move ahead of it".to_string())
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            return Check::fail("moves", format!("{at} {problem}"));
+        }
+        last_ms = entry.elapsed_ms;
+    }
+    let count = record.moves.len();
+    Check::pass(
+        "moves",
+        format!(
+            "{count} move{} on open cells, in order",
+            if count == 1 { "" } else { "s" }
+        ),
+    )
+}
+
+/// Replaying the moves over the clues, with the revealed cells filled in,
+/// must leave the board the result shows, and a solved game must end on
+/// the solution.
+fn check_board(record: &GameRecord) -> Check {
+    let sudoku = &record.sudoku;
+    let mut board = sudoku.restarted().board();
+    for entry in &record.moves {
+        if entry.row < SIZE && entry.col < SIZE && !sudoku.is_fixed(entry.row, entry.col) {
+            board[entry.row][entry.col] = entry.digit;
+        }
+    }
+    for (r, row) in board.iter_mut().enumerate() {
+        for (c, digit) in row.iter_mut().enumerate() {
+            if sudoku.is_revealed(r, c) {
+                *digit = sudoku.solution_value(r, c);
+            }
+        }
+    }
+    let shown = sudoku.board();
+    for r in 0..SIZE {
+        for c in 0..SIZE {
+            if board[r][c] != shown[r][c] {
+                return Check::fail(
+                    "final grid",
+                    format!(
+                        "r{}c{} holds {}, the moves leave {}",
+                        r + 1,
+                        c + 1,
+                        shown[r][c],
+                        board[r][c]
+                    ),
+                );
+            }
+        }
+    }
+    let revealed = sudoku.revealed_count();
+    if revealed > record.hints_used as usize {
+        return Check::fail(
+            "final grid",
+            format!(
+                "{revealed} cells revealed with {} hints recorded",
+                record.hints_used
+            ),
+        );
+    }
+    if record.outcome == Outcome::Solved && !sudoku.is_solved() {
+        return Check::fail(
+            "final grid",
+            format!(
+                "marked solved with {} cells empty and {} wrong",
+                sudoku.empty_count(),
+                sudoku.wrong_entry_count()
+            ),
+        );
+    }
+    let ending = if sudoku.is_solved() {
+        "the solution"
+    } else {
+        "the board shown"
+    };
+    Check::pass("final grid", format!("the moves leave {ending}"))
+}
+
+/// The moves must all fall within the time on the clock, and the game must
+/// not end later than now.
+fn check_time(record: &GameRecord, now: i64) -> Check {
+    // Read from a file anyone could have written, so kept from overflowing
+    let clock_ms = record
+        .elapsed_secs
+        .saturating_mul(1000)
+        .saturating_add(CLOCK_SLACK_MS);
+    if let Some(last) = record.moves.last()
+        && last.elapsed_ms > clock_ms
+    {
+        return Check::fail(
+            "time",
+            format!(
+                "the last move is at {}, after the {} on the clock",
+                clock(last.elapsed_ms / 1000),
+                clock(record.elapsed_secs)
+            ),
+        );
+    }
+    if record.finished_at > now.saturating_add(FUTURE_SLACK_SECS) {
+        return Check::fail(
+            "time",
+            format!("ended at {}, still to come", timestamp(record.finished_at)),
+        );
+    }
+    if i64::try_from(record.elapsed_secs).map_or(true, |secs| secs > record.finished_at) {
+        return Check::fail(
+            "time",
+            "the clock ran longer than the game has existed".into(),
+        );
+    }
+    Check::pass(
+        "time",
+        format!(
+            "every move within the {} on the clock",
+            clock(record.elapsed_secs)
+        ),
+    )
+}
+
+/// A verdict on each game in turn, as printed by `verify --result`.
+pub fn text(records: &[GameRecord], now: i64) -> (String, bool) {
+    let mut out = String::new();
+    let mut all_passed = true;
+    for record in records {
+        let sudoku = &record.sudoku;
+        out.push_str(&format!(
+            "{} {} game of {} ({}, {})\n",
+            sudoku.difficulty().name(),
+            sudoku.variant().name(),
+            timestamp(record.finished_at),
+            record.outcome.name(),
+            clock(record.elapsed_secs)
+        ));
+        let checks = verify(record, now);
+        for check in &checks {
+            let mark = if check.passed { "ok  " } else { "FAIL" };
+            out.push_str(&format!("  {mark} {}: {}\n", check.name, check.detail));
+        }
+        let failed = checks.iter().filter(|check| !check.passed).count();
+        if failed == 0 {
+            out.push_str("  Verdict: verified\n");
+        } else {
+            all_passed = false;
+            out.push_str(&format!(
+                "  Verdict: not verified, {failed} check{} failed\n",
+                if failed == 1 { "" } else { "s" }
+            ));
+        }
+    }
+    (out, all_passed)
+}
+
+fn timestamp(ts: i64) -> String {
+    DateTime::from_timestamp(ts, 0)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "????-??-?? ??:??".to_string())
+}
@@ -0,0 +1,56 @@
+/// How the board is turned on screen. Drawing, cursor moves and clicks go
+/// through it, while the puzzle and every stored coordinate stay as they are.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GridView {
+    /// Clockwise quarter turns, 0 to 3
+    pub quarter_turns: u8,
+    /// Flip left and right after turning
+    pub mirrored: bool,
+}
+
+impl GridView {
+    pub fn rotate(&mut self) {
+        self.quarter_turns = (self.quarter_turns + 1) % 4;
+    }
+
+    pub fn toggle_mirror(&mut self) {
+        self.mirrored = !self.mirrored;
+    }
+
+    /// Where a cell of an `n`×`n` board is drawn.
+    pub fn to_screen(self, (mut r, mut c): (usize, usize), n: usize) -> (usize, usize) {
+        for _ in 0..self.quarter_turns {
+            (r, c) = (c, n - 1 - r);
+        }
+        if self.mirrored {
+            c = n - 1 - c;
+        }
+        (r, c)
+    }
+
+    /// The cell of an `n`×`n` board drawn at a screen position.
+    pub fn to_board(self, (mut r, mut c): (usize, usize), n: usize) -> (usize, usize) {
+        if self.mirrored {
+            c = n - 1 - c;
+        }
+        for _ in 0..self.quarter_turns {
+            (r, c) = (n - 1 - c, r);
+        }
+        (r, c)
+    }
+
+    /// Short description for the grid title, `None` when the board is upright.
+    pub fn label(self) -> Option<String> {
+        let turn = (self.quarter_turns > 0).then(|| format!("turned {}°", self.degrees()));
+        let mirror = self.mirrored.then(|| "mirrored".to_string());
+        match (turn, mirror) {
+            (None, None) => None,
+            (Some(turn), Some(mirror)) => Some(format!("{turn}, {mirror}")),
+            (turn, mirror) => turn.or(mirror),
+        }
+    }
+
+    pub fn degrees(self) -> u16 {
+        self.quarter_turns as u16 * 90
+    }
+}
@@ -0,0 +1,108 @@
+use crate::quiz::Quiz;
+use crate::stats::DifficultyStats;
+use crate::sudoku::{Difficulty, Variant};
+
+/// An entry of the win screen's menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WinChoice {
+    NewGame,
+    SameDifficulty,
+    Quiz,
+    ViewBoard,
+    Quit,
+}
+
+impl WinChoice {
+    /// In the order the screen lists them.
+    pub const ALL: [WinChoice; 5] = [
+        WinChoice::NewGame,
+        WinChoice::SameDifficulty,
+        WinChoice::Quiz,
+        WinChoice::ViewBoard,
+        WinChoice::Quit,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WinChoice::NewGame => "New game",
+            WinChoice::SameDifficulty => "Another of the same difficulty",
+            WinChoice::Quiz => "Technique quiz on your game",
+            WinChoice::ViewBoard => "Look at the board",
+            WinChoice::Quit => "Quit",
+        }
+    }
+}
+
+/// What the win screen shows about a solved game, next to the earlier
+/// solves of its difficulty.
+#[derive(Clone, Debug)]
+pub struct WinSummary {
+    pub difficulty: Difficulty,
+    pub variant: Variant,
+    pub elapsed_secs: u64,
+    pub score: u32,
+    pub mistakes: u32,
+    pub hints_used: u32,
+    /// Fastest and mean time of the earlier solves, None before the first
+    pub previous_best: Option<u64>,
+    pub previous_average: Option<u64>,
+    /// Solves of the difficulty, this one included
+    pub solves: usize,
+    /// A question on a position of the game, when one makes a quiz
+    pub quiz: Option<Quiz>,
+    /// Whether the quiz is shown in place of the figures
+    pub quizzing: bool,
+    pub selected: usize,
+}
+
+impl WinSummary {
+    /// Sums up the game against `earlier`, the stats of its difficulty from
+    /// before it was recorded.
+    pub fn new(
+        variant: Variant,
+        elapsed_secs: u64,
+        score: u32,
+        mistakes: u32,
+        hints_used: u32,
+        quiz: Option<Quiz>,
+        earlier: &DifficultyStats,
+    ) -> Self {
+        WinSummary {
+            difficulty: earlier.difficulty,
+            variant,
+            elapsed_secs,
+            score,
+            mistakes,
+            hints_used,
+            previous_best: earlier.best_secs,
+            previous_average: earlier.average_secs,
+            solves: earlier.solved + 1,
+            quiz,
+            quizzing: false,
+            selected: 0,
+        }
+    }
+
+    /// Whether the game beat every earlier solve of its difficulty.
+    pub fn is_best(&self) -> bool {
+        self.previous_best
+            .is_some_and(|best| self.elapsed_secs < best)
+    }
+
+    /// The entries the screen lists, the quiz only when there is one.
+    pub fn choices(&self) -> Vec<WinChoice> {
+        WinChoice::ALL
+            .into_iter()
+            .filter(|&choice| choice != WinChoice::Quiz || self.quiz.is_some())
+            .collect()
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let count = self.choices().len() as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(count) as usize;
+    }
+
+    pub fn choice(&self) -> WinChoice {
+        self.choices()[self.selected]
+    }
+}
@@ -0,0 +1,269 @@
+//! The grid itself, as tui_sudoku draws it and `SudokuWidget` draws it too:
+//! the lines between cells, the text of each cell and where a click lands.
+//! What a cell looks like is up to whoever draws it.
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Position, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::Widget;
+use sudoku_engine::grid::{BOX_SIZE, SIZE};
+use sudoku_engine::solver::Cell;
+
+/// Columns of one cell, each drawn as its own span.
+pub const CELL_WIDTH: u16 = 3;
+
+/// Lines of a cell in the tall grid, a 3x3 block of pencil marks.
+pub const NOTE_CELL_ROWS: u16 = 3;
+
+/// Columns and rows the grid takes with `cell_rows` lines per cell, not
+/// counting any border around it.
+pub const fn grid_size(cell_rows: u16) -> (u16, u16) {
+    let cells = SIZE as u16;
+    (
+        cells * CELL_WIDTH + cells - 1,
+        cells * cell_rows + cells - 1,
+    )
+}
+
+/// What one cell of the grid is drawn from. A cell's spans are rebuilt only
+/// when this changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellLook {
+    pub style: Style,
+    pub content: CellContent,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellContent {
+    /// A digit with the mark drawn after it, a space when there is none
+    Digit(u8, char),
+    /// Pencil marks as a bit set
+    Notes(u16),
+    /// Every digit the board still allows, as a bit set
+    Candidates(u16),
+    /// A dot in an empty cell
+    Dot,
+    /// A digit kept out of sight
+    Hidden,
+    Blank,
+}
+
+/// The grid's text as last drawn, along with the look of every cell in it.
+/// Each frame only the cells whose look changed are rebuilt, so an idle board
+/// is drawn without building any spans.
+#[derive(Clone, Debug, Default)]
+pub struct GridText {
+    cell_rows: u16,
+    border_style: Style,
+    note_colors: [Option<Color>; SIZE],
+    /// By screen cell, row by row; empty until the first draw
+    looks: Vec<Option<CellLook>>,
+    text: Text<'static>,
+}
+
+impl GridText {
+    /// Sets the shape and colors of the grid, starting over with blank cells
+    /// when they differ from the last frame's. `note_colors` gives each
+    /// digit's pencil marks a color over the cell's own.
+    pub fn reshape(
+        &mut self,
+        cell_rows: u16,
+        border_style: Style,
+        note_colors: [Option<Color>; SIZE],
+    ) {
+        if self.looks.is_empty()
+            || self.cell_rows != cell_rows
+            || self.border_style != border_style
+            || self.note_colors != note_colors
+        {
+            *self = GridText {
+                cell_rows,
+                border_style,
+                note_colors,
+                looks: vec![None; SIZE * SIZE],
+                text: skeleton(border_style, cell_rows),
+            };
+        }
+    }
+
+    pub fn cell_rows(&self) -> u16 {
+        self.cell_rows
+    }
+
+    /// Gives the cell at a screen position its look, rebuilding its spans
+    /// only if the look changed.
+    pub fn set(&mut self, (screen_r, screen_c): Cell, look: CellLook) {
+        // Nothing to fill in before the grid has a shape
+        let Some(cached) = self.looks.get_mut(screen_r * SIZE + screen_c) else {
+            return;
+        };
+        if *cached == Some(look) {
+            return;
+        }
+        // Each row of cells is followed by a border line, and each cell
+        // takes a span per column, after a separator but the first
+        let first_line = screen_r * (usize::from(self.cell_rows) + 1);
+        let notes = matches!(look.content, CellContent::Notes(_));
+        for (i, text) in cell_lines(look.content, self.cell_rows)
+            .into_iter()
+            .enumerate()
+        {
+            let spans = &mut self.text.lines[first_line + i].spans;
+            for (k, ch) in text.chars().enumerate() {
+                let color = note_digit(ch)
+                    .filter(|_| notes)
+                    .and_then(|digit| self.note_colors[digit as usize - 1]);
+                let style = color.map_or(look.style, |color| look.style.fg(color));
+                spans[screen_c * (usize::from(CELL_WIDTH) + 1) + k] =
+                    Span::styled(ch.to_string(), style);
+            }
+        }
+        *cached = Some(look);
+    }
+
+    /// The screen cell under a position, for a grid drawn into `area`.
+    /// The lines between cells and anything outside the grid give `None`.
+    pub fn cell_at(&self, area: Rect, position: Position) -> Option<Cell> {
+        let rows = self.cell_rows.max(1);
+        let placed = place(area, rows);
+        if !placed.contains(position) {
+            return None;
+        }
+        let col = position.x - placed.x;
+        let row = position.y - placed.y;
+        let on_cell = col % (CELL_WIDTH + 1) < CELL_WIDTH && row % (rows + 1) < rows;
+        on_cell.then(|| {
+            (
+                usize::from(row / (rows + 1)),
+                usize::from(col / (CELL_WIDTH + 1)),
+            )
+        })
+    }
+}
+
+/// Draws the grid centered in the area, clipped when the area is smaller.
+impl Widget for &GridText {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        (&self.text).render(place(area, self.cell_rows.max(1)), buf);
+    }
+}
+
+/// Where in `area` the grid goes: centered, and cut to fit.
+fn place(area: Rect, cell_rows: u16) -> Rect {
+    let (width, height) = grid_size(cell_rows);
+    Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    }
+}
+
+/// The grid's borders with blank cells, to be filled in cell by cell.
+fn skeleton(border_style: Style, cell_rows: u16) -> Text<'static> {
+    const H_BORDER: &str = "───┼───┼───┼───┼───┼───┼───┼───┼───";
+    const V_SEP: char = '│';
+    let mut lines = Vec::new();
+    for screen_r in 0..SIZE {
+        if screen_r > 0 {
+            lines.push(Line::from(H_BORDER).style(border_style));
+        }
+        for _ in 0..cell_rows {
+            let mut spans = Vec::new();
+            for screen_c in 0..SIZE {
+                if screen_c > 0 {
+                    spans.push(Span::styled(V_SEP.to_string(), border_style));
+                }
+                spans.extend((0..CELL_WIDTH).map(|_| Span::raw(" ")));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+    Text::from(lines)
+}
+
+/// The text of each line of a cell. Pencil marks and candidates fill a tall
+/// cell as a 3x3 block; anything else sits on its middle line.
+fn cell_lines(content: CellContent, cell_rows: u16) -> Vec<String> {
+    let single = match content {
+        CellContent::Digit(n, mark) => format!(" {n}{mark}"),
+        CellContent::Notes(mask) | CellContent::Candidates(mask) if cell_rows == NOTE_CELL_ROWS => {
+            return note_block(&digits_of(mask)).to_vec();
+        }
+        CellContent::Notes(mask) => compact_notes(&digits_of(mask)),
+        CellContent::Candidates(mask) => candidate_dots(mask),
+        CellContent::Dot => " · ".to_string(),
+        CellContent::Hidden => " ▪ ".to_string(),
+        CellContent::Blank => "   ".to_string(),
+    };
+    (0..cell_rows)
+        .map(|i| {
+            if i == cell_rows / 2 {
+                single.clone()
+            } else {
+                "   ".to_string()
+            }
+        })
+        .collect()
+}
+
+/// The digit of a pencil mark as drawn in a cell, plain or subscript.
+fn note_digit(ch: char) -> Option<u8> {
+    match ch {
+        '1'..='9' => Some(ch as u8 - b'0'),
+        '₁'..='₉' => Some((ch as u32 - '₀' as u32) as u8),
+        _ => None,
+    }
+}
+
+/// Pencil marks laid out in a 3x3 block, each digit in its own place
+fn note_block(notes: &[u8]) -> [String; BOX_SIZE] {
+    std::array::from_fn(|row| {
+        (1..=BOX_SIZE as u8)
+            .map(|col| {
+                let digit = row as u8 * BOX_SIZE as u8 + col;
+                if notes.contains(&digit) {
+                    char::from(b'0' + digit)
+                } else {
+                    ' '
+                }
+            })
+            .collect()
+    })
+}
+
+/// The digits of a bit set of pencil marks or candidates, in ascending order.
+fn digits_of(mask: u16) -> Vec<u8> {
+    (1..=SIZE as u8).filter(|&d| mask & (1 << d) != 0).collect()
+}
+
+/// Candidates squeezed into a three-column cell as a 3x3 grid of braille
+/// dots, laid out like the tall grid's blocks: each column of the cell holds
+/// the dots of one column of the block, top to bottom.
+fn candidate_dots(mask: u16) -> String {
+    // Braille dots 1, 2 and 3 run down the left of the character
+    const DOTS: [u32; BOX_SIZE] = [0x01, 0x02, 0x04];
+    (0..BOX_SIZE)
+        .map(|col| {
+            let dots = (0..BOX_SIZE)
+                .filter(|&row| mask & 1 << (row * BOX_SIZE + col + 1) != 0)
+                .fold(0, |bits, row| bits | DOTS[row]);
+            char::from_u32(0x2800 + dots).unwrap_or(' ')
+        })
+        .collect()
+}
+
+/// Pencil marks squeezed into a three-column cell as subscript digits,
+/// with an ellipsis when more than three are noted
+fn compact_notes(notes: &[u8]) -> String {
+    const SUBSCRIPTS: [char; SIZE] = ['₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉'];
+    let sub = |d: &u8| SUBSCRIPTS[*d as usize - 1];
+    match notes {
+        [] => "   ".to_string(),
+        [a] => format!(" {} ", sub(a)),
+        [a, b] => format!("{}{} ", sub(a), sub(b)),
+        [a, b, c] => format!("{}{}{}", sub(a), sub(b), sub(c)),
+        [a, b, ..] => format!("{}{}…", sub(a), sub(b)),
+    }
+}
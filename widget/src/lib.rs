@@ -0,0 +1,366 @@
+//! A playable Sudoku pane for other ratatui apps, such as a dashboard with a
+//! puzzle in one corner. `SudokuWidget` draws a board into whatever area it
+//! is given, and `SudokuState` holds the position and takes the keys and
+//! clicks the host passes on. The rules come from `sudoku_engine`; puzzles
+//! are supplied by the host, as generation, hints and the rest of the game
+//! stay in tui_sudoku.
+//!
+//! The grid is drawn by `GridText`, which tui_sudoku draws its own board
+//! with too, so both lay out cells and map clicks alike. Keys are the game's
+//! default bindings; its keymap, hints and checks are not part of the pane.
+
+mod grid;
+
+pub use grid::{CELL_WIDTH, CellContent, CellLook, GridText, NOTE_CELL_ROWS, grid_size};
+
+use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::layout::{Position, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, StatefulWidget, Widget};
+use sudoku_engine::grid::SIZE;
+use sudoku_engine::solver::{Board, Cell};
+
+pub use sudoku_engine::grid::Variant;
+
+/// Columns and rows the board takes inside its block: three columns per
+/// cell and a line between cells either way.
+pub const BOARD_WIDTH: u16 = grid_size(1).0;
+pub const BOARD_HEIGHT: u16 = grid_size(1).1;
+
+/// The digit whose key types `c` with Shift held on a US layout, e.g. `!` for 1.
+pub fn shifted_digit(c: char) -> Option<u8> {
+    "!@#$%^&*(".find(c).map(|i| i as u8 + 1)
+}
+
+/// What an event did to the board, so the host knows whether to handle it
+/// itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Response {
+    /// Not meant for the board, such as a key it has no use for
+    Ignored,
+    Consumed,
+    /// The event completed the board with no digit repeated
+    Solved,
+}
+
+/// A game on the board: the clues, the player's entries and pencil marks,
+/// and the cursor. Kept by the host between frames.
+#[derive(Clone, Debug)]
+pub struct SudokuState {
+    variant: Variant,
+    givens: Board,
+    entries: Board,
+    /// Pencil marks of each cell, bit `d` for digit `d`
+    notes: [[u16; SIZE]; SIZE],
+    cursor: Cell,
+    note_mode: bool,
+    grid: GridText,
+    /// The area the grid was last drawn into, for mapping clicks
+    drawn: Option<Rect>,
+}
+
+impl SudokuState {
+    /// A game of the clues in `givens`, 0 for empty cells.
+    pub fn new(givens: Board, variant: Variant) -> Self {
+        SudokuState {
+            variant,
+            givens,
+            entries: [[0; SIZE]; SIZE],
+            notes: [[0; SIZE]; SIZE],
+            cursor: (0, 0),
+            note_mode: false,
+            grid: GridText::default(),
+            drawn: None,
+        }
+    }
+
+    /// A game of a puzzle in the usual one-line notation: 81 cells in
+    /// reading order, each a digit 1-9 or `0`/`.` for a blank.
+    pub fn from_line(line: &str, variant: Variant) -> Result<Self, String> {
+        let line = line.trim();
+        let length = line.chars().count();
+        if length != SIZE * SIZE {
+            return Err(format!("Expected {} cells, found {length}", SIZE * SIZE));
+        }
+        let mut givens = [[0; SIZE]; SIZE];
+        for (i, ch) in line.chars().enumerate() {
+            givens[i / SIZE][i % SIZE] = match ch {
+                '1'..='9' => ch as u8 - b'0',
+                '0' | '.' => 0,
+                other => return Err(format!("Not a cell: '{other}'")),
+            };
+        }
+        Ok(Self::new(givens, variant))
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// The clues and entries together, 0 for empty cells.
+    pub fn board(&self) -> Board {
+        let mut board = self.givens;
+        for (r, row) in board.iter_mut().enumerate() {
+            for (c, digit) in row.iter_mut().enumerate() {
+                if *digit == 0 {
+                    *digit = self.entries[r][c];
+                }
+            }
+        }
+        board
+    }
+
+    pub fn is_given(&self, (r, c): Cell) -> bool {
+        self.givens[r][c] != 0
+    }
+
+    pub fn cursor(&self) -> Cell {
+        self.cursor
+    }
+
+    pub fn set_cursor(&mut self, (r, c): Cell) {
+        self.cursor = (r.min(SIZE - 1), c.min(SIZE - 1));
+    }
+
+    /// Whether the digit keys toggle pencil marks instead of entering digits.
+    pub fn note_mode(&self) -> bool {
+        self.note_mode
+    }
+
+    /// Whether the digit in a cell repeats in a cell it sees.
+    pub fn clashes(&self, cell: Cell) -> bool {
+        let board = self.board();
+        let digit = board[cell.0][cell.1];
+        digit != 0
+            && (0..SIZE * SIZE)
+                .map(|i| (i / SIZE, i % SIZE))
+                .any(|other| board[other.0][other.1] == digit && self.variant.sees(cell, other))
+    }
+
+    /// Whether every cell is filled and no digit repeats where the rules
+    /// forbid it. Clues with a single solution have only it as such a board.
+    pub fn is_solved(&self) -> bool {
+        let board = self.board();
+        (0..SIZE * SIZE)
+            .map(|i| (i / SIZE, i % SIZE))
+            .all(|cell| board[cell.0][cell.1] != 0 && !self.clashes(cell))
+    }
+
+    /// Enters a digit in an open cell, clearing its pencil marks; 0 empties it.
+    pub fn set_digit(&mut self, (r, c): Cell, digit: u8) -> Response {
+        if self.is_given((r, c)) || self.is_solved() || usize::from(digit) > SIZE {
+            return Response::Ignored;
+        }
+        self.entries[r][c] = digit;
+        self.notes[r][c] = 0;
+        if self.is_solved() {
+            Response::Solved
+        } else {
+            Response::Consumed
+        }
+    }
+
+    /// Adds or removes a pencil mark in an empty open cell.
+    pub fn toggle_note(&mut self, (r, c): Cell, digit: u8) -> Response {
+        if self.board()[r][c] != 0 || !(1..=SIZE as u8).contains(&digit) {
+            return Response::Ignored;
+        }
+        self.notes[r][c] ^= 1 << digit;
+        Response::Consumed
+    }
+
+    /// Takes any terminal event, returning `Ignored` for those the board has
+    /// no use for so the host can act on them.
+    pub fn handle_event(&mut self, event: &Event) -> Response {
+        match event {
+            Event::Key(key) => self.handle_key(*key),
+            Event::Mouse(mouse) => self.handle_mouse(*mouse),
+            _ => Response::Ignored,
+        }
+    }
+
+    /// The game's default keys: arrow keys or `h`/`j`/`k`/`l` move the
+    /// cursor, digits fill the cell under it, Shift+digits toggle a pencil
+    /// mark, `0`, Backspace or Delete clear it, and `p` switches the digits
+    /// to pencil marks. Key releases are ignored.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Response {
+        if key.kind == KeyEventKind::Release
+            || key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return Response::Ignored;
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(0, 1),
+            KeyCode::Char(c @ '1'..='9') if self.note_mode => {
+                self.toggle_note(self.cursor, c as u8 - b'0')
+            }
+            KeyCode::Char(c @ '1'..='9') => self.set_digit(self.cursor, c as u8 - b'0'),
+            KeyCode::Char(c) if shifted_digit(c).is_some() => {
+                self.toggle_note(self.cursor, shifted_digit(c).unwrap_or(0))
+            }
+            KeyCode::Char('0') | KeyCode::Backspace | KeyCode::Delete => {
+                self.set_digit(self.cursor, 0)
+            }
+            KeyCode::Char('p') => {
+                self.note_mode = !self.note_mode;
+                Response::Consumed
+            }
+            _ => Response::Ignored,
+        }
+    }
+
+    /// A left click on a cell moves the cursor there. Clicks elsewhere, or
+    /// before the board has been drawn, are ignored.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Response {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return Response::Ignored;
+        }
+        let position = Position::new(mouse.column, mouse.row);
+        match self
+            .drawn
+            .and_then(|area| self.grid.cell_at(area, position))
+        {
+            Some(cell) => {
+                self.cursor = cell;
+                Response::Consumed
+            }
+            None => Response::Ignored,
+        }
+    }
+
+    fn move_cursor(&mut self, dr: isize, dc: isize) -> Response {
+        let step = |v: usize, d: isize| (v as isize + d).rem_euclid(SIZE as isize) as usize;
+        self.cursor = (step(self.cursor.0, dr), step(self.cursor.1, dc));
+        Response::Consumed
+    }
+}
+
+/// Draws a `SudokuState` into any area, centered in it and clipped when the
+/// area is smaller than the board. Wrap it in a block for a border and title.
+#[derive(Clone, Debug)]
+pub struct SudokuWidget<'a> {
+    block: Option<Block<'a>>,
+    given_style: Style,
+    entry_style: Style,
+    note_style: Style,
+    clash_style: Style,
+    cursor_style: Style,
+    line_style: Style,
+}
+
+impl Default for SudokuWidget<'_> {
+    fn default() -> Self {
+        SudokuWidget {
+            block: None,
+            given_style: Style::default().add_modifier(Modifier::BOLD),
+            entry_style: Style::default().fg(Color::Cyan),
+            note_style: Style::default().fg(Color::DarkGray),
+            clash_style: Style::default().fg(Color::Red),
+            cursor_style: Style::default().add_modifier(Modifier::REVERSED),
+            line_style: Style::default().fg(Color::DarkGray),
+        }
+    }
+}
+
+impl<'a> SudokuWidget<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    pub fn given_style(mut self, style: Style) -> Self {
+        self.given_style = style;
+        self
+    }
+
+    pub fn entry_style(mut self, style: Style) -> Self {
+        self.entry_style = style;
+        self
+    }
+
+    pub fn note_style(mut self, style: Style) -> Self {
+        self.note_style = style;
+        self
+    }
+
+    /// Style of digits repeated in a cell they see, laid over the others.
+    pub fn clash_style(mut self, style: Style) -> Self {
+        self.clash_style = style;
+        self
+    }
+
+    /// Style laid over the cell under the cursor.
+    pub fn cursor_style(mut self, style: Style) -> Self {
+        self.cursor_style = style;
+        self
+    }
+
+    /// Style of the lines between cells.
+    pub fn line_style(mut self, style: Style) -> Self {
+        self.line_style = style;
+        self
+    }
+
+    /// How a cell is drawn.
+    fn look(&self, state: &SudokuState, board: &Board, (r, c): Cell) -> CellLook {
+        let digit = board[r][c];
+        let (content, mut style) = if digit == 0 {
+            let notes = state.notes[r][c];
+            let content = if notes == 0 {
+                CellContent::Blank
+            } else {
+                CellContent::Notes(notes)
+            };
+            (content, self.note_style)
+        } else if state.is_given((r, c)) {
+            (CellContent::Digit(digit, ' '), self.given_style)
+        } else {
+            (CellContent::Digit(digit, ' '), self.entry_style)
+        };
+        if state.clashes((r, c)) {
+            style = style.patch(self.clash_style);
+        }
+        if state.cursor == (r, c) {
+            style = style.patch(self.cursor_style);
+        }
+        CellLook { style, content }
+    }
+}
+
+impl StatefulWidget for SudokuWidget<'_> {
+    type State = SudokuState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let inner = match &self.block {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.clone().render(area, buf);
+                inner
+            }
+            None => area,
+        };
+        let board = state.board();
+        let mut grid = std::mem::take(&mut state.grid);
+        grid.reshape(1, self.line_style, [None; SIZE]);
+        for r in 0..SIZE {
+            for c in 0..SIZE {
+                grid.set((r, c), self.look(state, &board, (r, c)));
+            }
+        }
+        (&grid).render(inner, buf);
+        state.grid = grid;
+        state.drawn = (!inner.is_empty()).then_some(inner);
+    }
+}